@@ -1,82 +1,190 @@
 use aes::cipher::generic_array::GenericArray;
+use aes::cipher::generic_array::typenum::U16;
 use aes::cipher::{BlockCipher, NewBlockCipher};
-use aes::Aes128;
+use aes::{Aes128, Aes192, Aes256};
 use std::collections::HashSet;
+use thiserror::Error;
 use crate::xor::xor_bytes;
+use crate::gf128::GF128;
+use crate::mac::constant_time_eq;
+
+///Custom AES errors
+#[derive(Error, Debug, PartialEq)]
+pub enum AesError {
+    ///Error for a key which is not 16, 24 or 32 bytes long
+    #[error("Illegal key length {0} passed as an AES key!")]
+    IllegalKeyLength(usize),
+    ///Error for a CBC IV which is not 16 bytes long
+    #[error("Illegal IV length {0} passed as an AES IV!")]
+    IllegalIVLength(usize),
+    ///Error for an empty GCM nonce
+    #[error("Illegal nonce length {0} passed as a GCM nonce!")]
+    IllegalNonceLength(usize),
+    ///Error for a GCM tag length which is not from 1 to 16 bytes, or a tag which is not the expected length
+    #[error("Illegal tag length {0} passed as a GCM tag!")]
+    IllegalTagLength(usize),
+    ///Error for plaintext which is not a whole number of blocks
+    #[error("Partial block of length {0} passed for AES encryption!")]
+    PartialEncryptionBlock(usize),
+    ///Error for ciphertext which is not a whole number of blocks
+    #[error("Partial block of length {0} passed for AES decryption!")]
+    PartialDecryptionBlock(usize),
+    ///Error for ciphertext passed to ECB detection which is not a whole number of blocks
+    #[error("Partial block of length {0} passed for AES analysis!")]
+    PartialAnalysisBlock(usize),
+    ///Error for a block size of zero passed to ECB detection
+    #[error("Illegal block size {0} passed for AES analysis!")]
+    IllegalBlockSize(usize),
+}
 
-///Encrypts plaintext using AES-ECB and the given 16-byte key.
-///Will panic if key length is not 16 bytes or plaintext length is not a multiple of 16 bytes.
-pub fn encrypt_ecb(plaintext: &Vec<u8>, key: &Vec<u8>) -> Vec<u8> {
-    if key.len() != 16 {
-        panic!("Illegal key length {} passed as an AES key!", key.len());
+///Legal AES key lengths in bytes, for AES-128, AES-192 and AES-256 respectively
+static KEY_LENGTHS: [usize; 3] = [16, 24, 32];
+
+///AES block cipher with the variant selected from the key length
+enum AesCipher {
+    Aes128(Aes128),
+    Aes192(Aes192),
+    Aes256(Aes256)
+}
+
+impl AesCipher {
+    ///Creates the AES variant matching the key length.
+    ///Returns an error if key length is not 16, 24 or 32 bytes.
+    fn new(key: &Vec<u8>) -> Result<AesCipher, AesError> {
+        return match key.len() {
+            16 => Ok(AesCipher::Aes128(Aes128::new(GenericArray::from_slice(&key)))),
+            24 => Ok(AesCipher::Aes192(Aes192::new(GenericArray::from_slice(&key)))),
+            32 => Ok(AesCipher::Aes256(Aes256::new(GenericArray::from_slice(&key)))),
+            _ => Err(AesError::IllegalKeyLength(key.len()))
+        };
+    }
+
+    fn encrypt_block(&self, block: &mut GenericArray<u8, U16>) {
+        match self {
+            AesCipher::Aes128(cipher) => cipher.encrypt_block(block),
+            AesCipher::Aes192(cipher) => cipher.encrypt_block(block),
+            AesCipher::Aes256(cipher) => cipher.encrypt_block(block)
+        }
+    }
+
+    fn decrypt_block(&self, block: &mut GenericArray<u8, U16>) {
+        match self {
+            AesCipher::Aes128(cipher) => cipher.decrypt_block(block),
+            AesCipher::Aes192(cipher) => cipher.decrypt_block(block),
+            AesCipher::Aes256(cipher) => cipher.decrypt_block(block)
+        }
+    }
+}
+
+///Returns an error if key length is not 16, 24 or 32 bytes
+fn validate_key(key: &Vec<u8>) -> Result<(), AesError> {
+    if !KEY_LENGTHS.contains(&key.len()) {
+        return Err(AesError::IllegalKeyLength(key.len()));
     }
+    return Ok(());
+}
+
+///Encrypts plaintext using AES-ECB and the given 16, 24 or 32-byte key.
+///Will panic if key length is not 16, 24 or 32 bytes or plaintext length is not a multiple of 16 bytes.
+pub fn encrypt_ecb(plaintext: &Vec<u8>, key: &Vec<u8>) -> Vec<u8> {
+    return try_encrypt_ecb(plaintext, key).unwrap_or_else(|e| panic!("{}", e));
+}
+
+///Encrypts plaintext using AES-ECB and the given 16, 24 or 32-byte key.
+///Returns an error if key length is not 16, 24 or 32 bytes or plaintext length is not a multiple of 16 bytes.
+pub fn try_encrypt_ecb(plaintext: &Vec<u8>, key: &Vec<u8>) -> Result<Vec<u8>, AesError> {
+    let cipher = AesCipher::new(&key)?;
     if plaintext.len() % 16 != 0 {
-        panic!("Partial block of length {} passed for AES encryption!", plaintext.len() % 16);
+        return Err(AesError::PartialEncryptionBlock(plaintext.len() % 16));
     }
 
     let mut ciphertext: Vec<u8> = vec![];
-    let key_array: GenericArray<u8, _> = GenericArray::clone_from_slice(&key);
-    let cipher = Aes128::new(&key_array);
-
     for i in 0..plaintext.len() / 16 {
         let mut block = GenericArray::clone_from_slice(&plaintext[16*i..16*(i+1)]);
         cipher.encrypt_block(&mut block);
         ciphertext.append(&mut block.to_vec());
     }
 
-    return ciphertext;
+    return Ok(ciphertext);
 }
 
-///Decrypts ciphertext using AES-ECB and the given 16-byte key.
-///Will panic if key length is not 16 bytes or ciphertext length is not a multiple of 16 bytes.
+///Decrypts ciphertext using AES-ECB and the given 16, 24 or 32-byte key.
+///Will panic if key length is not 16, 24 or 32 bytes or ciphertext length is not a multiple of 16 bytes.
 pub fn decrypt_ecb(ciphertext: &Vec<u8>, key: &Vec<u8>) -> Vec<u8> {
-    if key.len() != 16 {
-        panic!("Illegal key length {} passed as an AES key!", key.len());
-    }
+    return try_decrypt_ecb(ciphertext, key).unwrap_or_else(|e| panic!("{}", e));
+}
+
+///Decrypts ciphertext using AES-ECB and the given 16, 24 or 32-byte key.
+///Returns an error if key length is not 16, 24 or 32 bytes or ciphertext length is not a multiple of 16 bytes.
+pub fn try_decrypt_ecb(ciphertext: &Vec<u8>, key: &Vec<u8>) -> Result<Vec<u8>, AesError> {
+    let cipher = AesCipher::new(&key)?;
     if ciphertext.len() % 16 != 0 {
-        panic!("Partial block of length {} passed for AES decryption!", ciphertext.len() % 16);
+        return Err(AesError::PartialDecryptionBlock(ciphertext.len() % 16));
     }
 
     let mut plaintext: Vec<u8> = vec![];
-    let key_array: GenericArray<u8, _> = GenericArray::clone_from_slice(&key);
-    let cipher = Aes128::new(&key_array);
-
     for i in 0..ciphertext.len() / 16 {
         let mut block = GenericArray::clone_from_slice(&ciphertext[16*i..16*(i+1)]);
         cipher.decrypt_block(&mut block);
         plaintext.append(&mut block.to_vec());
     }
 
-    return plaintext;
+    return Ok(plaintext);
 }
 
 ///Detects use of ECB mode by searching for repeated blocks.
 ///Will panic if ciphertext length is not a multiple of 16 bytes.
 pub fn detect_ecb(ciphertext: &Vec<u8>) -> bool {
-    if ciphertext.len() % 16 != 0 {
-        panic!("Partial block of length {} passed for AES analysis!", ciphertext.len() % 16);
+    return detect_ecb_with_block_size(ciphertext, 16);
+}
+
+///Detects use of ECB mode by searching for repeated blocks.
+///Returns an error if ciphertext length is not a multiple of 16 bytes.
+pub fn try_detect_ecb(ciphertext: &Vec<u8>) -> Result<bool, AesError> {
+    return try_detect_ecb_with_block_size(ciphertext, 16);
+}
+
+///Detects use of ECB mode by searching for repeated blocks of the given size.
+///Will panic if the block size is zero or ciphertext length is not a multiple of it.
+pub fn detect_ecb_with_block_size(ciphertext: &Vec<u8>, block_size: usize) -> bool {
+    return try_detect_ecb_with_block_size(ciphertext, block_size).unwrap_or_else(|e| panic!("{}", e));
+}
+
+///Detects use of ECB mode by searching for repeated blocks of the given size.
+///Returns an error if the block size is zero or ciphertext length is not a multiple of it.
+pub fn try_detect_ecb_with_block_size(ciphertext: &Vec<u8>, block_size: usize) -> Result<bool, AesError> {
+    if block_size == 0 {
+        return Err(AesError::IllegalBlockSize(block_size));
+    }
+    if ciphertext.len() % block_size != 0 {
+        return Err(AesError::PartialAnalysisBlock(ciphertext.len() % block_size));
     }
 
     let mut unique: HashSet<Vec<u8>> = HashSet::new();
-    for i in 0..ciphertext.len() / 16 {
-        unique.insert(ciphertext[16*i..16*(i+1)].to_vec());
+    for i in 0..ciphertext.len() / block_size {
+        unique.insert(ciphertext[block_size*i..block_size*(i+1)].to_vec());
     }
 
-    return unique.len() < (ciphertext.len() / 16);
+    return Ok(unique.len() < (ciphertext.len() / block_size));
 }
 
 ///Encrypts plaintext using AES-CBC mode and the given key and IV.
-///Will panic if key length is not 16 bytes, IV length is not 16 bytes,
+///Will panic if key length is not 16, 24 or 32 bytes, IV length is not 16 bytes,
 ///or plaintext length is not a multiple of 16 bytes.
 pub fn encrypt_cbc(plaintext: &Vec<u8>, key: &Vec<u8>, iv: &Vec<u8>) -> Vec<u8> {
-    if key.len() != 16 {
-        panic!("Illegal key length {} passed as an AES key!", key.len());
-    }
+    return try_encrypt_cbc(plaintext, key, iv).unwrap_or_else(|e| panic!("{}", e));
+}
+
+///Encrypts plaintext using AES-CBC mode and the given key and IV.
+///Returns an error if key length is not 16, 24 or 32 bytes, IV length is not 16 bytes,
+///or plaintext length is not a multiple of 16 bytes.
+pub fn try_encrypt_cbc(plaintext: &Vec<u8>, key: &Vec<u8>, iv: &Vec<u8>) -> Result<Vec<u8>, AesError> {
+    validate_key(&key)?;
     if iv.len() != 16 {
-        panic!("Illegal IV length {} passed as an AES IV!", iv.len());
+        return Err(AesError::IllegalIVLength(iv.len()));
     }
     if plaintext.len() % 16 != 0 {
-        panic!("Partial block of length {} passed for AES encryption!", plaintext.len() % 16);
+        return Err(AesError::PartialEncryptionBlock(plaintext.len() % 16));
     }
 
     let mut ciphertext: Vec<u8> = vec![];
@@ -84,47 +192,56 @@ pub fn encrypt_cbc(plaintext: &Vec<u8>, key: &Vec<u8>, iv: &Vec<u8>) -> Vec<u8>
 
     for i in 0..plaintext.len() / 16 {
         let to_encrypt = xor_bytes(&last_block, &plaintext[16*i..16*(i+1)].to_vec());
-        let mut encrypted = encrypt_ecb(&to_encrypt, &key);
+        let mut encrypted = try_encrypt_ecb(&to_encrypt, &key)?;
         last_block = encrypted.clone();
         ciphertext.append(&mut encrypted);
     }
 
-    return ciphertext;
+    return Ok(ciphertext);
 }
 
 ///Decrypts ciphertext using AES-CBC mode and the given key and IV.
-///Will panic if key length is not 16 bytes, IV length is not 16 bytes,
+///Will panic if key length is not 16, 24 or 32 bytes, IV length is not 16 bytes,
 ///or ciphertext length is not a multiple of 16 bytes.
 pub fn decrypt_cbc(ciphertext: &Vec<u8>, key: &Vec<u8>, iv: &Vec<u8>) -> Vec<u8> {
-    if key.len() != 16 {
-        panic!("Illegal key length {} passed as an AES key!", key.len());
-    }
+    return try_decrypt_cbc(ciphertext, key, iv).unwrap_or_else(|e| panic!("{}", e));
+}
+
+///Decrypts ciphertext using AES-CBC mode and the given key and IV.
+///Returns an error if key length is not 16, 24 or 32 bytes, IV length is not 16 bytes,
+///or ciphertext length is not a multiple of 16 bytes.
+pub fn try_decrypt_cbc(ciphertext: &Vec<u8>, key: &Vec<u8>, iv: &Vec<u8>) -> Result<Vec<u8>, AesError> {
+    validate_key(&key)?;
     if iv.len() != 16 {
-        panic!("Illegal IV length {} passed as an AES IV!", iv.len());
+        return Err(AesError::IllegalIVLength(iv.len()));
     }
     if ciphertext.len() % 16 != 0 {
-        panic!("Partial block of length {} passed for AES decryption!", ciphertext.len() % 16);
+        return Err(AesError::PartialDecryptionBlock(ciphertext.len() % 16));
     }
 
     let mut plaintext: Vec<u8> = vec![];
     let mut last_block = iv.clone();
 
     for i in 0..ciphertext.len() / 16 {
-        let decrypted = decrypt_ecb(&ciphertext[16*i..16*(i+1)].to_vec(), &key);
+        let decrypted = try_decrypt_ecb(&ciphertext[16*i..16*(i+1)].to_vec(), &key)?;
         let mut plaintext_block = xor_bytes(&last_block, &decrypted);
         last_block = ciphertext[16*i..16*(i+1)].to_vec();
         plaintext.append(&mut plaintext_block);
     }
 
-    return plaintext;
+    return Ok(plaintext);
 }
 
 ///Encrypts plaintext using AES-CTR mode and the given key and nonce.
-///Will panic if key length is not 16 bytes.
+///Will panic if key length is not 16, 24 or 32 bytes.
 pub fn encrypt_ctr(plaintext: &Vec<u8>, key: &Vec<u8>, nonce: u64) -> Vec<u8> {
-    if key.len() != 16 {
-        panic!("Illegal key length {} passed as an AES key!", key.len());
-    }
+    return try_encrypt_ctr(plaintext, key, nonce).unwrap_or_else(|e| panic!("{}", e));
+}
+
+///Encrypts plaintext using AES-CTR mode and the given key and nonce.
+///Returns an error if key length is not 16, 24 or 32 bytes.
+pub fn try_encrypt_ctr(plaintext: &Vec<u8>, key: &Vec<u8>, nonce: u64) -> Result<Vec<u8>, AesError> {
+    validate_key(&key)?;
 
     let mut ciphertext = vec![];
     let mut ctr_block = vec![];
@@ -132,21 +249,161 @@ pub fn encrypt_ctr(plaintext: &Vec<u8>, key: &Vec<u8>, nonce: u64) -> Vec<u8> {
 
     for i in 0..plaintext.len() {
         if i % 16 == 0 {
-            ctr_block = encrypt_ecb(&ctr.to_le_bytes().to_vec(), &key);
+            ctr_block = try_encrypt_ecb(&ctr.to_le_bytes().to_vec(), &key)?;
             ctr += 1 << 64;
         }
         ciphertext.push(plaintext[i] ^ ctr_block[i % 16]);
     }
 
-    return ciphertext;
+    return Ok(ciphertext);
 }
 
 ///Decrypts ciphertext using AES-CTR mode and the given key and nonce.
-///Will panic if key length is not 16 bytes.
+///Will panic if key length is not 16, 24 or 32 bytes.
 pub fn decrypt_ctr(ciphertext: &Vec<u8>, key: &Vec<u8>, nonce: u64) -> Vec<u8> {
     return encrypt_ctr(&ciphertext, &key, nonce);
 }
 
+///Decrypts ciphertext using AES-CTR mode and the given key and nonce.
+///Returns an error if key length is not 16, 24 or 32 bytes.
+pub fn try_decrypt_ctr(ciphertext: &Vec<u8>, key: &Vec<u8>, nonce: u64) -> Result<Vec<u8>, AesError> {
+    return try_encrypt_ctr(&ciphertext, &key, nonce);
+}
+
+///Computes GHASH over the additional data and ciphertext with the given authentication key H.
+///Both inputs are zero-padded to whole blocks, followed by a block holding their bit lengths.
+pub fn ghash(h: &GF128, aad: &Vec<u8>, ciphertext: &Vec<u8>) -> GF128 {
+    let mut y = GF128::zero();
+
+    for data in [aad, ciphertext].iter() {
+        for block in data.chunks(16) {
+            y = (y + GF128::from_bytes(block)) * *h;
+        }
+    }
+
+    let mut length_block = ((aad.len() * 8) as u64).to_be_bytes().to_vec();
+    length_block.append(&mut ((ciphertext.len() * 8) as u64).to_be_bytes().to_vec());
+    return (y + GF128::from_bytes(&length_block)) * *h;
+}
+
+///Derives the GHASH authentication key H = E(K, 0^128)
+pub fn gcm_auth_key(key: &Vec<u8>) -> GF128 {
+    return GF128::from_bytes(&encrypt_ecb(&vec![0; 16], &key));
+}
+
+///Computes the pre-counter block J0 for the given nonce.
+///96-bit nonces are used directly, while other lengths are compressed with GHASH.
+fn gcm_initial_counter(h: &GF128, nonce: &Vec<u8>) -> Result<Vec<u8>, AesError> {
+    if nonce.len() == 0 {
+        return Err(AesError::IllegalNonceLength(0));
+    }
+
+    return Ok(if nonce.len() == 12 {
+        let mut counter = nonce.clone();
+        counter.append(&mut vec![0, 0, 0, 1]);
+        counter
+    }
+    else {
+        ghash(&h, &vec![], &nonce).to_bytes()
+    });
+}
+
+///Increments the last 32 bits of a counter block, wrapping on overflow
+fn gcm_increment(counter: &mut Vec<u8>) {
+    let low = u32::from_be_bytes([counter[12], counter[13], counter[14], counter[15]]);
+    counter.splice(12..16, low.wrapping_add(1).to_be_bytes().iter().cloned());
+}
+
+///Applies the GCM counter-mode keystream starting from the given counter block
+fn gctr(input: &Vec<u8>, key: &Vec<u8>, initial_counter: &Vec<u8>) -> Vec<u8> {
+    let mut output = vec![];
+    let mut counter = initial_counter.clone();
+    let mut keystream = vec![];
+
+    for i in 0..input.len() {
+        if i % 16 == 0 {
+            keystream = encrypt_ecb(&counter, &key);
+            gcm_increment(&mut counter);
+        }
+        output.push(input[i] ^ keystream[i % 16]);
+    }
+
+    return output;
+}
+
+///Computes the full 16-byte GCM tag for the given ciphertext and additional data
+fn gcm_tag(key: &Vec<u8>, h: &GF128, initial_counter: &Vec<u8>, aad: &Vec<u8>, ciphertext: &Vec<u8>) -> Vec<u8> {
+    let mask = GF128::from_bytes(&encrypt_ecb(&initial_counter, &key));
+    return (ghash(&h, &aad, &ciphertext) + mask).to_bytes();
+}
+
+///Encrypts plaintext using AES-GCM with the given key, nonce and additional authenticated data.
+///Returns (ciphertext, tag). Will panic if key length is not 16, 24 or 32 bytes or the nonce is empty.
+pub fn encrypt_gcm(plaintext: &Vec<u8>, key: &Vec<u8>, nonce: &Vec<u8>, aad: &Vec<u8>) -> (Vec<u8>, Vec<u8>) {
+    return try_encrypt_gcm(plaintext, key, nonce, aad).unwrap_or_else(|e| panic!("{}", e));
+}
+
+///Encrypts plaintext using AES-GCM with the given key, nonce and additional authenticated data.
+///Returns (ciphertext, tag), or an error if key length is not 16, 24 or 32 bytes or the nonce is empty.
+pub fn try_encrypt_gcm(plaintext: &Vec<u8>, key: &Vec<u8>, nonce: &Vec<u8>, aad: &Vec<u8>) -> Result<(Vec<u8>, Vec<u8>), AesError> {
+    validate_key(&key)?;
+    let h = gcm_auth_key(&key);
+    let mut counter = gcm_initial_counter(&h, &nonce)?;
+    let tag_counter = counter.clone();
+
+    gcm_increment(&mut counter);
+    let ciphertext = gctr(&plaintext, &key, &counter);
+    let tag = gcm_tag(&key, &h, &tag_counter, &aad, &ciphertext);
+
+    return Ok((ciphertext, tag));
+}
+
+///Decrypts ciphertext using AES-GCM with the given key, nonce and additional authenticated data.
+///Returns None if the full 16-byte tag does not authenticate the ciphertext and additional data.
+///Will panic if key length is not 16, 24 or 32 bytes, the nonce is empty, or the tag is not 16 bytes.
+pub fn decrypt_gcm(ciphertext: &Vec<u8>, key: &Vec<u8>, nonce: &Vec<u8>, aad: &Vec<u8>, tag: &Vec<u8>) -> Option<Vec<u8>> {
+    return try_decrypt_gcm(ciphertext, key, nonce, aad, tag).unwrap_or_else(|e| panic!("{}", e));
+}
+
+///Decrypts ciphertext using AES-GCM with the given key, nonce and additional authenticated data.
+///Returns Ok(None) if the full 16-byte tag does not authenticate the ciphertext and additional data,
+///or an error if key length is not 16, 24 or 32 bytes, the nonce is empty, or the tag is not 16 bytes.
+pub fn try_decrypt_gcm(ciphertext: &Vec<u8>, key: &Vec<u8>, nonce: &Vec<u8>, aad: &Vec<u8>, tag: &Vec<u8>) -> Result<Option<Vec<u8>>, AesError> {
+    return try_decrypt_gcm_truncated(ciphertext, key, nonce, aad, tag, 16);
+}
+
+///Decrypts ciphertext using AES-GCM, accepting tags truncated to the given length (in bytes).
+///Returns None if the tag is not the first tag_length bytes of the full tag for the ciphertext and additional data.
+///Will panic if key length is not 16, 24 or 32 bytes, the nonce is empty, tag_length is not from 1 to 16,
+///or the tag is not tag_length bytes.
+pub fn decrypt_gcm_truncated(ciphertext: &Vec<u8>, key: &Vec<u8>, nonce: &Vec<u8>, aad: &Vec<u8>, tag: &Vec<u8>, tag_length: usize) -> Option<Vec<u8>> {
+    return try_decrypt_gcm_truncated(ciphertext, key, nonce, aad, tag, tag_length).unwrap_or_else(|e| panic!("{}", e));
+}
+
+///Decrypts ciphertext using AES-GCM, accepting tags truncated to the given length (in bytes).
+///Returns Ok(None) if the tag is not the first tag_length bytes of the full tag for the ciphertext and additional data,
+///or an error if key length is not 16, 24 or 32 bytes, the nonce is empty, tag_length is not from 1 to 16,
+///or the tag is not tag_length bytes.
+pub fn try_decrypt_gcm_truncated(ciphertext: &Vec<u8>, key: &Vec<u8>, nonce: &Vec<u8>, aad: &Vec<u8>, tag: &Vec<u8>, tag_length: usize) -> Result<Option<Vec<u8>>, AesError> {
+    if tag_length == 0 || tag_length > 16 {
+        return Err(AesError::IllegalTagLength(tag_length));
+    }
+    if tag.len() != tag_length {
+        return Err(AesError::IllegalTagLength(tag.len()));
+    }
+    validate_key(&key)?;
+
+    let h = gcm_auth_key(&key);
+    let mut counter = gcm_initial_counter(&h, &nonce)?;
+
+    if !constant_time_eq(&gcm_tag(&key, &h, &counter, &aad, &ciphertext)[0..tag_length], &tag) {
+        return Ok(None);
+    }
+
+    gcm_increment(&mut counter);
+    return Ok(Some(gctr(&ciphertext, &key, &counter)));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -224,6 +481,14 @@ mod tests {
         detect_ecb(&ciphertext);
     }
 
+    #[test]
+    fn test_detect_ecb_with_block_size() {
+        let ciphertext = ascii_to_bytes("12345678abcdefgh12345678");
+
+        assert!(detect_ecb_with_block_size(&ciphertext, 8));
+        assert!(!detect_ecb_with_block_size(&ciphertext, 12));
+    }
+
     #[test]
     fn test_encrypt_cbc() {
         let key = hex_to_bytes("56e47a38c5598974bc46903dba290349");
@@ -339,4 +604,223 @@ mod tests {
 
         decrypt_ctr(&ciphertext, &key, 0);
     }
+
+    #[test]
+    fn test_ghash() {
+        let h = hex_to_bytes("66e94bd4ef8a2c3b884cfa59ca342b2e");
+        let ciphertext = hex_to_bytes("0388dace60b6a392f328c2b971b2fe78");
+
+        assert_eq!(gcm_auth_key(&vec![0; 16]).to_bytes(), h);
+        assert_eq!(ghash(&GF128::from_bytes(&h), &vec![], &ciphertext).to_bytes(), hex_to_bytes("f38cbb1ad69223dcc3457ae5b6b0f885"));
+    }
+
+    #[test]
+    fn test_encrypt_gcm() {
+        //Test cases 1-6 from the GCM specification (McGrew & Viega)
+        let zero_key = vec![0; 16];
+        let zero_nonce = vec![0; 12];
+        assert_eq!(encrypt_gcm(&vec![], &zero_key, &zero_nonce, &vec![]), (vec![], hex_to_bytes("58e2fccefa7e3061367f1d57a4e7455a")));
+        assert_eq!(encrypt_gcm(&vec![0; 16], &zero_key, &zero_nonce, &vec![]), (hex_to_bytes("0388dace60b6a392f328c2b971b2fe78"), hex_to_bytes("ab6e47d42cec13bdf53a67b21257bddf")));
+
+        let key = hex_to_bytes("feffe9928665731c6d6a8f9467308308");
+        let nonce = hex_to_bytes("cafebabefacedbaddecaf888");
+        let plaintext = hex_to_bytes("d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a721c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b391aafd255");
+        let ciphertext = hex_to_bytes("42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091473f5985");
+        assert_eq!(encrypt_gcm(&plaintext, &key, &nonce, &vec![]), (ciphertext.clone(), hex_to_bytes("4d5c2af327cd64a62cf35abd2ba6fab4")));
+
+        let aad = hex_to_bytes("feedfacedeadbeeffeedfacedeadbeefabaddad2");
+        let short_plaintext = plaintext[0..60].to_vec();
+        assert_eq!(encrypt_gcm(&short_plaintext, &key, &nonce, &aad), (ciphertext[0..60].to_vec(), hex_to_bytes("5bc94fbc3221a5db94fae95ae7121a47")));
+
+        let short_nonce = hex_to_bytes("cafebabefacedbad");
+        let short_nonce_ciphertext = hex_to_bytes("61353b4c2806934a777ff51fa22a4755699b2a714fcdc6f83766e5f97b6c742373806900e49f24b22b097544d4896b424989b5e1ebac0f07c23f4598");
+        assert_eq!(encrypt_gcm(&short_plaintext, &key, &short_nonce, &aad), (short_nonce_ciphertext, hex_to_bytes("3612d2e79e3b0785561be14aaca2fccb")));
+
+        let long_nonce = hex_to_bytes("9313225df88406e555909c5aff5269aa6a7a9538534f7da1e4c303d2a318a728c3c0c95156809539fcf0e2429a6b525416aedbf5a0de6a57a637b39b");
+        let long_nonce_ciphertext = hex_to_bytes("8ce24998625615b603a033aca13fb894be9112a5c3a211a8ba262a3cca7e2ca701e4a9a4fba43c90ccdcb281d48c7c6fd62875d2aca417034c34aee5");
+        assert_eq!(encrypt_gcm(&short_plaintext, &key, &long_nonce, &aad), (long_nonce_ciphertext, hex_to_bytes("619cc5aefffe0bfa462af43c1699d050")));
+    }
+
+    #[test]
+    #[should_panic(expected="Illegal nonce length 0 passed as a GCM nonce!")]
+    fn test_encrypt_gcm_empty_nonce() {
+        encrypt_gcm(&vec![0; 16], &vec![0; 16], &vec![], &vec![]);
+    }
+
+    #[test]
+    fn test_decrypt_gcm() {
+        let key = hex_to_bytes("feffe9928665731c6d6a8f9467308308");
+        let nonce = hex_to_bytes("cafebabefacedbaddecaf888");
+        let aad = hex_to_bytes("feedfacedeadbeeffeedfacedeadbeefabaddad2");
+        let plaintext = hex_to_bytes("d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a721c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39");
+        let ciphertext = hex_to_bytes("42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091");
+        let tag = hex_to_bytes("5bc94fbc3221a5db94fae95ae7121a47");
+
+        assert_eq!(decrypt_gcm(&ciphertext, &key, &nonce, &aad, &tag), Some(plaintext));
+    }
+
+    #[test]
+    fn test_decrypt_gcm_bad_tag() {
+        let key = ascii_to_bytes("YELLOW SUBMARINE");
+        let nonce = vec![0x42; 12];
+        let aad = ascii_to_bytes("header");
+        let (mut ciphertext, tag) = encrypt_gcm(&ascii_to_bytes("Attack at dawn"), &key, &nonce, &aad);
+
+        assert_eq!(decrypt_gcm(&ciphertext, &key, &nonce, &ascii_to_bytes("Header"), &tag), None);
+        ciphertext[0] ^= 1;
+        assert_eq!(decrypt_gcm(&ciphertext, &key, &nonce, &aad, &tag), None);
+    }
+
+    #[test]
+    fn test_decrypt_gcm_truncated_tag() {
+        let key = ascii_to_bytes("YELLOW SUBMARINE");
+        let nonce = vec![0x42; 12];
+        let plaintext = ascii_to_bytes("Attack at dawn");
+        let (ciphertext, tag) = encrypt_gcm(&plaintext, &key, &nonce, &vec![]);
+        let mut truncated = tag[0..4].to_vec();
+
+        assert_eq!(decrypt_gcm_truncated(&ciphertext, &key, &nonce, &vec![], &truncated, 4), Some(plaintext));
+        assert_eq!(decrypt_gcm_truncated(&ciphertext, &key, &nonce, &vec![], &tag, 16), decrypt_gcm(&ciphertext, &key, &nonce, &vec![], &tag));
+        truncated[3] ^= 1;
+        assert_eq!(decrypt_gcm_truncated(&ciphertext, &key, &nonce, &vec![], &truncated, 4), None);
+
+        //The verifier picks the tag length, so shorter tags than it expects are rejected
+        assert_eq!(try_decrypt_gcm(&ciphertext, &key, &nonce, &vec![], &tag[0..4].to_vec()), Err(AesError::IllegalTagLength(4)));
+        assert_eq!(try_decrypt_gcm_truncated(&ciphertext, &key, &nonce, &vec![], &tag[0..1].to_vec(), 4), Err(AesError::IllegalTagLength(1)));
+        assert_eq!(try_decrypt_gcm_truncated(&ciphertext, &key, &nonce, &vec![], &vec![], 0), Err(AesError::IllegalTagLength(0)));
+    }
+
+    #[test]
+    #[should_panic(expected="Illegal tag length 17 passed as a GCM tag!")]
+    fn test_decrypt_gcm_long_tag() {
+        decrypt_gcm(&vec![0; 16], &vec![0; 16], &vec![0; 12], &vec![], &vec![0; 17]);
+    }
+
+    ///Plaintext shared by the NIST SP 800-38A examples
+    static SP800_38A_PLAINTEXT: &str = "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e5130c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710";
+
+    #[test]
+    fn test_ecb_192() {
+        //NIST SP 800-38A F.1.3/F.1.4
+        let key = hex_to_bytes("8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b");
+        let plaintext = hex_to_bytes(SP800_38A_PLAINTEXT);
+        let ciphertext = hex_to_bytes("bd334f1d6e45f25ff712a214571fa5cc974104846d0ad3ad7734ecb3ecee4eefef7afd2270e2e60adce0ba2face6444e9a4b41ba738d6c72fb16691603c18e0e");
+
+        assert_eq!(encrypt_ecb(&plaintext, &key), ciphertext);
+        assert_eq!(decrypt_ecb(&ciphertext, &key), plaintext);
+    }
+
+    #[test]
+    fn test_ecb_256() {
+        //NIST SP 800-38A F.1.5/F.1.6
+        let key = hex_to_bytes("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4");
+        let plaintext = hex_to_bytes(SP800_38A_PLAINTEXT);
+        let ciphertext = hex_to_bytes("f3eed1bdb5d2a03c064b5a7e3db181f8591ccb10d410ed26dc5ba74a31362870b6ed21b99ca6f4f9f153e7b1beafed1d23304b7a39f9f3ff067d8d8f9e24ecc7");
+
+        assert_eq!(encrypt_ecb(&plaintext, &key), ciphertext);
+        assert_eq!(decrypt_ecb(&ciphertext, &key), plaintext);
+    }
+
+    #[test]
+    fn test_cbc_192() {
+        //NIST SP 800-38A F.2.3/F.2.4
+        let key = hex_to_bytes("8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b");
+        let iv = hex_to_bytes("000102030405060708090a0b0c0d0e0f");
+        let plaintext = hex_to_bytes(SP800_38A_PLAINTEXT);
+        let ciphertext = hex_to_bytes("4f021db243bc633d7178183a9fa071e8b4d9ada9ad7dedf4e5e738763f69145a571b242012fb7ae07fa9baac3df102e008b0e27988598881d920a9e64f5615cd");
+
+        assert_eq!(encrypt_cbc(&plaintext, &key, &iv), ciphertext);
+        assert_eq!(decrypt_cbc(&ciphertext, &key, &iv), plaintext);
+    }
+
+    #[test]
+    fn test_cbc_256() {
+        //NIST SP 800-38A F.2.5/F.2.6
+        let key = hex_to_bytes("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4");
+        let iv = hex_to_bytes("000102030405060708090a0b0c0d0e0f");
+        let plaintext = hex_to_bytes(SP800_38A_PLAINTEXT);
+        let ciphertext = hex_to_bytes("f58c4c04d6e5f1ba779eabfb5f7bfbd69cfc4e967edb808d679f777bc6702c7d39f23369a9d9bacfa530e26304231461b2eb05e2c39be9fcda6c19078c6a9d1b");
+
+        assert_eq!(encrypt_cbc(&plaintext, &key, &iv), ciphertext);
+        assert_eq!(decrypt_cbc(&ciphertext, &key, &iv), plaintext);
+    }
+
+    #[test]
+    fn test_ctr_192() {
+        //The cryptopals counter blocks (nonce 5 then the block count, both little-endian) are not the NIST ones, so the
+        //ciphertext is the SP 800-38A plaintext XORed with AES-ECB of those blocks under the F.1.3 key.
+        //Computed with Python cryptography 48.0.0.
+        let key = hex_to_bytes("8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b");
+        let plaintext = hex_to_bytes(SP800_38A_PLAINTEXT);
+        let ciphertext = hex_to_bytes("c75d26a29a9a48edaac46130a0a79ae6dbfe531745bf597071147ae222a9c77b2e667fb9842b1b19f0f0c5e68154b895c1084d31f6a40e7c892c7bd01022e4ee");
+
+        assert_eq!(encrypt_ctr(&plaintext, &key, 5), ciphertext);
+        assert_eq!(decrypt_ctr(&ciphertext[0..50].to_vec(), &key, 5), plaintext[0..50].to_vec());
+    }
+
+    #[test]
+    fn test_ctr_256() {
+        //As for AES-192, with the F.1.5 key
+        let key = hex_to_bytes("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4");
+        let plaintext = hex_to_bytes(SP800_38A_PLAINTEXT);
+        let ciphertext = hex_to_bytes("f9d5ed99e176361df5fd89cc66a7c48fdb25e17a4e46b591c8c8bd0897f31499d87717a6620a56d1ac45e69f67ed601d3181571c89a5235cc4b8ffbecb4a4c95");
+
+        assert_eq!(encrypt_ctr(&plaintext, &key, 5), ciphertext);
+        assert_eq!(decrypt_ctr(&ciphertext[0..50].to_vec(), &key, 5), plaintext[0..50].to_vec());
+    }
+
+    #[test]
+    fn test_gcm_256() {
+        //Test cases 13-14 from the GCM specification (McGrew & Viega)
+        let zero_key = vec![0; 32];
+        let zero_nonce = vec![0; 12];
+        assert_eq!(encrypt_gcm(&vec![], &zero_key, &zero_nonce, &vec![]), (vec![], hex_to_bytes("530f8afbc74536b9a963b4f1c4cb738b")));
+        assert_eq!(encrypt_gcm(&vec![0; 16], &zero_key, &zero_nonce, &vec![]), (hex_to_bytes("cea7403d4d606b6e074ec5d3baf39d18"), hex_to_bytes("d0d1c8a799996bf0265b98b5d48ab919")));
+    }
+
+    #[test]
+    #[should_panic(expected="Illegal key length 20 passed as an AES key!")]
+    fn test_encrypt_ecb_bad_key_length() {
+        encrypt_ecb(&vec![0; 16], &vec![0; 20]);
+    }
+
+    #[test]
+    fn test_try_ecb() {
+        let key = hex_to_bytes("E8E9EAEBEDEEEFF0F2F3F4F5F7F8F9FA");
+        let plaintext = hex_to_bytes("014BAF2278A69D331D5180103643E99A");
+
+        assert_eq!(try_encrypt_ecb(&plaintext, &key), Ok(encrypt_ecb(&plaintext, &key)));
+        assert_eq!(try_encrypt_ecb(&plaintext, &key[0..13].to_vec()), Err(AesError::IllegalKeyLength(13)));
+        assert_eq!(try_encrypt_ecb(&plaintext[0..13].to_vec(), &key), Err(AesError::PartialEncryptionBlock(13)));
+        assert_eq!(try_decrypt_ecb(&plaintext[0..13].to_vec(), &key), Err(AesError::PartialDecryptionBlock(13)));
+    }
+
+    #[test]
+    fn test_try_cbc() {
+        let key = hex_to_bytes("56e47a38c5598974bc46903dba290349");
+        let iv = hex_to_bytes("8ce82eefbea0da3c44699ed7db51b7d9");
+        let ciphertext = hex_to_bytes("c30e32ffedc0774e6aff6af0869f71aa");
+
+        assert_eq!(try_decrypt_cbc(&ciphertext, &key, &iv), Ok(decrypt_cbc(&ciphertext, &key, &iv)));
+        assert_eq!(try_decrypt_cbc(&ciphertext, &key, &iv[0..8].to_vec()), Err(AesError::IllegalIVLength(8)));
+        assert_eq!(try_encrypt_cbc(&ciphertext, &vec![0; 17], &iv), Err(AesError::IllegalKeyLength(17)));
+        assert_eq!(try_encrypt_ctr(&ciphertext, &vec![0; 17], 0), Err(AesError::IllegalKeyLength(17)));
+    }
+
+    #[test]
+    fn test_try_detect_ecb() {
+        assert_eq!(try_detect_ecb(&vec![0; 32]), Ok(true));
+        assert_eq!(try_detect_ecb(&vec![0; 13]), Err(AesError::PartialAnalysisBlock(13)));
+        assert_eq!(try_detect_ecb_with_block_size(&vec![0; 16], 0), Err(AesError::IllegalBlockSize(0)));
+    }
+
+    #[test]
+    fn test_try_gcm() {
+        let (ciphertext, tag) = encrypt_gcm(&vec![0; 16], &vec![0; 16], &vec![0; 12], &vec![]);
+
+        assert_eq!(try_decrypt_gcm(&ciphertext, &vec![0; 16], &vec![0; 12], &vec![], &tag), Ok(Some(vec![0; 16])));
+        assert_eq!(try_decrypt_gcm(&ciphertext, &vec![0; 16], &vec![1; 12], &vec![], &tag), Ok(None));
+        assert_eq!(try_decrypt_gcm(&ciphertext, &vec![0; 16], &vec![], &vec![], &tag), Err(AesError::IllegalNonceLength(0)));
+        assert_eq!(try_encrypt_gcm(&ciphertext, &vec![0; 8], &vec![0; 12], &vec![]), Err(AesError::IllegalKeyLength(8)));
+    }
 }
\ No newline at end of file
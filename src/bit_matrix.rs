@@ -0,0 +1,378 @@
+///Dense matrix over GF(2), with each row packed into 64-bit words
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitMatrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<Vec<u64>>
+}
+
+impl BitMatrix {
+    ///Creates an all-zero matrix of the given dimensions
+    pub fn new(rows: usize, cols: usize) -> BitMatrix {
+        return BitMatrix {
+            rows,
+            cols,
+            data: vec![vec![0; (cols + 63) / 64]; rows]
+        };
+    }
+
+    ///Creates the n x n identity matrix
+    pub fn identity(n: usize) -> BitMatrix {
+        let mut ret = BitMatrix::new(n, n);
+        for i in 0..n {
+            ret.set(i, i, true);
+        }
+        return ret;
+    }
+
+    ///Creates a matrix from a list of equal-length bit vectors, one per row.
+    ///Will panic if the rows have different lengths.
+    pub fn from_rows(rows: &Vec<Vec<bool>>) -> BitMatrix {
+        let cols = if rows.is_empty() {0} else {rows[0].len()};
+        let mut ret = BitMatrix::new(rows.len(), cols);
+        for i in 0..rows.len() {
+            if rows[i].len() != cols {
+                panic!("Rows of unequal length passed.");
+            }
+            for j in 0..cols {
+                ret.set(i, j, rows[i][j]);
+            }
+        }
+        return ret;
+    }
+
+    ///Creates a matrix whose columns are the given bit vectors.
+    ///Will panic if the columns have different lengths.
+    pub fn from_columns(columns: &Vec<Vec<bool>>) -> BitMatrix {
+        return BitMatrix::from_rows(columns).transpose();
+    }
+
+    ///Returns the number of rows
+    pub fn rows(&self) -> usize {
+        return self.rows;
+    }
+
+    ///Returns the number of columns
+    pub fn cols(&self) -> usize {
+        return self.cols;
+    }
+
+    ///Gets the bit at (row, col)
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        return (self.data[row][col / 64] >> (col % 64)) & 1 == 1;
+    }
+
+    ///Sets the bit at (row, col)
+    pub fn set(&mut self, row: usize, col: usize, value: bool) {
+        if value {
+            self.data[row][col / 64] |= 1 << (col % 64);
+        }
+        else {
+            self.data[row][col / 64] &= !(1 << (col % 64));
+        }
+    }
+
+    ///Returns the given row as a bit vector
+    pub fn row(&self, row: usize) -> Vec<bool> {
+        return (0..self.cols).map(|j| self.get(row, j)).collect();
+    }
+
+    ///Returns the given column as a bit vector
+    pub fn column(&self, col: usize) -> Vec<bool> {
+        return (0..self.rows).map(|i| self.get(i, col)).collect();
+    }
+
+    ///Adds (XORs) row src into row dst
+    fn xor_row(&mut self, src: usize, dst: usize) {
+        for w in 0..self.data[src].len() {
+            let word = self.data[src][w];
+            self.data[dst][w] ^= word;
+        }
+    }
+
+    ///Appends the rows of another matrix below this one.
+    ///Will panic if the column counts differ.
+    pub fn append_rows(&mut self, other: &BitMatrix) {
+        if self.cols != other.cols {
+            panic!("Tried to stack matrices with {} and {} columns", self.cols, other.cols);
+        }
+        self.data.append(&mut other.data.clone());
+        self.rows += other.rows;
+    }
+
+    ///Returns a new matrix made of rows [start, end) of this one
+    pub fn select_rows(&self, start: usize, end: usize) -> BitMatrix {
+        return BitMatrix {
+            rows: end - start,
+            cols: self.cols,
+            data: self.data[start..end].to_vec()
+        };
+    }
+
+    ///Returns the transpose of this matrix
+    pub fn transpose(&self) -> BitMatrix {
+        let mut ret = BitMatrix::new(self.cols, self.rows);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                if self.get(i, j) {
+                    ret.set(j, i, true);
+                }
+            }
+        }
+        return ret;
+    }
+
+    ///Computes the matrix sum self + other (entrywise XOR).
+    ///Will panic if the dimensions do not match.
+    pub fn add(&self, other: &BitMatrix) -> BitMatrix {
+        if self.rows != other.rows || self.cols != other.cols {
+            panic!("Tried to add {}x{} matrix to {}x{} matrix", self.rows, self.cols, other.rows, other.cols);
+        }
+
+        let mut ret = self.clone();
+        for i in 0..self.rows {
+            for w in 0..self.data[i].len() {
+                ret.data[i][w] ^= other.data[i][w];
+            }
+        }
+        return ret;
+    }
+
+    ///Computes the matrix product self * other.
+    ///Will panic if the inner dimensions do not match.
+    pub fn mul(&self, other: &BitMatrix) -> BitMatrix {
+        if self.cols != other.rows {
+            panic!("Tried to multiply {}x{} matrix by {}x{} matrix", self.rows, self.cols, other.rows, other.cols);
+        }
+
+        //Each output row is the sum of the rows of other selected by the bits of our row
+        let mut ret = BitMatrix::new(self.rows, other.cols);
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                if self.get(i, k) {
+                    for w in 0..other.data[k].len() {
+                        ret.data[i][w] ^= other.data[k][w];
+                    }
+                }
+            }
+        }
+        return ret;
+    }
+
+    ///Computes the matrix-vector product self * v.
+    ///Will panic if the vector length does not match the column count.
+    pub fn mul_vector(&self, v: &Vec<bool>) -> Vec<bool> {
+        let column = BitMatrix::from_columns(&vec![v.clone()]);
+        return self.mul(&column).column(0);
+    }
+
+    ///Reduces the matrix to reduced row echelon form in place by Gaussian elimination.
+    ///Returns the pivot column of each nonzero row, in order.
+    pub fn reduce(&mut self) -> Vec<usize> {
+        let mut pivots = vec![];
+        let mut pivot_row = 0;
+
+        for col in 0..self.cols {
+            if pivot_row == self.rows {
+                break;
+            }
+
+            //Find a row with a 1 in this column and move it into pivot position
+            let found = (pivot_row..self.rows).find(|&r| self.get(r, col));
+            let r = match found {
+                None => continue,
+                Some(r) => r
+            };
+            self.data.swap(pivot_row, r);
+
+            //Clear this column from every other row
+            for other in 0..self.rows {
+                if other != pivot_row && self.get(other, col) {
+                    self.xor_row(pivot_row, other);
+                }
+            }
+
+            pivots.push(col);
+            pivot_row += 1;
+        }
+
+        return pivots;
+    }
+
+    ///Computes the rank of the matrix
+    pub fn rank(&self) -> usize {
+        return self.clone().reduce().len();
+    }
+
+    ///Computes a basis for the kernel (null space) of the matrix.
+    ///Returns a matrix whose rows are the basis vectors, so self * row = 0 for each row.
+    pub fn kernel(&self) -> BitMatrix {
+        let mut reduced = self.clone();
+        let pivots = reduced.reduce();
+        let free: Vec<usize> = (0..self.cols).filter(|c| !pivots.contains(c)).collect();
+
+        //Each free column gives one basis vector: set it to 1 and back-substitute the pivots
+        let mut basis = BitMatrix::new(free.len(), self.cols);
+        for (i, &f) in free.iter().enumerate() {
+            basis.set(i, f, true);
+            for (row, &p) in pivots.iter().enumerate() {
+                if reduced.get(row, f) {
+                    basis.set(i, p, true);
+                }
+            }
+        }
+
+        return basis;
+    }
+
+    ///Finds some x with self * x = b, or returns None if the system is inconsistent.
+    ///Will panic if b does not have one entry per row.
+    pub fn solve(&self, b: &Vec<bool>) -> Option<Vec<bool>> {
+        if b.len() != self.rows {
+            panic!("Target vector length does not match row count.");
+        }
+
+        //Reduce the augmented matrix [self | b]
+        let mut augmented = BitMatrix::new(self.rows, self.cols + 1);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                augmented.set(i, j, self.get(i, j));
+            }
+            augmented.set(i, self.cols, b[i]);
+        }
+        let pivots = augmented.reduce();
+
+        //A pivot in the augmented column means 0 = 1
+        let mut x = vec![false; self.cols];
+        for (row, &p) in pivots.iter().enumerate() {
+            if p == self.cols {
+                return None;
+            }
+            x[p] = augmented.get(row, self.cols);
+        }
+
+        return Some(x);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::random;
+    use crate::mt19937::{MT19937, untemper};
+
+    ///Generates a random matrix of the given size
+    fn random_matrix(rows: usize, cols: usize) -> BitMatrix {
+        let mut m = BitMatrix::new(rows, cols);
+        for i in 0..rows {
+            for j in 0..cols {
+                m.set(i, j, random());
+            }
+        }
+        return m;
+    }
+
+    #[test]
+    fn test_get_set() {
+        let mut m = BitMatrix::new(3, 130);
+        m.set(2, 129, true);
+        m.set(0, 64, true);
+        assert!(m.get(2, 129));
+        assert!(m.get(0, 64));
+        assert!(!m.get(1, 64));
+        m.set(2, 129, false);
+        assert!(!m.get(2, 129));
+    }
+
+    #[test]
+    fn test_mul() {
+        let a = random_matrix(70, 90);
+        let b = random_matrix(90, 40);
+        let product = a.mul(&b);
+
+        assert_eq!(a.mul(&BitMatrix::identity(90)), a);
+        assert_eq!(product.transpose(), b.transpose().mul(&a.transpose()));
+        assert_eq!(product.column(5), a.mul_vector(&b.column(5)));
+    }
+
+    #[test]
+    fn test_add() {
+        let a = random_matrix(10, 70);
+        let b = random_matrix(10, 70);
+        let sum = a.add(&b);
+
+        assert_eq!(sum.add(&b), a);
+        assert_eq!(sum.get(3, 65), a.get(3, 65) ^ b.get(3, 65));
+        assert_eq!(a.add(&a), BitMatrix::new(10, 70));
+    }
+
+    #[test]
+    #[should_panic(expected="Tried to multiply 2x3 matrix by 2x3 matrix")]
+    fn test_mul_mismatch() {
+        BitMatrix::new(2, 3).mul(&BitMatrix::new(2, 3));
+    }
+
+    #[test]
+    fn test_rank() {
+        let m = BitMatrix::from_rows(&vec![
+            vec![true, false, true],
+            vec![false, true, true],
+            vec![true, true, false]
+        ]);
+        assert_eq!(m.rank(), 2);
+        assert_eq!(BitMatrix::identity(100).rank(), 100);
+    }
+
+    #[test]
+    fn test_kernel() {
+        let m = random_matrix(60, 150);
+        let kernel = m.kernel();
+
+        assert_eq!(kernel.rows(), 150 - m.rank());
+        assert_eq!(kernel.rank(), kernel.rows());
+        for i in 0..kernel.rows() {
+            assert!(m.mul_vector(&kernel.row(i)).iter().all(|b| !b));
+        }
+    }
+
+    #[test]
+    fn test_solve() {
+        let m = random_matrix(80, 100);
+        let x: Vec<bool> = (0..100).map(|_| random()).collect();
+        let b = m.mul_vector(&x);
+
+        let solution = m.solve(&b).unwrap();
+        assert_eq!(m.mul_vector(&solution), b);
+
+        let inconsistent = BitMatrix::from_rows(&vec![vec![true, false], vec![true, false]]);
+        assert_eq!(inconsistent.solve(&vec![true, false]), None);
+    }
+
+    #[test]
+    fn test_invert_mt19937_tempering() {
+        ///Converts a word to a bit vector, least significant bit first
+        fn to_bits(x: u32) -> Vec<bool> {
+            return (0..32).map(|i| (x >> i) & 1 == 1).collect();
+        }
+
+        //Tempering is linear, so build its matrix column by column from the basis words
+        let mut columns = vec![];
+        for i in 0..32 {
+            let mut state = [0; 624];
+            state[0] = 1 << i;
+            columns.push(to_bits(MT19937::from_state(state, 0).extract_number()));
+        }
+        let tempering = BitMatrix::from_columns(&columns);
+
+        let output: u32 = random();
+        let solution = tempering.solve(&to_bits(output)).unwrap();
+        let mut untempered: u32 = 0;
+        for i in 0..32 {
+            if solution[i] {
+                untempered |= 1 << i;
+            }
+        }
+
+        assert_eq!(untempered, untemper(output));
+    }
+}
@@ -21,7 +21,7 @@ lazy_static! {
 }
 
 ///Creates user token
-fn create_token(user_data: &str) -> Vec<u8> {
+pub fn create_token(user_data: &str) -> Vec<u8> {
     let escaped = user_data.replace(";", "';'").replace("=", "'='");
     let mut token = String::from("comment1=cooking%20MCs;userdata=");
     token.push_str(&escaped);
@@ -32,10 +32,12 @@ fn create_token(user_data: &str) -> Vec<u8> {
 }
 
 ///Decrypts user token and detects whether it contains ";admin=true;"
-fn is_admin(token: &Vec<u8>) -> bool {
+pub fn is_admin(token: &Vec<u8>) -> bool {
     let decrypted = decrypt_cbc(&token, &KEY, &IV);
-    let as_string = bytes_to_ascii(&pkcs7_unpad(&decrypted).unwrap());
-    return as_string.contains(";admin=true;");
+    return match pkcs7_unpad(&decrypted) {
+        Err(_) => false,
+        Ok(unpadded) => bytes_to_ascii(&unpadded).contains(";admin=true;")
+    };
 }
 
 fn challenge16() -> Vec<u8> {
@@ -14,7 +14,7 @@ lazy_static! {
 }
 
 ///Creates user token
-fn create_token(user_data: &str) -> Vec<u8> {
+pub fn create_token(user_data: &str) -> Vec<u8> {
     let escaped = user_data.replace(";", "';'").replace("=", "'='");
     let mut token = String::from("comment1=cooking%20MCs;userdata=");
     token.push_str(&escaped);
@@ -26,7 +26,7 @@ fn create_token(user_data: &str) -> Vec<u8> {
 }
 
 ///Decrypts user token and detects whether it contains ";admin=true;"
-fn is_admin(token: &Vec<u8>) -> bool {
+pub fn is_admin(token: &Vec<u8>) -> bool {
     let decrypted = decrypt_ctr(&token, &KEY, *NONCE);
     let as_string = bytes_to_ascii(&decrypted);
     return as_string.contains(";admin=true;");
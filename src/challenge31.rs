@@ -1,16 +1,36 @@
-use std::thread::sleep;
-use std::time::{Duration, SystemTime};
+use std::net::TcpListener;
+use std::sync::mpsc::channel;
+use std::thread::{sleep, spawn};
+use std::time::{Duration, Instant};
 use std::fs::File;
 use rand::random;
 use crate::mac::create_hmac;
 use crate::hash::Hash;
 use crate::converter::{hex_to_bytes, bytes_to_hex};
+use crate::timing_attack::{timing_attack, http_get_status, insecure_compare, TimingAttackSettings};
 use std::io::Read;
+use rocket::Rocket;
+use rocket::config::{Config, Environment};
 use rocket::http::Status;
 use rocket::local::Client;
 
-//Insecure comparison delay
-static DELAY_MS: Duration = Duration::from_millis(5);
+//Insecure comparison delay for challenge 31
+static DELAY: Duration = Duration::from_millis(5);
+
+//Insecure comparison delay for challenge 32
+static SHORT_DELAY: Duration = Duration::from_micros(500);
+
+//Longest wait for the signature server to start listening
+static STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+///Port for the signature checking server
+pub const DEFAULT_PORT: u16 = 9000;
+
+///Path to check signatures with the challenge 31 delay
+pub static CHECK_PATH: &str = "/check/challenge10.txt/";
+
+///Path to check signatures with the challenge 32 delay
+pub static SHORT_CHECK_PATH: &str = "/check_short/challenge10.txt/";
 
 lazy_static! {
     pub static ref KEY: Vec<u8> = {
@@ -35,84 +55,104 @@ lazy_static! {
     };
 }
 
-///Performs byte-by-byte comparison of a and b, delaying DELAY_MS after each byte.
-fn insecure_compare(a: &Vec<u8>, b: &Vec<u8>) -> bool {
-    if a.len() != b.len() {
-        return false;
-    }
-
-    for i in 0..a.len(){
-        if a[i] != b[i] {
-            return false;
-        }
-        sleep(DELAY_MS);
-    }
-    return true;
-}
-
 ///Checks if the file exists, and, if so, if the given signature is valid.
 ///Returns 200 for a valid signature, or 203 for invalid (thousands of 4xx/5xx codes cause Rocket to give up)
-#[get("/check/<filename>/<signature>")]
-fn check_signature(filename: String, signature: String) -> Status {
+fn check(filename: &str, signature: &str, delay: Duration) -> Status {
     if filename != "challenge10.txt" {
         return Status::NotFound;
     }
-    let hash = hex_to_bytes(&signature);
+    let hash = hex_to_bytes(signature);
 
-    if insecure_compare(&hash, &HASH) {
+    if insecure_compare(&hash, &HASH, delay) {
         return Status::Ok;
     }
 
     return Status::Accepted;
 }
 
-pub fn challenge31() -> Vec<u8> {
-    //Initialize webserver
-    let target_base = "/check/challenge10.txt/";
-    let mut hash: Vec<u8> = vec![0;Hash::MD4.hash_length()];
-    let rocket = rocket::ignite().mount("/", routes![check_signature]);
-    let client = Client::new(rocket).expect("valid rocket instance");
+///Signature check with the challenge 31 delay
+#[get("/check/<filename>/<signature>")]
+fn check_signature(filename: String, signature: String) -> Status {
+    return check(&filename, &signature, DELAY);
+}
 
-    //For each byte, time all possible values for the byte and assume longest time -> correct byte
-    for i in 0..Hash::MD4.hash_length() {
-        let mut max_time: u128 = 0;
-        let mut max_byte: u8 = 0;
+///Signature check with the challenge 32 delay
+#[get("/check_short/<filename>/<signature>")]
+fn check_signature_short(filename: String, signature: String) -> Status {
+    return check(&filename, &signature, SHORT_DELAY);
+}
 
-        for byte in 0..=255 as u8 {
-            hash[i] = byte;
-            let mut total_time: u128 = 0;
+///Builds the signature checking server on localhost at the given port
+fn server(port: u16) -> Rocket {
+    let config = Config::build(Environment::Development)
+        .address("127.0.0.1")
+        .port(port)
+        .finalize()
+        .expect("Invalid signature server configuration.");
 
-            for _attempt in 0..50 {
-                //Generate test URI and initialize HTTP client
-                let uri = format!("{}{}", target_base, bytes_to_hex(&hash));
+    return rocket::custom(config).mount("/", routes![check_signature, check_signature_short]);
+}
 
-                let start_time = SystemTime::now();
+///Recovers the HMAC from the given check path using the timing leak, sending requests straight to a local Rocket instance.
+///Returns None if the timing attack fails.
+pub fn challenge31(check_path: &str) -> Option<Vec<u8>> {
+    let client = Client::new(server(DEFAULT_PORT)).expect("valid rocket instance");
+    let oracle = |signature: &Vec<u8>| {
+        client.get(format!("{}{}", check_path, bytes_to_hex(signature))).dispatch().status() == Status::Ok
+    };
 
-                let response = client.get(uri).dispatch();
+    return timing_attack(oracle, Hash::MD4.hash_length(), &TimingAttackSettings::new());
+}
 
-                let end_time = SystemTime::now();
+///Recovers the HMAC from the given check path using the timing leak, over HTTP to a server on a free local port.
+///Returns None if the timing attack fails, and panics if the server does not start.
+pub fn challenge31_socket(check_path: &str) -> Option<Vec<u8>> {
+    //Let the OS pick a free port, then release it for the server to bind
+    let port = TcpListener::bind("127.0.0.1:0").and_then(|listener| listener.local_addr())
+        .expect("Could not find a free port for the signature server.").port();
+
+    //Launching only returns if it fails, in which case the error is sent back
+    let (sender, receiver) = channel();
+    spawn(move || {
+        let error = server(port).launch();
+        sender.send(error.to_string()).ok();
+    });
+
+    //Wait for the server to start listening
+    let start = Instant::now();
+    while http_get_status(port, "/").is_err() {
+        if let Ok(error) = receiver.try_recv() {
+            panic!("Signature server failed to launch: {}", error);
+        }
+        if start.elapsed() > STARTUP_TIMEOUT {
+            panic!("Signature server did not start listening in time.");
+        }
+        sleep(Duration::from_millis(10));
+    }
 
-                //Exit immediately if we get 200 OK
-                if response.status() == Status::Ok {
-                    return hash;
-                }
+    let oracle = |signature: &Vec<u8>| {
+        http_get_status(port, &format!("{}{}", check_path, bytes_to_hex(signature))).expect("server is running") == 200
+    };
 
-                let duration = end_time.duration_since(start_time);
+    return timing_attack(oracle, Hash::MD4.hash_length(), &TimingAttackSettings::new());
+}
 
-                match duration {
-                    Ok(d) => total_time += d.as_nanos(),
-                    Err(_) => ()
-                }
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            if total_time > max_time {
-                max_time = total_time;
-                max_byte = byte;
-            }
-        }
+    #[test]
+    fn test_solution() {
+        assert_eq!(challenge31(CHECK_PATH), Some(HASH.clone()));
+    }
 
-        hash[i] = max_byte;
+    #[test]
+    fn test_solution_short_delay() {
+        assert_eq!(challenge31(SHORT_CHECK_PATH), Some(HASH.clone()));
     }
 
-    return vec![];
+    #[test]
+    fn test_solution_socket() {
+        assert_eq!(challenge31_socket(SHORT_CHECK_PATH), Some(HASH.clone()));
+    }
 }
\ No newline at end of file
@@ -19,17 +19,21 @@ fn solution42() -> RSASignature {
     block.push(0xff);
     block.push(0x00);
 
-    //Append sequence header
-    block.push(0x70); //Object is a constructed sequence
-    block.push(0x15); //Total length is 21 bytes
+    //Append DigestInfo sequence header
+    block.push(0x30); //Object is a sequence
+    block.push(0x20); //Total length is 32 bytes
 
-    //Append hash specifier
-    block.push(0x46); //Object is an object identifier
-    block.push(0x01); //Length is 1 byte
-    block.push(0x04); //Identifier for MD4 hash
+    //Append algorithm identifier
+    block.push(0x30); //Object is a sequence
+    block.push(0x0c); //Length is 12 bytes
+    block.push(0x06); //Object is an object identifier
+    block.push(0x08); //Length is 8 bytes
+    block.append(&mut vec![0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x02, 0x04]); //Identifier for MD4 hash
+    block.push(0x05); //Parameters are NULL
+    block.push(0x00);
 
     //Append hash bytes
-    block.push(0x44); //Object is an octet string
+    block.push(0x04); //Object is an octet string
     block.push(0x10); //Length is 16 bytes
     block.append(&mut hash.clone()); //Hash bytes
 
@@ -49,8 +53,25 @@ fn solution42() -> RSASignature {
 mod tests {
     use super::*;
 
+    use crate::rsa::SignatureScheme;
+
     #[test]
     fn test_solution() {
         assert!(RSA_SERVER.verify_signature(&solution42()));
     }
+
+    #[test]
+    fn test_strict_verifier() {
+        //The forged block has garbage after the hash, which a check of the whole block rejects
+        assert!(!RSA_SERVER.verify_signature_with(&solution42(), SignatureScheme::PKCS15(Hash::MD4)));
+        assert!(!RSA_SERVER.verify_signature_with(&solution42(), SignatureScheme::PKCS15DER(Hash::MD4)));
+        assert!(RSA_SERVER.verify_signature_with(&RSA_SERVER.sign_message(&ascii_to_bytes("Hi mom")), SignatureScheme::PKCS15(Hash::MD4)));
+    }
+
+    #[test]
+    fn test_pss_verifier() {
+        for salt_length in [0, 16].iter() {
+            assert!(!RSA_SERVER.verify_signature_with(&solution42(), SignatureScheme::PSS(Hash::MD4, *salt_length)));
+        }
+    }
 }
\ No newline at end of file
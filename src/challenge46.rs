@@ -4,16 +4,16 @@ use gmp::mpz::Mpz;
 
 lazy_static! {
     static ref MESSAGE: String = bytes_to_ascii(&base64_to_bytes("VGhhdCdzIHdoeSBJIGZvdW5kIHlvdSBkb24ndCBwbGF5IGFyb3VuZCB3aXRoIHRoZSBGdW5reSBDb2xkIE1lZGluYQ=="));
-    static ref RSA_SERVER: RSA = RSA::new(1024);
+    pub static ref RSA_SERVER: RSA = RSA::new(1024);
 }
 
 ///Returns ciphertext of secret message
-fn get_original_ciphertext() -> Vec<u8> {
+pub fn get_original_ciphertext() -> Vec<u8> {
     return RSA_SERVER.encrypt(&ascii_to_bytes(&MESSAGE));
 }
 
 ///Decrypts ciphertext and returns the last bit of the yielded plaintext
-fn parity_oracle(ciphertext: &Vec<u8>) -> bool {
+pub fn parity_oracle(ciphertext: &Vec<u8>) -> bool {
     let b = RSA_SERVER.decrypt(ciphertext);
     return b[b.len() - 1] % 2 == 1;
 }
@@ -4,7 +4,7 @@ use crate::converter::{ascii_to_bytes, hex_to_bytes, bytes_to_ascii};
 use gmp::mpz::Mpz;
 
 lazy_static! {
-    static ref RSA_SERVER: RSA = RSA::new(KEY_LENGTH);
+    pub static ref RSA_SERVER: RSA = RSA::new(KEY_LENGTH);
 }
 
 static MESSAGE: &str = "Short message";
@@ -12,13 +12,13 @@ static KEY_LENGTH: usize = 1024;
 static PADDED_LENGTH: usize = (KEY_LENGTH / 8) - 1;
 
 ///Returns true if ciphertext is properly padded, false otherwise
-fn pkcs_oracle(ciphertext: &Vec<u8>) -> bool {
+pub fn pkcs_oracle(ciphertext: &Vec<u8>) -> bool {
     let plaintext = RSA_SERVER.decrypt(&ciphertext);
     return plaintext.len() == PADDED_LENGTH && plaintext[0] == 2;
 }
 
 ///Returns ciphertext of the secret message
-fn generate_ciphertext() -> Vec<u8> {
+pub fn generate_ciphertext() -> Vec<u8> {
     return RSA_SERVER.encrypt(&pkcs15_message_pad(&ascii_to_bytes(&MESSAGE), KEY_LENGTH));
 }
 
@@ -5,7 +5,7 @@ use crate::aes::encrypt_cbc;
 use crate::padding::pkcs7_pad;
 
 ///Returns the compressed length of a header containing secret key and the given message
-fn compression_oracle(message: &Vec<u8>) -> usize {
+pub fn compression_oracle(message: &Vec<u8>) -> usize {
     //Generate header bytes
     let header = format!("Host: hapless.com\nCookie: sessionid=TmV2ZXIgcmV2ZWFsIHRoZSBXdS1UYW5nIFNlY3JldCE=\nContent-Length: {}\n", message.len());
     let mut header_bytes = ascii_to_bytes(&header);
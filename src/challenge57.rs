@@ -48,7 +48,7 @@ fn challenge57() -> bool {
 
     //Perform malicious Diffie-Hellman exchanges to recover K mod r for each r
     let mut b_list = vec![];
-    let mut dh_bob = DiffieHellman::new_from_params(&p, &g);
+    let mut dh_bob = DiffieHellman::new_from_params_with_order(&p, &g, &q);
     for r in &r_list {
         //Find some element h of order r
         let exponent = (p.clone().sub(Mpz::one())).div(Mpz::from(*r));
@@ -89,13 +89,11 @@ fn challenge57() -> bool {
         }
     }
 
-    let x = chinese_remainder_theorem(&b_list, &r_list_mpz);
-    println!("Bob: {}", dh_bob.private_key.to_str_radix(16));
-    println!("Eve: {}", x.to_str_radix(16));
+    //The CRT gives Bob's private key mod r1*r2*...*rn. The product is at least q and the key is below q,
+    //so this is the whole key.
+    let key = chinese_remainder_theorem(&b_list, &r_list_mpz);
 
-    //TODO: x = bob's private key mod r1*r2*...*rn - how do we get from here to the full key?
-
-    return x.eq(&dh_bob.private_key);
+    return key.eq(&dh_bob.private_key);
 }
 
 #[cfg(test)]
@@ -0,0 +1,105 @@
+use std::ops::{Add, Div, Mul, Sub};
+use gmp::mpz::Mpz;
+use crate::converter::{ascii_to_bytes, bytes_to_hex, hex_to_bytes};
+use crate::diffie_hellman::DiffieHellman;
+use crate::hash::Hash;
+use crate::mac::create_prefix_mac;
+use crate::math_tools::{chinese_remainder_theorem, pollard_kangaroo, small_prime_factors};
+use rand::random;
+
+lazy_static! {
+    static ref P: Mpz = Mpz::from_str_radix("11470374874925275658116663507232161402086650258453896274534991676898999262641581519101074740642369848233294239851519212341844337347119899874391456329785623", 10).unwrap();
+    static ref Q: Mpz = Mpz::from_str_radix("335062023296420808191071248367701059461", 10).unwrap();
+    static ref J: Mpz = Mpz::from_str_radix("34233586850807404623475048381328686211071196701374230492615844865929237417097514638999377942356150481334217896204702", 10).unwrap();
+    static ref G: Mpz = Mpz::from_str_radix("622952335333961296978159266084741085889881358738459939978290179936063635566740258555167783009058567397963466103140082647486611657350811560630587013183357", 10).unwrap();
+}
+
+///Largest subgroup order we are willing to brute-force
+const FACTOR_BOUND: u64 = 1 << 16;
+
+///Finds an element of order r in Z_p* by raising random elements to the power (p-1)/r
+fn element_of_order(r: u64) -> Mpz {
+    let exponent = P.clone().sub(Mpz::one()).div(Mpz::from(r));
+    let mut h = Mpz::one();
+
+    while h.eq(&Mpz::one()) {
+        let byte_length = P.bit_length() / 8;
+        let mut random_bytes: Vec<u8> = vec![0; byte_length];
+        for i in 0..byte_length {
+            random_bytes[i] = random();
+        }
+        let random_value = Mpz::from_str_radix(&bytes_to_hex(&random_bytes), 16).unwrap();
+        h = random_value.powm(&exponent, &P);
+    }
+
+    return h;
+}
+
+///Recovers Bob's private key from small subgroups of j, then catches the rest with the kangaroo
+fn challenge58() -> bool {
+    let mut dh_bob = DiffieHellman::new_from_params_with_order(&P, &G, &Q);
+    let message = ascii_to_bytes("crazy flamboyant for the rap enjoyment");
+
+    //Recover the key modulo each small factor of j, as in challenge 57.
+    //j is even but 2 is skipped, since it is not worth a round trip.
+    let mut residues = vec![];
+    let mut moduli = vec![];
+    for r in small_prime_factors(&J, FACTOR_BOUND) {
+        if r == 2 || J.clone().modulus(&Mpz::from(r * r)) == Mpz::zero() {
+            continue;
+        }
+
+        let h = element_of_order(r);
+        let mut dh_eve = DiffieHellman::new_from_public_key(&P, &G, &h);
+        dh_bob.exchange_keys(&mut dh_eve);
+        let target_mac = dh_bob.sign_message(&message);
+
+        let mut key = Mpz::one();
+        let mut found = false;
+        for k in 0..r {
+            let test_aes = Hash::SHA1.digest(&hex_to_bytes(&key.to_str_radix(16)))[0..16].to_vec();
+            if create_prefix_mac(&message, &test_aes, Hash::SHA256).signature == target_mac.signature {
+                residues.push(Mpz::from(k));
+                moduli.push(Mpz::from(r));
+                found = true;
+                break;
+            }
+            key = key.mul(&h).modulus(&P);
+        }
+
+        if !found {
+            panic!("Could not find solution for r={}", r);
+        }
+    }
+
+    //Bob's key is n + m*r for known n, r, so y' = y * g^-n = (g^r)^m with m in [0, q/r]
+    let n = chinese_remainder_theorem(&residues, &moduli);
+    let r = moduli.iter().fold(Mpz::one(), |acc, m| acc.mul(m));
+    let g_prime = G.powm(&r, &P);
+    let y_prime = dh_bob.public_key.clone().mul(&G.powm(&n, &P).invert(&P).unwrap()).modulus(&P);
+    let upper_bound = Q.clone().div(&r);
+
+    let m = match pollard_kangaroo(&g_prime, &y_prime, &P, &Mpz::zero(), &upper_bound) {
+        None => panic!("Kangaroo search failed to find the private key"),
+        Some(m) => m
+    };
+    let key = n.add(m.mul(&r));
+
+    return key.eq(&dh_bob.private_key);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parameters() {
+        assert_eq!(P.clone().sub(Mpz::one()), J.clone().mul(&*Q));
+        assert_eq!(G.powm(&Q, &P), Mpz::one());
+    }
+
+    #[test]
+    fn test_solution() {
+        assert!(challenge58());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,109 @@
+use gmp::mpz::Mpz;
+use crate::converter::ascii_to_bytes;
+use crate::ec::{Curve, Point, CRYPTOPALS_CURVE};
+use crate::ecdh::{ECDH, derive_key};
+use crate::hash::Hash;
+use crate::mac::create_prefix_mac;
+use crate::math_tools::{chinese_remainder_theorem, small_prime_factors};
+
+///Values of b for curves sharing p and a with the cryptopals curve, together with their group orders
+static INVALID_CURVES: [(u64, &str); 3] = [
+    (210, "233970423115425145550826547352470124412"),
+    (504, "233970423115425145544350131142039591210"),
+    (727, "233970423115425145545378039958152057148")
+];
+
+///Largest subgroup order we are willing to brute-force
+const FACTOR_BOUND: u64 = 1 << 16;
+
+///Finds a point of order r on the curve by multiplying a random point by order/r
+fn point_of_order(curve: &Curve, order: &Mpz, r: u64) -> Point {
+    let cofactor = order / Mpz::from(r);
+    loop {
+        let point = curve.scalar_mul(&curve.random_point(), &cofactor);
+        if point != Point::Infinity {
+            return point;
+        }
+    }
+}
+
+///Recovers Bob's private key by sending him points of small order on invalid curves
+fn challenge59() -> bool {
+    let mut bob = ECDH::new();
+    let message = ascii_to_bytes("crazy flamboyant for the rap enjoyment");
+
+    let mut residues = vec![];
+    let mut moduli = vec![];
+    let mut modulus_product = Mpz::one();
+    for (b, order) in INVALID_CURVES.iter() {
+        let order = Mpz::from_str_radix(order, 10).unwrap();
+        let curve = Curve {
+            p: CRYPTOPALS_CURVE.p.clone(),
+            a: CRYPTOPALS_CURVE.a.clone(),
+            b: Mpz::from(*b),
+            g: Point::Infinity,
+            n: order.clone()
+        };
+
+        for r in small_prime_factors(&order, FACTOR_BOUND) {
+            //Skip subgroups already covered by a previous curve, and stop once the key is determined.
+            //2 is skipped as well: when the 2-torsion is not cyclic, multiplying by order/2 kills every point.
+            if r == 2 || moduli.contains(&Mpz::from(r)) || modulus_product > CRYPTOPALS_CURVE.n {
+                continue;
+            }
+
+            //Perform malicious ECDH key exchange with Bob using a point of order r
+            let h = point_of_order(&curve, &order, r);
+            let mut eve = ECDH::new_from_public_key(&curve, &h);
+            bob.exchange_keys(&mut eve);
+            let target_mac = bob.sign_message(&message);
+
+            //Bob's shared point is (x mod r)*h, so search the subgroup for the matching MAC
+            let mut key = Point::Infinity;
+            let mut found = false;
+            for k in 0..r {
+                if create_prefix_mac(&message, &derive_key(&key), Hash::SHA256).signature == target_mac.signature {
+                    residues.push(Mpz::from(k));
+                    moduli.push(Mpz::from(r));
+                    modulus_product = modulus_product * Mpz::from(r);
+                    found = true;
+                    break;
+                }
+                key = curve.add(&key, &h);
+            }
+
+            if !found {
+                panic!("Could not find solution for r={}", r);
+            }
+        }
+    }
+
+    let x = chinese_remainder_theorem(&residues, &moduli);
+    return x == bob.private_key;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_factors() {
+        let order = Mpz::from_str_radix(INVALID_CURVES[0].1, 10).unwrap();
+        assert_eq!(small_prime_factors(&order, FACTOR_BOUND), vec![2, 3, 11, 23, 31, 89, 4999, 28411, 45361]);
+    }
+
+    #[test]
+    fn test_invalid_point_rejected() {
+        let order = Mpz::from_str_radix(INVALID_CURVES[0].1, 10).unwrap();
+        let curve = Curve {b: Mpz::from(210), n: order.clone(), ..CRYPTOPALS_CURVE.clone()};
+        let h = point_of_order(&curve, &order, 4999);
+
+        assert_eq!(curve.scalar_mul(&h, &Mpz::from(4999)), Point::Infinity);
+        assert!(!CRYPTOPALS_CURVE.validate_point(&h));
+    }
+
+    #[test]
+    fn test_solution() {
+        assert!(challenge59());
+    }
+}
\ No newline at end of file
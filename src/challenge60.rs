@@ -0,0 +1,158 @@
+use gmp::mpz::Mpz;
+use crate::converter::ascii_to_bytes;
+use crate::ec::{MontgomeryCurve, CRYPTOPALS_MONTGOMERY_CURVE};
+use crate::ecdh::{MontgomeryECDH, derive_key_x_only};
+use crate::hash::Hash;
+use crate::mac::{create_prefix_mac, MAC};
+use crate::math_tools::{chinese_remainder_theorem, small_prime_factors};
+
+lazy_static! {
+    ///Order of the quadratic twist, which together with the curve has 2p + 2 points
+    static ref TWIST_ORDER: Mpz = {
+        let curve = &*CRYPTOPALS_MONTGOMERY_CURVE;
+        Mpz::from(2) * &curve.p + Mpz::from(2) - Mpz::from(8) * &curve.n
+    };
+}
+
+///Largest twist subgroup order we are willing to brute-force
+const FACTOR_BOUND: u64 = 1 << 22;
+
+static MESSAGE: &str = "crazy flamboyant for the rap enjoyment";
+
+///Finds the u coordinate of a point on the twist whose order is the product of the given primes,
+///by multiplying random twist points by the cofactor until no factor of the order is lost
+fn twist_point_of_order(curve: &MontgomeryCurve, factors: &Vec<u64>) -> Mpz {
+    let order = factors.iter().fold(Mpz::one(), |acc, r| acc * Mpz::from(*r));
+    let cofactor = &*TWIST_ORDER / &order;
+    loop {
+        let u = curve.random_scalar();
+        if curve.is_on_curve(&u) {
+            continue;
+        }
+
+        let h = curve.ladder(&u, &cofactor);
+        if factors.iter().all(|r| curve.ladder(&h, &(&order / Mpz::from(*r))) != Mpz::zero()) {
+            return h;
+        }
+    }
+}
+
+///Performs a malicious exchange with Bob using the given u coordinate and returns his MAC
+fn query(bob: &mut MontgomeryECDH, h: &Mpz) -> MAC {
+    let mut eve = MontgomeryECDH::new_from_public_key(&bob.curve, h);
+    bob.exchange_keys(&mut eve);
+    return bob.sign_message(&ascii_to_bytes(MESSAGE));
+}
+
+///Returns true if the shared u coordinate produces the target MAC
+fn check_mac(shared: &Mpz, target: &MAC) -> bool {
+    return create_prefix_mac(&target.message, &derive_key_x_only(shared), Hash::SHA256).signature == target.signature;
+}
+
+///Searches the subgroup generated by h (of order r) for Bob's shared secret.
+///Since k*h and -k*h share a u coordinate, this only finds k mod r up to sign, as a value in [0, r/2].
+///Walks the multiples with the differential addition u(P+Q) * u(P-Q) = (u(P)u(Q) - 1)^2 / (u(P) - u(Q))^2.
+fn find_residue(curve: &MontgomeryCurve, h: &Mpz, r: u64, target: &MAC) -> u64 {
+    let p = &curve.p;
+    if check_mac(&Mpz::zero(), target) {
+        return 0;
+    }
+
+    let mut previous = h.clone();
+    let mut current = curve.ladder(h, &Mpz::from(2));
+    if check_mac(&previous, target) {
+        return 1;
+    }
+
+    for i in 2..=r / 2 {
+        if check_mac(&current, target) {
+            return i;
+        }
+
+        let numerator = (&current * h - Mpz::one()).pow(2);
+        let denominator = (&previous * (&current - h).pow(2)).modulus(p);
+        let next = (numerator * denominator.invert(p).unwrap()).modulus(p);
+        previous = current;
+        current = next;
+    }
+
+    panic!("Could not find solution for r={}", r);
+}
+
+///Recovers Bob's private key (up to sign) by sending him u coordinates of points on the twist
+fn challenge60(bob: &mut MontgomeryECDH) -> Mpz {
+    let curve = CRYPTOPALS_MONTGOMERY_CURVE.clone();
+
+    //Find k mod r (up to sign) for each small factor r of the twist order.
+    //2 is skipped: the only point of order 2 is (0, 0), which the ladder cannot tell apart from infinity.
+    let factors: Vec<u64> = small_prime_factors(&TWIST_ORDER, FACTOR_BOUND).into_iter().filter(|r| *r != 2).collect();
+    let mut residues = vec![];
+    let mut moduli = vec![];
+    for &r in factors.iter() {
+        let h = twist_point_of_order(&curve, &vec![r]);
+        let target = query(bob, &h);
+        residues.push(Mpz::from(find_residue(&curve, &h, r, &target)));
+        moduli.push(Mpz::from(r));
+    }
+
+    //Fix the sign of one nonzero residue, then use points of order r_anchor * r_i to find
+    //whether each other residue has the same or opposite sign
+    let anchor = residues.iter().position(|c| *c != Mpz::zero()).unwrap();
+    for i in 0..residues.len() {
+        if i == anchor || residues[i] == Mpz::zero() {
+            continue;
+        }
+
+        let h = twist_point_of_order(&curve, &vec![factors[anchor], factors[i]]);
+        let target = query(bob, &h);
+
+        let guess = chinese_remainder_theorem(&vec![residues[anchor].clone(), residues[i].clone()], &vec![moduli[anchor].clone(), moduli[i].clone()]);
+        if !check_mac(&curve.ladder(&h, &guess), &target) {
+            residues[i] = &moduli[i] - &residues[i];
+        }
+    }
+
+    //Now k = +/-residue mod R, and the lifted public key is +/-k*g. Writing k*g = +/-public_key, one of
+    //public_key - (+/-residue)*g is m*R*g with |m| <= n/R, so run a kangaroo over that range for each sign.
+    let r_product = moduli.iter().fold(Mpz::one(), |acc, r| acc * r);
+    let residue = chinese_remainder_theorem(&residues, &moduli);
+    let weierstrass = curve.to_weierstrass();
+    let public_key = curve.point_to_weierstrass(&curve.lift_u(&bob.public_key).unwrap());
+    let base = weierstrass.scalar_mul(&weierstrass.g, &r_product);
+    let bound = &curve.n / &r_product + Mpz::one();
+
+    for candidate in [residue.clone(), &r_product - &residue].iter() {
+        let offset = weierstrass.negate(&weierstrass.scalar_mul(&weierstrass.g, candidate));
+        let target = weierstrass.add(&public_key, &offset);
+        if let Some(m) = weierstrass.kangaroo(&base, &target, &-&bound, &bound) {
+            return (candidate + m * &r_product).modulus(&curve.n);
+        }
+    }
+
+    panic!("Kangaroo search failed to find the private key");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_twist_point_of_order() {
+        let curve = &*CRYPTOPALS_MONTGOMERY_CURVE;
+        let h = twist_point_of_order(curve, &vec![11, 107]);
+
+        assert!(!curve.is_on_curve(&h));
+        assert_eq!(curve.ladder(&h, &Mpz::from(11 * 107)), Mpz::zero());
+        assert_ne!(curve.ladder(&h, &Mpz::from(11)), Mpz::zero());
+        assert_ne!(curve.ladder(&h, &Mpz::from(107)), Mpz::zero());
+    }
+
+    #[test]
+    fn test_solution() {
+        let mut bob = MontgomeryECDH::new();
+        let key = challenge60(&mut bob);
+
+        //x-only ECDH cannot tell k from -k, since both give the same public key
+        assert!(key == bob.private_key || key == &bob.curve.n - &bob.private_key);
+    }
+}
\ No newline at end of file
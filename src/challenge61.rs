@@ -0,0 +1,150 @@
+use gmp::mpz::{Mpz, ProbabPrimeResult};
+use rand::random;
+use crate::converter::ascii_to_bytes;
+use crate::ec::{Curve, Point};
+use crate::ecdsa::{ECDSA, ECDSASignature, hash_message};
+use crate::math_tools::{chinese_remainder_theorem, pohlig_hellman};
+use crate::rsa::{RSA, RSASignature};
+
+///Largest prime factor allowed in p - 1 when generating smooth primes
+const SMOOTHNESS_BOUND: u64 = 1 << 16;
+
+///Builds an ECDSA keypair (with its own generator) which validates someone else's signature.
+///For R = u1*G + u2*Q, any d' gives t = u1 + u2*d' and G' = t^-1 * R, so that u1*G' + u2*(d'*G') = R.
+fn ecdsa_dsks(signature: &ECDSASignature, public_key: &Point) -> ECDSA {
+    let curve = &signature.curve;
+    let n = &curve.n;
+    let w = signature.s.invert(n).unwrap();
+    let u1 = (hash_message(&signature.message, n) * &w).modulus(n);
+    let u2 = (&signature.r * &w).modulus(n);
+    let r = curve.add(&curve.scalar_mul(&curve.g, &u1), &curve.scalar_mul(public_key, &u2));
+
+    loop {
+        let d = curve.random_scalar();
+        let t = (&u1 + &u2 * &d).modulus(n);
+        let t_inv = match t.invert(n) {
+            None => continue,
+            Some(t_inv) => t_inv
+        };
+
+        let g = curve.scalar_mul(&r, &t_inv);
+        let forged_curve = Curve {g: g.clone(), ..curve.clone()};
+        return ECDSA::new_from_keys(&forged_curve, &d, &curve.scalar_mul(&g, &d));
+    }
+}
+
+///Picks a random prime below the smoothness bound which is not already in use
+fn random_small_prime(used: &Vec<u64>) -> u64 {
+    loop {
+        let candidate = Mpz::from(random::<u64>() % SMOOTHNESS_BOUND).nextprime();
+        let r = Option::<u64>::from(&candidate).unwrap();
+        if r > 2 && r < SMOOTHNESS_BOUND && !used.contains(&r) {
+            return r;
+        }
+    }
+}
+
+///Generates a prime p of at least the given bit length where p - 1 is a product of distinct small primes
+///(avoiding the excluded ones) and s generates Z_p*. Returns p and the factors of p - 1.
+fn smooth_prime(bits: usize, excluded: &Vec<u64>, s: &Mpz, m: &Mpz) -> (Mpz, Vec<u64>) {
+    loop {
+        let mut factors = vec![2];
+        let mut used = excluded.clone();
+        let mut product = Mpz::from(2);
+        while product.bit_length() < bits {
+            let r = random_small_prime(&used);
+            used.push(r);
+            factors.push(r);
+            product = product * Mpz::from(r);
+        }
+
+        let p = &product + Mpz::one();
+        if p.probab_prime(25) == ProbabPrimeResult::NotPrime {
+            continue;
+        }
+
+        //s must generate all of Z_p* so that every m has a discrete log base s
+        if factors.iter().all(|f| s.powm(&(&product / Mpz::from(*f)), &p) != Mpz::one()) && m.modulus(&p) != Mpz::zero() {
+            return (p, factors);
+        }
+    }
+}
+
+///Builds an RSA keypair which validates someone else's signature, by choosing n' = pq with smooth p - 1
+///and q - 1 so that the discrete log e' with s^e' = pad(m) mod n' can be found with Pohlig-Hellman
+fn rsa_dsks(signature: &RSASignature, original: &RSA) -> RSA {
+    let s = &signature.signature;
+    let m = s.powm(&original.e, &original.n);
+    let bits = original.n.bit_length() / 2 + 1;
+
+    loop {
+        let (p, p_factors) = smooth_prime(bits, &vec![], s, &m);
+        let (q, q_factors) = smooth_prime(bits, &p_factors, s, &m);
+        let e_p = pohlig_hellman(s, &m, &p, &p_factors).unwrap();
+        let e_q = pohlig_hellman(s, &m, &q, &q_factors).unwrap();
+
+        //p - 1 and q - 1 only share the factor 2, so the logs must agree mod 2 to combine
+        if e_p.tstbit(0) != e_q.tstbit(0) {
+            continue;
+        }
+
+        let p_minus_one = &p - Mpz::one();
+        let q_half = (&q - Mpz::one()) / Mpz::from(2);
+        let e = chinese_remainder_theorem(&vec![e_p, e_q.modulus(&q_half)], &vec![p_minus_one.clone(), q_half.clone()]);
+
+        //e' needs an inverse mod phi(n') for the new keypair to be usable
+        let phi = &p_minus_one * (&q - Mpz::one());
+        match e.invert(&phi) {
+            None => continue,
+            Some(d) => return RSA::new_from_parameters(&(&p * &q), &e, &d)
+        }
+    }
+}
+
+///Forges keypairs that validate Alice's ECDSA and RSA signatures
+fn challenge61() -> bool {
+    let message = ascii_to_bytes("hi mom");
+
+    let alice_ecdsa = ECDSA::new();
+    let ecdsa_signature = alice_ecdsa.sign_message(&message);
+    let eve_ecdsa = ecdsa_dsks(&ecdsa_signature, &alice_ecdsa.q);
+    let forged = ECDSASignature {curve: eve_ecdsa.curve.clone(), ..ecdsa_signature};
+
+    let alice_rsa = RSA::new(1024);
+    let rsa_signature = alice_rsa.sign_message(&message);
+    let eve_rsa = rsa_dsks(&rsa_signature, &alice_rsa);
+
+    return eve_ecdsa.verify_signature(&forged) && eve_rsa.verify_signature(&rsa_signature);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ecdsa_dsks() {
+        let alice = ECDSA::new();
+        let signature = alice.sign_message(&ascii_to_bytes("hi mom"));
+        let eve = ecdsa_dsks(&signature, &alice.q);
+        let forged = ECDSASignature {curve: eve.curve.clone(), ..signature};
+
+        assert_ne!(eve.q, alice.q);
+        assert!(eve.verify_signature(&forged));
+        assert!(!alice.verify_signature(&forged));
+    }
+
+    #[test]
+    fn test_rsa_dsks_keypair() {
+        let alice = RSA::new(1024);
+        let eve = rsa_dsks(&alice.sign_message(&ascii_to_bytes("hi mom")), &alice);
+
+        //Eve's keypair is a real one, and can produce signatures of its own
+        assert!(eve.n > alice.n);
+        assert!(eve.verify_signature(&eve.sign_message(&ascii_to_bytes("hi eve"))));
+    }
+
+    #[test]
+    fn test_solution() {
+        assert!(challenge61());
+    }
+}
\ No newline at end of file
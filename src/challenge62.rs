@@ -0,0 +1,133 @@
+use gmp::mpz::Mpz;
+use rand::random;
+use crate::converter::ascii_to_bytes;
+use crate::dsa::DSA;
+use crate::ecdsa::{ECDSA, hash_message};
+use crate::hash::Hash;
+use crate::math_tools::lll;
+
+///Number of low bits which are zero in every biased nonce
+const BIAS_BITS: usize = 8;
+
+///Generates a random nonce mod q whose low BIAS_BITS bits are zero
+fn biased_nonce(q: &Mpz) -> Mpz {
+    loop {
+        let mut bytes: Vec<u8> = vec![];
+        for _i in 0..q.bit_length()/8 + 1 {
+            bytes.push(random());
+        }
+        let k = (Mpz::from(&bytes[0..]).modulus(q) >> BIAS_BITS) << BIAS_BITS;
+        if k != Mpz::zero() {
+            return k;
+        }
+    }
+}
+
+///Recovers a private key from (r, s, hash) triples whose nonces are all multiples of 2^l.
+///Each signature gives k = 2^l * b with small b, so b = t*d - u mod q for t = r * (s * 2^l)^-1
+///and u = -hash * (s * 2^l)^-1. The vector (b_1 * 2^l, ..., b_n * 2^l, d, q) is then a short vector
+///of the lattice spanned by the rows q * 2^l * e_i, (t * 2^l, 1, 0) and (u * 2^l, 0, q), which LLL finds.
+///The check function should return true if the given candidate is the correct private key.
+fn recover_key<F: Fn(&Mpz) -> bool>(signatures: &Vec<(Mpz, Mpz, Mpz)>, q: &Mpz, check: F) -> Option<Mpz> {
+    let n = signatures.len();
+    let scale = Mpz::one() << BIAS_BITS;
+
+    let mut basis = vec![];
+    for i in 0..n {
+        let mut row = vec![Mpz::zero(); n + 2];
+        row[i] = q * &scale;
+        basis.push(row);
+    }
+
+    let mut t_row = vec![Mpz::zero(); n + 2];
+    let mut u_row = vec![Mpz::zero(); n + 2];
+    for (i, (r, s, h)) in signatures.iter().enumerate() {
+        let w = (s * &scale).invert(q).unwrap();
+        t_row[i] = (r * &w).modulus(q) * &scale;
+        u_row[i] = (-(h * &w)).modulus(q) * &scale;
+    }
+    t_row[n] = Mpz::one();
+    u_row[n + 1] = q.clone();
+    basis.push(t_row);
+    basis.push(u_row);
+
+    //The target vector is the reduced row ending in +/-q, with the key (up to sign) just before it
+    for row in lll(&basis) {
+        if row[n + 1].abs() != *q {
+            continue;
+        }
+
+        for candidate in [row[n].modulus(q), (-&row[n]).modulus(q)].iter() {
+            if check(candidate) {
+                return Some(candidate.clone());
+            }
+        }
+    }
+
+    return None;
+}
+
+///Recovers an ECDSA private key on the cryptopals curve from signatures with biased nonces
+fn challenge62_ecdsa(signature_count: usize) -> bool {
+    let alice = ECDSA::new();
+    let n = &alice.curve.n;
+
+    let mut signatures = vec![];
+    while signatures.len() < signature_count {
+        let message = ascii_to_bytes(&format!("message {}", signatures.len()));
+        if let Some(signature) = alice.sign_message_with_nonce(&message, &biased_nonce(n)) {
+            signatures.push((signature.r, signature.s, hash_message(&message, n)));
+        }
+    }
+
+    let key = recover_key(&signatures, n, |d| alice.curve.scalar_mul(&alice.curve.g, d) == alice.q);
+    return key == Some(alice.d.clone());
+}
+
+///Recovers a DSA private key from signatures with biased nonces
+fn challenge62_dsa(signature_count: usize) -> bool {
+    let alice = DSA::new();
+
+    let mut signatures = vec![];
+    while signatures.len() < signature_count {
+        let message = ascii_to_bytes(&format!("message {}", signatures.len()));
+        if let Some(signature) = alice.sign_message_with_nonce(&message, &biased_nonce(&alice.q)) {
+            let h = Mpz::from(&Hash::SHA1.digest(&message)[0..]);
+            signatures.push((signature.r, signature.s, h));
+        }
+    }
+
+    let key = recover_key(&signatures, &alice.q, |x| alice.g.powm(x, &alice.p) == alice.y);
+    return key == Some(alice.x.clone());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ec::CRYPTOPALS_CURVE;
+
+    #[test]
+    fn test_biased_nonce() {
+        for _i in 0..20 {
+            let k = biased_nonce(&CRYPTOPALS_CURVE.n);
+            assert_eq!(k.modulus(&Mpz::from(1 << BIAS_BITS)), Mpz::zero());
+            assert!(k > Mpz::zero());
+        }
+    }
+
+    #[test]
+    fn test_too_few_signatures() {
+        //With fewer bits of bias than bits in the key, the key is not determined
+        assert!(!challenge62_ecdsa(8));
+    }
+
+    #[test]
+    fn test_solution_ecdsa() {
+        assert!(challenge62_ecdsa(22));
+    }
+
+    #[test]
+    fn test_solution_dsa() {
+        assert!(challenge62_dsa(30));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,135 @@
+use rand::random;
+use crate::aes::{encrypt_gcm, ghash};
+use crate::converter::ascii_to_bytes;
+use crate::gf128::GF128;
+use crate::polynomial::Polynomial;
+use crate::xor::xor_bytes;
+
+lazy_static! {
+    static ref KEY: Vec<u8> = {
+        let mut k: Vec<u8> = vec![];
+        for _i in 0..16 {
+            k.push(random());
+        }
+        k
+    };
+    static ref NONCE: Vec<u8> = {
+        let mut n: Vec<u8> = vec![];
+        for _i in 0..12 {
+            n.push(random());
+        }
+        n
+    };
+}
+
+///Known plaintexts which the server encrypts (and which we are assumed to have captured)
+static MESSAGES: [&str; 3] = [
+    "Meet me at the usual place at noon.",
+    "Bring the documents and come alone.",
+    "Do not trust anyone else with this."
+];
+
+///Captured GCM message: additional data, ciphertext and tag
+struct GCMMessage {
+    aad: Vec<u8>,
+    ciphertext: Vec<u8>,
+    tag: Vec<u8>
+}
+
+///Encrypts a message with the server's key, carelessly reusing the same nonce every time
+fn encrypt_message(plaintext: &Vec<u8>, aad: &Vec<u8>) -> GCMMessage {
+    let (ciphertext, tag) = encrypt_gcm(&plaintext, &KEY, &NONCE, &aad);
+    return GCMMessage {aad: aad.clone(), ciphertext, tag};
+}
+
+///Builds the polynomial GHASH(H) + tag, whose coefficients are the message blocks.
+///Evaluating at the true authentication key gives the (secret) tag mask s.
+fn ghash_polynomial(message: &GCMMessage) -> Polynomial {
+    let mut blocks = vec![];
+    for data in [&message.aad, &message.ciphertext].iter() {
+        for block in data.chunks(16) {
+            blocks.push(GF128::from_bytes(block));
+        }
+    }
+
+    let mut length_block = ((message.aad.len() * 8) as u64).to_be_bytes().to_vec();
+    length_block.append(&mut ((message.ciphertext.len() * 8) as u64).to_be_bytes().to_vec());
+    blocks.push(GF128::from_bytes(&length_block));
+
+    //First block is the coefficient of H^m, last block (lengths) is the coefficient of H^1
+    let mut coefficients = vec![GF128::from_bytes(&message.tag)];
+    for block in blocks.iter().rev() {
+        coefficients.push(*block);
+    }
+
+    return Polynomial::new(coefficients);
+}
+
+///Recovers the candidate authentication keys for two messages encrypted under the same nonce.
+///Both share the tag mask s, so it cancels when their GHASH polynomials are added together.
+fn recover_auth_keys(first: &GCMMessage, second: &GCMMessage) -> Vec<GF128> {
+    let f = &ghash_polynomial(first) + &ghash_polynomial(second);
+    return f.roots();
+}
+
+///Forges a valid (aad, ciphertext, tag) for the target plaintext without knowing the key.
+///Returns None if no candidate authentication key checks out, or the target is longer than the recovered keystream.
+fn challenge63(target: &Vec<u8>, target_aad: &Vec<u8>) -> Option<(Vec<u8>, Vec<u8>, Vec<u8>)> {
+    //Capture three messages encrypted under the repeated nonce
+    let captured: Vec<GCMMessage> = MESSAGES.iter().enumerate()
+        .map(|(i, m)| encrypt_message(&ascii_to_bytes(m), &vec![i as u8; 8]))
+        .collect();
+
+    //Recover the keystream from a known plaintext, which limits how long the target can be
+    let known_plaintext = ascii_to_bytes(MESSAGES[0]);
+    if target.len() > known_plaintext.len() {
+        return None;
+    }
+    let keystream = xor_bytes(&known_plaintext, &captured[0].ciphertext);
+
+    //Find candidate keys from the first two messages, then use the third to eliminate false candidates
+    let mut found = None;
+    for h in recover_auth_keys(&captured[0], &captured[1]) {
+        let s = GF128::from_bytes(&captured[0].tag) + ghash(&h, &captured[0].aad, &captured[0].ciphertext);
+        let expected_tag = ghash(&h, &captured[2].aad, &captured[2].ciphertext) + s;
+        if expected_tag.to_bytes() == captured[2].tag {
+            found = Some((h, s));
+            break;
+        }
+    }
+    let (auth_key, mask) = found?;
+
+    //Encrypt the target with the keystream and compute a valid tag using the recovered authentication key and mask
+    let ciphertext = xor_bytes(&target, &keystream[0..target.len()].to_vec());
+    let tag = (ghash(&auth_key, &target_aad, &ciphertext) + mask).to_bytes();
+    return Some((target_aad.clone(), ciphertext, tag));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aes::{decrypt_gcm, gcm_auth_key};
+
+    #[test]
+    fn test_recover_auth_keys() {
+        let first = encrypt_message(&ascii_to_bytes(MESSAGES[0]), &vec![]);
+        let second = encrypt_message(&ascii_to_bytes(MESSAGES[1]), &ascii_to_bytes("extra"));
+
+        assert!(recover_auth_keys(&first, &second).contains(&gcm_auth_key(&KEY)));
+    }
+
+    #[test]
+    fn test_solution() {
+        let target = ascii_to_bytes("Transfer $1000000 to Eve.");
+        let target_aad = ascii_to_bytes("priority=high");
+        let (aad, ciphertext, tag) = challenge63(&target, &target_aad).unwrap();
+
+        assert_eq!(decrypt_gcm(&ciphertext, &KEY, &NONCE, &aad, &tag), Some(target));
+    }
+
+    #[test]
+    fn test_target_too_long() {
+        let target = ascii_to_bytes("This target is longer than any of the captured messages.");
+        assert!(challenge63(&target, &vec![]).is_none());
+    }
+}
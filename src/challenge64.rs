@@ -0,0 +1,153 @@
+use rand::random;
+use crate::aes::{encrypt_gcm, try_decrypt_gcm_truncated};
+use crate::bit_matrix::BitMatrix;
+use crate::gf128::GF128;
+
+lazy_static! {
+    static ref KEY: Vec<u8> = {
+        let mut k: Vec<u8> = vec![];
+        for _i in 0..16 {
+            k.push(random());
+        }
+        k
+    };
+    static ref NONCE: Vec<u8> = {
+        let mut n: Vec<u8> = vec![];
+        for _i in 0..12 {
+            n.push(random());
+        }
+        n
+    };
+}
+
+///Encrypts a random message of the given number of blocks, returning (ciphertext, truncated tag)
+fn encrypt_message(blocks: usize, tag_length: usize) -> (Vec<u8>, Vec<u8>) {
+    let mut plaintext = vec![];
+    for _i in 0..16 * blocks {
+        plaintext.push(random());
+    }
+
+    let (ciphertext, tag) = encrypt_gcm(&plaintext, &KEY, &NONCE, &vec![]);
+    return (ciphertext, tag[0..tag_length].to_vec());
+}
+
+///Validation oracle for a server which truncates its tags to the given length (in bytes).
+///Returns true if the tag authenticates the ciphertext, and false for tags of any other length.
+fn oracle(ciphertext: &Vec<u8>, tag: &Vec<u8>, tag_length: usize) -> bool {
+    return try_decrypt_gcm_truncated(&ciphertext, &KEY, &NONCE, &vec![], &tag, tag_length).map_or(false, |p| p.is_some());
+}
+
+///Builds the matrix Ad = M_c * S^i for each single-bit error c = x^b in the block multiplied by H^(2^i).
+///Ad maps the authentication key to the resulting change in the tag, which is linear over GF(2).
+fn error_matrices(block_exponent: usize) -> Vec<BitMatrix> {
+    let squaring = GF128::squaring_matrix();
+    let mut square_power = BitMatrix::identity(128);
+    let mut matrices = vec![];
+
+    for _i in 1..=block_exponent {
+        square_power = squaring.mul(&square_power);
+        for b in 0..128 {
+            matrices.push(GF128(1 << (127 - b)).multiplication_matrix().mul(&square_power));
+        }
+    }
+
+    return matrices;
+}
+
+///Applies an error vector to the ciphertext. The i-th group of 128 bits is XORed into the block
+///whose GHASH coefficient is H^(2^i), leaving every other block untouched.
+fn apply_error(ciphertext: &Vec<u8>, error: &Vec<bool>, block_exponent: usize) -> Vec<u8> {
+    let mut forged = ciphertext.clone();
+    let blocks = ciphertext.len() / 16;
+
+    for i in 1..=block_exponent {
+        let e = GF128::from_bits(&error[128 * (i - 1)..128 * i].to_vec()).to_bytes();
+        let index = blocks + 1 - (1 << i);
+        for j in 0..16 {
+            forged[16 * index + j] ^= e[j];
+        }
+    }
+
+    return forged;
+}
+
+///Recovers the GHASH key from a message of 2^block_exponent blocks and its truncated tag.
+///Each round zeroes as many tag bits as possible for every candidate key, forges until the oracle
+///accepts, then uses the remaining tag bits (which must also have been zero) as new equations in H.
+fn recover_auth_key<F: Fn(&Vec<u8>, &Vec<u8>) -> bool>(oracle: F, ciphertext: &Vec<u8>, tag: &Vec<u8>, block_exponent: usize) -> GF128 {
+    let tag_bits = 8 * tag.len();
+    let variables = 128 * block_exponent;
+    let matrices = error_matrices(block_exponent);
+
+    //Known linear equations on H, and a basis for the keys which still satisfy them
+    let mut known = BitMatrix::new(0, 128);
+    let mut basis = BitMatrix::identity(128);
+
+    while basis.cols() > 1 {
+        let dimension = basis.cols();
+        let zero_rows = std::cmp::min(tag_bits - 1, (variables - 1) / dimension);
+
+        //Build the dependency matrix T mapping error bits to the first zero_rows rows of Ad * basis
+        let mut dependency = BitMatrix::new(zero_rows * dimension, variables);
+        for v in 0..variables {
+            let product = matrices[v].select_rows(0, zero_rows).mul(&basis);
+            for r in 0..zero_rows {
+                for c in 0..dimension {
+                    if product.get(r, c) {
+                        dependency.set(r * dimension + c, v, true);
+                    }
+                }
+            }
+        }
+        let kernel = dependency.kernel().transpose();
+
+        //Try random errors from the kernel of T until a forgery is accepted
+        loop {
+            let mut coefficients: Vec<bool> = (0..kernel.cols()).map(|_| random()).collect();
+            coefficients[0] = true; //Guarantee a nonzero error
+            let error = kernel.mul_vector(&coefficients);
+
+            if oracle(&apply_error(&ciphertext, &error, block_exponent), &tag) {
+                //Every tag bit of Ad * H was zero, so the rows we did not force to zero give new equations
+                let mut ad = BitMatrix::new(128, 128);
+                for v in 0..variables {
+                    if error[v] {
+                        ad = ad.add(&matrices[v]);
+                    }
+                }
+                known.append_rows(&ad.select_rows(zero_rows, tag_bits));
+                break;
+            }
+        }
+
+        basis = known.kernel().transpose();
+    }
+
+    return GF128::from_bits(&basis.column(0));
+}
+
+///Recovers the authentication key for messages with the given tag length (in bytes) and block count exponent.
+///The challenge parameters (4-byte tags, 2^17 blocks) need around 2^16 forgeries of 2MB messages in the
+///first round, which is too slow for the test suite - the test uses shorter tags and messages instead.
+fn challenge64(tag_length: usize, block_exponent: usize) -> GF128 {
+    let (ciphertext, tag) = encrypt_message(1 << block_exponent, tag_length);
+    return recover_auth_key(|c: &Vec<u8>, t: &Vec<u8>| oracle(c, t, tag_length), &ciphertext, &tag, block_exponent);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aes::gcm_auth_key;
+
+    #[test]
+    fn test_oracle() {
+        let (ciphertext, tag) = encrypt_message(2, 4);
+        assert!(oracle(&ciphertext, &tag, 4));
+        assert!(!oracle(&ciphertext, &tag[0..1].to_vec(), 4));
+    }
+
+    #[test]
+    fn test_solution() {
+        assert_eq!(challenge64(2, 9), gcm_auth_key(&KEY));
+    }
+}
@@ -1,15 +1,33 @@
 use std::fs::File;
 use std::io::{BufRead, BufReader};
+use thiserror::Error;
+
+///Custom conversion errors
+#[derive(Error, Debug, PartialEq)]
+pub enum ConversionError {
+    ///Error for a character which is not a hex digit
+    #[error("Illegal hex digit {0}")]
+    IllegalHexDigit(char),
+    ///Error for a hex string with an odd number of digits, which cannot be split into whole bytes
+    #[error("Odd hex string length {0}")]
+    OddLength(usize),
+    ///Error for a character which is not a base64 digit
+    #[error("Illegal base64 digit {0}")]
+    IllegalBase64Digit(char),
+    ///Error for a base64 string with a lone digit in its last quartet, which cannot encode a byte
+    #[error("Illegal base64 string length {0}")]
+    IllegalBase64Length(usize),
+}
 
 ///Casts a hex digit (of either case) to a byte value from 0-15.
-///Will panic if an illegal digit is passed.
-fn hex_digit_to_byte(digit: char) -> u8 {
+///Returns an error if an illegal digit is passed.
+fn hex_digit_to_byte(digit: char) -> Result<u8, ConversionError> {
     if !(digit >= '0' && digit <= '9')
         && !(digit >= 'a' && digit <= 'f')
         && !(digit >= 'A' && digit <= 'F') {
-        std::panic!("Illegal hex digit {}", digit);
+        return Err(ConversionError::IllegalHexDigit(digit));
     }
-    return if digit >= '0' && digit <= '9' {
+    return Ok(if digit >= '0' && digit <= '9' {
         (digit as u8) - ('0' as u8)
     }
     else if digit >= 'a' && digit <= 'f' {
@@ -17,7 +35,7 @@ fn hex_digit_to_byte(digit: char) -> u8 {
     }
     else {
         (digit as u8) - ('A' as u8) + 10
-    }
+    });
 }
 
 ///Casts a byte value from 0-15 to the corresponding hex digit (0-f) as a char.
@@ -34,15 +52,15 @@ fn byte_to_hex_digit(byte: u8) -> char {
 }
 
 ///Casts a base-64 digit to a byte value from 0-63.
-///Will panic if an illegal digit is passed.
-fn base_64_digit_to_byte(digit: char) -> u8 {
+///Returns an error if an illegal digit is passed.
+fn base_64_digit_to_byte(digit: char) -> Result<u8, ConversionError> {
     if !(digit >= 'A' && digit <= 'Z')
         && !(digit >= 'a' && digit <= 'z')
         && !(digit >= '0' && digit <= '9')
         && digit != '+' && digit != '/' {
-        std::panic!("Illegal base64 digit {}", digit);
+        return Err(ConversionError::IllegalBase64Digit(digit));
     }
-    return if digit >= 'A' && digit <= 'Z' {
+    return Ok(if digit >= 'A' && digit <= 'Z' {
         (digit as u8) - ('A' as u8)
     }
     else if digit >= 'a' && digit <= 'z' {
@@ -56,7 +74,7 @@ fn base_64_digit_to_byte(digit: char) -> u8 {
     }
     else {
         63
-    }
+    });
 }
 
 ///Gets the base64 digit corresponding to a byte value.
@@ -82,7 +100,9 @@ fn byte_to_base_64_digit(digit: u8) -> char {
     }
 }
 
-///Takes a hex string and parses it into a vector of bytes
+///Takes a hex string and parses it into a vector of bytes.
+///Odd-length strings are treated as having a leading zero, as bytes_to_hex drops it.
+///Will panic if the string contains an illegal digit.
 pub fn hex_to_bytes(hex: &str) -> Vec<u8> {
     //Guarantee the hex string has an even number of digits
     if hex.len() % 2 != 0 {
@@ -91,22 +111,32 @@ pub fn hex_to_bytes(hex: &str) -> Vec<u8> {
         return hex_to_bytes(&padded);
     }
 
+    return try_hex_to_bytes(hex).unwrap_or_else(|e| panic!("{}", e));
+}
+
+///Takes a hex string and parses it into a vector of bytes.
+///Returns an error if the string contains an illegal digit or has an odd number of digits.
+pub fn try_hex_to_bytes(hex: &str) -> Result<Vec<u8>, ConversionError> {
+    if hex.len() % 2 != 0 {
+        return Err(ConversionError::OddLength(hex.len()));
+    }
+
     let mut ret: Vec<u8> = vec![];
     let mut first_digit: bool = true;
     let mut prev_digit: u8 = 0;
 
     for digit in hex.chars() {
         if first_digit {
-            prev_digit = hex_digit_to_byte(digit);
+            prev_digit = hex_digit_to_byte(digit)?;
             first_digit = false;
         }
         else {
-            ret.push(prev_digit * 16 + hex_digit_to_byte(digit));
+            ret.push(prev_digit * 16 + hex_digit_to_byte(digit)?);
             first_digit = true;
         }
     }
 
-    return ret;
+    return Ok(ret);
 }
 
 ///Takes a byte vector and parses into a hex string
@@ -146,13 +176,25 @@ pub fn bytes_to_ascii(bytes: &Vec<u8>) -> String {
     return ascii;
 }
 
-///Decodes a Base64 string to the corresponding bytes
+///Decodes a Base64 string to the corresponding bytes.
+///Will panic if the string contains an illegal digit or has an illegal length.
 pub fn base64_to_bytes(base64: &str) -> Vec<u8> {
+    return try_base64_to_bytes(base64).unwrap_or_else(|e| panic!("{}", e));
+}
+
+///Decodes a Base64 string to the corresponding bytes. Missing padding is allowed.
+///Returns an error if the string contains an illegal digit or has an illegal length.
+pub fn try_base64_to_bytes(base64: &str) -> Result<Vec<u8>, ConversionError> {
+    //A single digit in the last quartet cannot encode a whole byte
+    if base64.trim_end_matches('=').len() % 4 == 1 {
+        return Err(ConversionError::IllegalBase64Length(base64.len()));
+    }
+
     //If base-64 string is not padded, add padding in and recurse
     if base64.len() % 4 != 0 {
         let mut padded = String::from(base64);
         padded.push_str(&"=".repeat(4 - base64.len() % 4));
-        return base64_to_bytes(&padded);
+        return try_base64_to_bytes(&padded);
     }
 
     let mut bytes: Vec<u8> = vec![];
@@ -175,9 +217,9 @@ pub fn base64_to_bytes(base64: &str) -> Vec<u8> {
         for j in 0..4 {
             match it.next() {
                 Some(c) => digits[j] = c,
-                None => panic!("Walked off end of string!")
+                None => return Err(ConversionError::IllegalBase64Length(base64.len()))
             }
-            values[j] = base_64_digit_to_byte(digits[j]);
+            values[j] = base_64_digit_to_byte(digits[j])?;
         }
         bytes.push((values[0] << 2) + (values[1] >> 4));
         bytes.push((values[1] << 4) + (values[2] >> 2));
@@ -189,9 +231,9 @@ pub fn base64_to_bytes(base64: &str) -> Vec<u8> {
         for i in 0..(4 - pad) {
             match it.next() {
                 Some(c) => digits[i] = c,
-                None => panic!("Walked off end of string!")
+                None => return Err(ConversionError::IllegalBase64Length(base64.len()))
             }
-            values[i] = base_64_digit_to_byte(digits[i]);
+            values[i] = base_64_digit_to_byte(digits[i])?;
         }
 
         bytes.push((values[0] << 2) + (values[1] >> 4));
@@ -200,7 +242,7 @@ pub fn base64_to_bytes(base64: &str) -> Vec<u8> {
         }
     }
 
-    return bytes;
+    return Ok(bytes);
 }
 
 ///Processes a byte vector into the corresponding Base64 string
@@ -324,6 +366,22 @@ mod tests {
         hex_to_bytes(hex);
     }
 
+    #[test]
+    fn test_hex_to_bytes_odd_length() {
+        let hex = "f31";
+        let bytes: Vec<u8> = vec![15, 49];
+
+        assert_eq!(hex_to_bytes(hex), bytes);
+    }
+
+    #[test]
+    fn test_try_hex_to_bytes() {
+        assert_eq!(try_hex_to_bytes("0f31"), Ok(vec![15, 49]));
+        assert_eq!(try_hex_to_bytes("0fh1"), Err(ConversionError::IllegalHexDigit('h')));
+        assert_eq!(try_hex_to_bytes("f31"), Err(ConversionError::OddLength(3)));
+        assert_eq!(try_hex_to_bytes(""), Ok(vec![]));
+    }
+
     #[test]
     fn test_bytes_to_hex_base() {
         let hex = "af31";
@@ -378,6 +436,20 @@ mod tests {
         base64_to_bytes(s);
     }
 
+    #[test]
+    #[should_panic(expected="Illegal base64 string length 5")]
+    fn test_base64_to_bytes_bad_length() {
+        base64_to_bytes("YW55I");
+    }
+
+    #[test]
+    fn test_try_base64_to_bytes() {
+        assert_eq!(try_base64_to_bytes("YW55IGNhcm5hbCBwbGVhc3VyZQ"), Ok(ascii_to_bytes("any carnal pleasure")));
+        assert_eq!(try_base64_to_bytes("YW55*A=="), Err(ConversionError::IllegalBase64Digit('*')));
+        assert_eq!(try_base64_to_bytes("YW5=IA=="), Err(ConversionError::IllegalBase64Digit('=')));
+        assert_eq!(try_base64_to_bytes("YW55I==="), Err(ConversionError::IllegalBase64Length(8)));
+    }
+
     #[test]
     fn test_bytes_to_base64() {
         let b1 = ascii_to_bytes("any carnal pleasur");
@@ -0,0 +1,598 @@
+use gmp::mpz::Mpz;
+use thiserror::Error;
+use crate::converter::hex_to_bytes;
+use crate::hash::Hash;
+
+///Custom DER errors
+#[derive(Error, Clone, Debug, PartialEq)]
+pub enum DerError {
+    ///Error for input which ends in the middle of a value
+    #[error("DER input ended in the middle of a value!")]
+    Truncated,
+    ///Error for a tag which is not supported, or not the one expected
+    #[error("Unexpected DER tag {0:#04x}!")]
+    UnexpectedTag(u8),
+    ///Error for a length which is indefinite or not in its shortest form
+    #[error("DER length is not minimally encoded!")]
+    NonMinimalLength,
+    ///Error for contents which are not a valid DER encoding of their type
+    #[error("DER contents are not valid for tag {0:#04x}!")]
+    InvalidContents(u8),
+    ///Error for bytes left over after a complete value
+    #[error("{0} trailing bytes after DER value!")]
+    TrailingBytes(usize),
+    ///Error for a DigestInfo which does not have the right structure
+    #[error("DER value is not a DigestInfo!")]
+    MalformedDigestInfo,
+    ///Error for a DigestInfo algorithm identifier which is not a known hash
+    #[error("Unknown DigestInfo algorithm identifier!")]
+    UnknownAlgorithm,
+    ///Error for a DigestInfo whose digest is the wrong length for its hash
+    #[error("DigestInfo digest length {0} does not match its algorithm!")]
+    DigestLengthMismatch(usize),
+}
+
+const TAG_INTEGER: u8 = 0x02;
+const TAG_BIT_STRING: u8 = 0x03;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_NULL: u8 = 0x05;
+const TAG_OBJECT_IDENTIFIER: u8 = 0x06;
+const TAG_SEQUENCE: u8 = 0x30;
+
+///Prefix of the object identifiers for the NIST hash algorithms (SHA-2 and SHA-3)
+const NIST_HASH_ALGORITHMS: [u64; 8] = [2, 16, 840, 1, 101, 3, 4, 2];
+
+///Hashes which have DigestInfo algorithm identifiers
+static DIGEST_INFO_HASHES: [Hash; 12] = [
+    Hash::MD4, Hash::MD5, Hash::SHA1, Hash::SHA224, Hash::SHA256, Hash::SHA384, Hash::SHA512, Hash::SHA512_256,
+    Hash::SHA3_224, Hash::SHA3_256, Hash::SHA3_384, Hash::SHA3_512
+];
+
+///ASN.1 values which can be encoded and parsed with DER
+#[derive(Clone, Debug, PartialEq)]
+pub enum DerValue {
+    Integer(Mpz),
+    ///Number of unused bits in the last byte, then the bytes
+    BitString(u8, Vec<u8>),
+    OctetString(Vec<u8>),
+    Null,
+    ///Arcs of the object identifier
+    ObjectIdentifier(Vec<u64>),
+    Sequence(Vec<DerValue>)
+}
+
+///Encodes a length in short form if it is below 128, or long form otherwise
+fn encode_length(length: usize) -> Vec<u8> {
+    if length < 0x80 {
+        return vec![length as u8];
+    }
+
+    let bytes: Vec<u8> = length.to_be_bytes().iter().skip_while(|b| **b == 0).cloned().collect();
+    let mut encoded = vec![0x80 | bytes.len() as u8];
+    encoded.extend_from_slice(&bytes);
+    return encoded;
+}
+
+///Encodes an integer as the fewest two's complement bytes
+fn encode_integer(x: &Mpz) -> Vec<u8> {
+    if *x >= Mpz::zero() {
+        let mut bytes = hex_to_bytes(&x.to_str_radix(16));
+        if bytes[0] & 0x80 != 0 {
+            bytes.insert(0, 0x00);
+        }
+        return bytes;
+    }
+
+    //A negative x is stored as 2^(8L) + x, for the smallest L where this still has the top bit set
+    let mut length = 1;
+    while (Mpz::one() << 8 * length - 1) < -x {
+        length += 1;
+    }
+    let bytes = hex_to_bytes(&((Mpz::one() << 8 * length) + x).to_str_radix(16));
+    return bytes;
+}
+
+///Encodes an object identifier, packing the first two arcs into one subidentifier
+fn encode_object_identifier(arcs: &Vec<u64>) -> Vec<u8> {
+    if arcs.len() < 2 || arcs[0] > 2 || (arcs[0] < 2 && arcs[1] >= 40) {
+        panic!("Illegal object identifier.");
+    }
+
+    let mut encoded = vec![];
+    let mut subidentifiers = vec![arcs[0] * 40 + arcs[1]];
+    subidentifiers.extend_from_slice(&arcs[2..]);
+    for subidentifier in subidentifiers {
+        //Base 128, most significant group first, with the top bit set on all but the last byte
+        let mut groups = vec![(subidentifier & 0x7f) as u8];
+        let mut remaining = subidentifier >> 7;
+        while remaining > 0 {
+            groups.push(0x80 | (remaining & 0x7f) as u8);
+            remaining >>= 7;
+        }
+        groups.reverse();
+        encoded.append(&mut groups);
+    }
+    return encoded;
+}
+
+///Parses the contents of an object identifier.
+///Returns an error if a subidentifier is padded with leading 0x80 bytes or is cut off.
+fn parse_object_identifier(contents: &[u8]) -> Result<Vec<u64>, DerError> {
+    let invalid = DerError::InvalidContents(TAG_OBJECT_IDENTIFIER);
+    if contents.is_empty() || contents[contents.len() - 1] & 0x80 != 0 {
+        return Err(invalid);
+    }
+
+    let mut subidentifiers = vec![];
+    let mut current: u64 = 0;
+    let mut start = true;
+    for byte in contents {
+        if start && *byte == 0x80 || current >> 57 != 0 {
+            return Err(invalid);
+        }
+        current = (current << 7) | (byte & 0x7f) as u64;
+        start = byte & 0x80 == 0;
+        if start {
+            subidentifiers.push(current);
+            current = 0;
+        }
+    }
+
+    let first = subidentifiers[0];
+    let mut arcs = match first {
+        0..=39 => vec![0, first],
+        40..=79 => vec![1, first - 40],
+        _ => vec![2, first - 80]
+    };
+    arcs.extend_from_slice(&subidentifiers[1..]);
+    return Ok(arcs);
+}
+
+///Reads a tag and length starting at the index, returning the tag and the range of the contents.
+///Returns an error if the length is not minimally encoded or runs past the end of the input.
+fn parse_header(bytes: &[u8], index: usize) -> Result<(u8, usize, usize), DerError> {
+    let tag = *bytes.get(index).ok_or(DerError::Truncated)?;
+    let first = *bytes.get(index + 1).ok_or(DerError::Truncated)?;
+    let mut start = index + 2;
+
+    let length = if first < 0x80 {
+        first as usize
+    }
+    else {
+        //Long form: no indefinite lengths, no leading zero bytes, and only for lengths of 128 and up
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 8 {
+            return Err(DerError::NonMinimalLength);
+        }
+        let length_bytes = bytes.get(start..start + count).ok_or(DerError::Truncated)?;
+        if length_bytes[0] == 0 {
+            return Err(DerError::NonMinimalLength);
+        }
+        start += count;
+        let length = length_bytes.iter().fold(0, |length, b| (length << 8) | *b as usize);
+        if length < 0x80 {
+            return Err(DerError::NonMinimalLength);
+        }
+        length
+    };
+
+    if bytes.len() - start < length {
+        return Err(DerError::Truncated);
+    }
+    return Ok((tag, start, start + length));
+}
+
+///Parses the value starting at the index, returning it and the index just after it
+fn parse_value(bytes: &[u8], index: usize) -> Result<(DerValue, usize), DerError> {
+    let (tag, start, end) = parse_header(bytes, index)?;
+    let contents = &bytes[start..end];
+    let invalid = DerError::InvalidContents(tag);
+
+    let value = match tag {
+        TAG_INTEGER => {
+            //The first nine bits must not all be the same, or a shorter encoding would do
+            if contents.is_empty() || contents.len() > 1 && (contents[0] == 0x00 && contents[1] & 0x80 == 0
+                || contents[0] == 0xff && contents[1] & 0x80 != 0) {
+                return Err(invalid);
+            }
+            let mut x = Mpz::from(contents);
+            if contents[0] & 0x80 != 0 {
+                x = x - (Mpz::one() << 8 * contents.len());
+            }
+            DerValue::Integer(x)
+        },
+        TAG_BIT_STRING => {
+            //Unused bits must be zero, and there can be none if there are no bytes
+            let unused_bits = *contents.first().ok_or(invalid.clone())?;
+            let data = contents[1..].to_vec();
+            let last = data.last().cloned().unwrap_or(0);
+            if unused_bits > 7 || (data.is_empty() && unused_bits != 0) || last & ((1 << unused_bits) - 1) != 0 {
+                return Err(invalid);
+            }
+            DerValue::BitString(unused_bits, data)
+        },
+        TAG_OCTET_STRING => DerValue::OctetString(contents.to_vec()),
+        TAG_NULL => {
+            if !contents.is_empty() {
+                return Err(invalid);
+            }
+            DerValue::Null
+        },
+        TAG_OBJECT_IDENTIFIER => DerValue::ObjectIdentifier(parse_object_identifier(contents)?),
+        TAG_SEQUENCE => {
+            let mut elements = vec![];
+            let mut element_index = start;
+            while element_index < end {
+                let (element, next) = parse_value(&bytes[..end], element_index)?;
+                elements.push(element);
+                element_index = next;
+            }
+            DerValue::Sequence(elements)
+        },
+        _ => return Err(DerError::UnexpectedTag(tag))
+    };
+
+    return Ok((value, end));
+}
+
+impl DerValue {
+    ///Encodes the value with DER.
+    ///Will panic if it is an illegal object identifier, or a bit string with over 7 unused bits.
+    pub fn encode(&self) -> Vec<u8> {
+        let (tag, contents) = match self {
+            DerValue::Integer(x) => (TAG_INTEGER, encode_integer(x)),
+            DerValue::BitString(unused_bits, data) => {
+                if *unused_bits > 7 {
+                    panic!("Bit string cannot have more than 7 unused bits.");
+                }
+                let mut contents = vec![*unused_bits];
+                contents.extend_from_slice(data);
+                (TAG_BIT_STRING, contents)
+            },
+            DerValue::OctetString(data) => (TAG_OCTET_STRING, data.clone()),
+            DerValue::Null => (TAG_NULL, vec![]),
+            DerValue::ObjectIdentifier(arcs) => (TAG_OBJECT_IDENTIFIER, encode_object_identifier(arcs)),
+            DerValue::Sequence(elements) => (TAG_SEQUENCE, elements.iter().flat_map(|element| element.encode()).collect())
+        };
+
+        let mut encoded = vec![tag];
+        encoded.append(&mut encode_length(contents.len()));
+        encoded.extend_from_slice(&contents);
+        return encoded;
+    }
+
+    ///Parses a single DER encoded value, which must take up all of the input.
+    ///Returns an error if anything is not encoded exactly as DER requires.
+    pub fn parse(bytes: &[u8]) -> Result<DerValue, DerError> {
+        let (value, end) = parse_value(bytes, 0)?;
+        if end != bytes.len() {
+            return Err(DerError::TrailingBytes(bytes.len() - end));
+        }
+        return Ok(value);
+    }
+}
+
+///Returns the object identifier for a hash, or None if it does not have one
+pub fn hash_oid(hash: Hash) -> Option<Vec<u64>> {
+    let nist_hash = |n: u64| {
+        let mut arcs = NIST_HASH_ALGORITHMS.to_vec();
+        arcs.push(n);
+        Some(arcs)
+    };
+
+    return match hash {
+        Hash::MD4 => Some(vec![1, 2, 840, 113549, 2, 4]),
+        Hash::MD5 => Some(vec![1, 2, 840, 113549, 2, 5]),
+        Hash::SHA1 => Some(vec![1, 3, 14, 3, 2, 26]),
+        Hash::SHA256 => nist_hash(1),
+        Hash::SHA384 => nist_hash(2),
+        Hash::SHA512 => nist_hash(3),
+        Hash::SHA224 => nist_hash(4),
+        Hash::SHA512_256 => nist_hash(6),
+        Hash::SHA3_224 => nist_hash(7),
+        Hash::SHA3_256 => nist_hash(8),
+        Hash::SHA3_384 => nist_hash(9),
+        Hash::SHA3_512 => nist_hash(10),
+        _ => None
+    };
+}
+
+///Finds the hash with the given object identifier
+fn hash_from_oid(arcs: &Vec<u64>) -> Result<Hash, DerError> {
+    return match DIGEST_INFO_HASHES.iter().find(|hash| hash_oid(**hash).as_ref() == Some(arcs)) {
+        None => Err(DerError::UnknownAlgorithm),
+        Some(hash) => Ok(*hash)
+    };
+}
+
+///Encodes the DigestInfo structure which PKCS#1.5 signatures contain:
+///SEQUENCE { SEQUENCE { algorithm OID, NULL parameters }, OCTET STRING digest }
+///Will panic if the hash has no object identifier or the digest is the wrong length.
+pub fn digest_info(hash: Hash, digest: &Vec<u8>) -> Vec<u8> {
+    let oid = match hash_oid(hash) {
+        None => panic!("Hash has no DigestInfo algorithm identifier."),
+        Some(oid) => oid
+    };
+    if digest.len() != hash.hash_length() {
+        panic!("Message length does not match expected hash length.");
+    }
+
+    let algorithm = DerValue::Sequence(vec![DerValue::ObjectIdentifier(oid), DerValue::Null]);
+    return DerValue::Sequence(vec![algorithm, DerValue::OctetString(digest.clone())]).encode();
+}
+
+///Parses a DigestInfo, returning the hash and digest.
+///Returns an error unless the input is exactly a DER encoded DigestInfo for a known hash, with NULL parameters.
+pub fn parse_digest_info(bytes: &[u8]) -> Result<(Hash, Vec<u8>), DerError> {
+    let (oid, digest) = match DerValue::parse(bytes)? {
+        DerValue::Sequence(elements) => match elements.as_slice() {
+            [DerValue::Sequence(algorithm), DerValue::OctetString(digest)] => match algorithm.as_slice() {
+                [DerValue::ObjectIdentifier(oid), DerValue::Null] => (oid.clone(), digest.clone()),
+                _ => return Err(DerError::MalformedDigestInfo)
+            },
+            _ => return Err(DerError::MalformedDigestInfo)
+        },
+        _ => return Err(DerError::MalformedDigestInfo)
+    };
+
+    let hash = hash_from_oid(&oid)?;
+    if digest.len() != hash.hash_length() {
+        return Err(DerError::DigestLengthMismatch(digest.len()));
+    }
+    return Ok((hash, digest));
+}
+
+///Reads a tag and length the way a careless hand-written parser might, returning the index of the contents
+///and the length. Long form lengths may have any number of bytes, of which only the last four count.
+fn parse_header_lenient(bytes: &[u8], index: usize, expected_tag: u8) -> Result<(usize, usize), DerError> {
+    let tag = *bytes.get(index).ok_or(DerError::Truncated)?;
+    if tag != expected_tag {
+        return Err(DerError::UnexpectedTag(tag));
+    }
+
+    let first = *bytes.get(index + 1).ok_or(DerError::Truncated)?;
+    if first < 0x80 {
+        return Ok((index + 2, first as usize));
+    }
+    let count = (first & 0x7f) as usize;
+    let length_bytes = bytes.get(index + 2..index + 2 + count).ok_or(DerError::Truncated)?;
+    let length = length_bytes.iter().rev().take(4).rev().fold(0, |length, b| (length << 8) | *b as usize);
+    return Ok((index + 2 + count, length));
+}
+
+///Parses a DigestInfo like the lenient parsers behind BERserk-style signature forgeries.
+///Only the algorithm OID and digest are read: the parameters are skipped without being checked,
+///garbage is allowed in long form lengths, and anything after the digest is ignored.
+///This is deliberately insecure, for demonstrating the forgeries.
+pub fn parse_digest_info_lenient(bytes: &[u8]) -> Result<(Hash, Vec<u8>), DerError> {
+    let (index, _) = parse_header_lenient(bytes, 0, TAG_SEQUENCE)?;
+
+    //Skip to the end of the AlgorithmIdentifier after reading the OID
+    let (algorithm_index, algorithm_length) = parse_header_lenient(bytes, index, TAG_SEQUENCE)?;
+    let (oid_index, oid_length) = parse_header_lenient(bytes, algorithm_index, TAG_OBJECT_IDENTIFIER)?;
+    let oid = parse_object_identifier(bytes.get(oid_index..oid_index + oid_length).ok_or(DerError::Truncated)?)?;
+    let hash = hash_from_oid(&oid)?;
+
+    let (digest_index, _) = parse_header_lenient(bytes, algorithm_index + algorithm_length, TAG_OCTET_STRING)?;
+    let digest = bytes.get(digest_index..digest_index + hash.hash_length()).ok_or(DerError::Truncated)?;
+    return Ok((hash, digest.to_vec()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rsa::{RSA, RSASignature, SignatureScheme};
+    use crate::converter::ascii_to_bytes;
+
+    ///Finds an e=3 signature for a padded block with the given prefix and suffix, and free bytes between them.
+    ///The low bytes of s come from a cube root of the suffix mod 2^(8 * suffix length), which fixes the low
+    ///bytes of s^3. The high bytes come from the real cube root of the prefix, with the carries from the
+    ///low bytes landing in the free bytes. Returns None if the suffix is even, so has no cube root.
+    fn forge_cube_root(prefix: &Vec<u8>, suffix: &Vec<u8>, block_length: usize) -> Option<Mpz> {
+        if suffix[suffix.len() - 1] & 1 == 0 {
+            return None;
+        }
+
+        //Cubing permutes the odd numbers mod 2^k, and is undone by raising to 3^-1 mod 2^(k-2)
+        let suffix_bits = 8 * suffix.len();
+        let low_modulus = Mpz::one() << suffix_bits;
+        let inverse = Mpz::from(3).invert(&(Mpz::one() << suffix_bits - 2))?;
+        let low = Mpz::from(&suffix[0..]).powm(&inverse, &low_modulus);
+
+        let shift = 8 * (block_length - prefix.len());
+        let high = (Mpz::from(&prefix[0..]) << shift).root(3) + Mpz::one();
+        return Some(((high >> suffix_bits) + Mpz::one()) * &low_modulus + low);
+    }
+
+    ///Forges an e=3 signature on the first of a series of messages whose hash is odd
+    fn forge_signature<F: Fn(&Vec<u8>) -> (Vec<u8>, Vec<u8>)>(layout: F) -> RSASignature {
+        for i in 0.. {
+            let message = ascii_to_bytes(&format!("Hi mom {}", i));
+            let (prefix, suffix) = layout(&Hash::SHA1.digest(&message));
+            if let Some(signature) = forge_cube_root(&prefix, &suffix, 128) {
+                return RSASignature {
+                    message,
+                    signature
+                };
+            }
+        }
+        unreachable!();
+    }
+
+    ///Cubes a forged signature and returns what follows the 00 01 ff 00 padding
+    fn forged_digest_info(forged: &RSASignature) -> Vec<u8> {
+        let block = hex_to_bytes(&forged.signature.pow(3).to_str_radix(16));
+        assert_eq!(block[0..3], [0x01, 0xff, 0x00]);
+        return block[3..].to_vec();
+    }
+
+    #[test]
+    fn test_encode_parse() {
+        let values = vec![
+            (DerValue::Integer(Mpz::zero()), vec![0x02, 0x01, 0x00]),
+            (DerValue::Integer(Mpz::from(127)), vec![0x02, 0x01, 0x7f]),
+            (DerValue::Integer(Mpz::from(128)), vec![0x02, 0x02, 0x00, 0x80]),
+            (DerValue::Integer(Mpz::from(256)), vec![0x02, 0x02, 0x01, 0x00]),
+            (DerValue::Integer(Mpz::from(-128)), vec![0x02, 0x01, 0x80]),
+            (DerValue::Integer(Mpz::from(-129)), vec![0x02, 0x02, 0xff, 0x7f]),
+            (DerValue::BitString(6, vec![0x6e, 0x5d, 0xc0]), vec![0x03, 0x04, 0x06, 0x6e, 0x5d, 0xc0]),
+            (DerValue::OctetString(vec![0x03, 0x02, 0x06, 0xa0]), vec![0x04, 0x04, 0x03, 0x02, 0x06, 0xa0]),
+            (DerValue::Null, vec![0x05, 0x00]),
+            (DerValue::ObjectIdentifier(vec![1, 2, 840, 113549]), vec![0x06, 0x06, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d]),
+            (DerValue::Sequence(vec![DerValue::Null, DerValue::Integer(Mpz::from(3))]), vec![0x30, 0x05, 0x05, 0x00, 0x02, 0x01, 0x03])
+        ];
+
+        for (value, encoded) in values.iter() {
+            assert_eq!(value.encode(), *encoded);
+            assert_eq!(DerValue::parse(encoded), Ok(value.clone()));
+        }
+    }
+
+    #[test]
+    fn test_long_length() {
+        let value = DerValue::OctetString(vec![0xaa; 300]);
+        let encoded = value.encode();
+        assert_eq!(encoded[0..4], [0x04, 0x82, 0x01, 0x2c]);
+        assert_eq!(DerValue::parse(&encoded), Ok(value));
+
+        let value = DerValue::OctetString(vec![0xaa; 200]);
+        assert_eq!(value.encode()[0..3], [0x04, 0x81, 0xc8]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let errors = vec![
+            (vec![0x02, 0x02, 0x00, 0x7f], DerError::InvalidContents(TAG_INTEGER)),
+            (vec![0x02, 0x02, 0xff, 0x80], DerError::InvalidContents(TAG_INTEGER)),
+            (vec![0x02, 0x00], DerError::InvalidContents(TAG_INTEGER)),
+            (vec![0x05, 0x01, 0x00], DerError::InvalidContents(TAG_NULL)),
+            (vec![0x03, 0x02, 0x01, 0x01], DerError::InvalidContents(TAG_BIT_STRING)),
+            (vec![0x03, 0x01, 0x08], DerError::InvalidContents(TAG_BIT_STRING)),
+            (vec![0x06, 0x02, 0x2a, 0x86], DerError::InvalidContents(TAG_OBJECT_IDENTIFIER)),
+            (vec![0x06, 0x03, 0x2a, 0x80, 0x01], DerError::InvalidContents(TAG_OBJECT_IDENTIFIER)),
+            (vec![0x04, 0x81, 0x01, 0xaa], DerError::NonMinimalLength),
+            (vec![0x04, 0x82, 0x00, 0x80], DerError::NonMinimalLength),
+            (vec![0x30, 0x80, 0x05, 0x00, 0x00, 0x00], DerError::NonMinimalLength),
+            (vec![0x04, 0x03, 0xaa], DerError::Truncated),
+            (vec![0x30, 0x03, 0x02, 0x02, 0x01, 0x01], DerError::Truncated),
+            (vec![0x05, 0x00, 0x00], DerError::TrailingBytes(1)),
+            (vec![0x01, 0x01, 0xff], DerError::UnexpectedTag(0x01))
+        ];
+
+        for (encoded, error) in errors.iter() {
+            assert_eq!(DerValue::parse(encoded), Err(error.clone()));
+        }
+    }
+
+    #[test]
+    fn test_digest_info() {
+        //Prefixes from RFC 8017 section 9.2
+        let prefixes = [
+            (Hash::MD5, "3020300c06082a864886f70d020505000410"),
+            (Hash::SHA1, "3021300906052b0e03021a05000414"),
+            (Hash::SHA224, "302d300d06096086480165030402040500041c"),
+            (Hash::SHA256, "3031300d060960864801650304020105000420"),
+            (Hash::SHA384, "3041300d060960864801650304020205000430"),
+            (Hash::SHA512, "3051300d060960864801650304020305000440"),
+            (Hash::SHA512_256, "3031300d060960864801650304020605000420")
+        ];
+
+        for (hash, prefix) in prefixes.iter() {
+            let digest = hash.digest(&ascii_to_bytes("DigestInfo"));
+            let mut expected = hex_to_bytes(prefix);
+            expected.extend_from_slice(&digest);
+            assert_eq!(digest_info(*hash, &digest), expected);
+            assert_eq!(parse_digest_info(&expected), Ok((*hash, digest.clone())));
+            assert_eq!(parse_digest_info_lenient(&expected), Ok((*hash, digest)));
+        }
+    }
+
+    #[test]
+    fn test_parse_digest_info_errors() {
+        let digest = Hash::SHA256.digest(&ascii_to_bytes("DigestInfo"));
+        let encoded = digest_info(Hash::SHA256, &digest);
+
+        //Parameters missing rather than NULL
+        let algorithm = DerValue::Sequence(vec![DerValue::ObjectIdentifier(hash_oid(Hash::SHA256).unwrap())]);
+        let no_parameters = DerValue::Sequence(vec![algorithm, DerValue::OctetString(digest.clone())]).encode();
+        assert_eq!(parse_digest_info(&no_parameters), Err(DerError::MalformedDigestInfo));
+
+        let mut unknown = encoded.clone();
+        unknown[14] = 0x0f;
+        assert_eq!(parse_digest_info(&unknown), Err(DerError::UnknownAlgorithm));
+
+        let short = DerValue::Sequence(vec![
+            DerValue::Sequence(vec![DerValue::ObjectIdentifier(hash_oid(Hash::SHA256).unwrap()), DerValue::Null]),
+            DerValue::OctetString(digest[..20].to_vec())
+        ]).encode();
+        assert_eq!(parse_digest_info(&short), Err(DerError::DigestLengthMismatch(20)));
+
+        let mut trailing = encoded.clone();
+        trailing.push(0x00);
+        assert_eq!(parse_digest_info(&trailing), Err(DerError::TrailingBytes(1)));
+        assert_eq!(parse_digest_info(&DerValue::Null.encode()), Err(DerError::MalformedDigestInfo));
+    }
+
+    #[test]
+    #[should_panic(expected="Hash has no DigestInfo algorithm identifier.")]
+    fn test_digest_info_unknown_hash() {
+        digest_info(Hash::BAD16, &vec![0; 2]);
+    }
+
+    #[test]
+    fn test_forgery_garbage_in_parameters() {
+        //00 01 ff 00 || SEQUENCE { SEQUENCE { OID, [garbage] }, OCTET STRING digest }
+        let rsa = RSA::builder(1024).build();
+        let forged = forge_signature(|digest| {
+            let prefix = hex_to_bytes("0001ff00307a306206052b0e03021a");
+            let mut suffix = vec![0x04, 0x14];
+            suffix.extend_from_slice(digest);
+            (prefix, suffix)
+        });
+
+        assert!(parse_digest_info_lenient(&forged_digest_info(&forged)).is_ok());
+        assert!(parse_digest_info(&forged_digest_info(&forged)).is_err());
+        assert!(rsa.verify_signature_with(&forged, SignatureScheme::PKCS15Lenient(Hash::SHA1)));
+        assert!(!rsa.verify_signature_with(&forged, SignatureScheme::PKCS15DER(Hash::SHA1)));
+        assert!(!rsa.verify_signature_with(&forged, SignatureScheme::PKCS15(Hash::SHA1)));
+    }
+
+    #[test]
+    fn test_forgery_garbage_in_length() {
+        //00 01 ff 00 || SEQUENCE with a long form length made up of garbage then the real length bytes
+        let rsa = RSA::builder(1024).build();
+        let forged = forge_signature(|digest| {
+            let prefix = hex_to_bytes("0001ff0030d9");
+            let mut suffix = hex_to_bytes("000000213009");
+            suffix.append(&mut hex_to_bytes("06052b0e03021a05000414"));
+            suffix.extend_from_slice(digest);
+            (prefix, suffix)
+        });
+
+        assert!(parse_digest_info_lenient(&forged_digest_info(&forged)).is_ok());
+        assert!(parse_digest_info(&forged_digest_info(&forged)).is_err());
+        assert!(rsa.verify_signature_with(&forged, SignatureScheme::PKCS15Lenient(Hash::SHA1)));
+        assert!(!rsa.verify_signature_with(&forged, SignatureScheme::PKCS15DER(Hash::SHA1)));
+        assert!(!rsa.verify_signature_with(&forged, SignatureScheme::PKCS15(Hash::SHA1)));
+    }
+
+    #[test]
+    fn test_forgery_trailing_garbage() {
+        //00 01 ff 00 || DigestInfo || garbage, as in challenge 42
+        let rsa = RSA::builder(1024).build();
+        let message = ascii_to_bytes("Hi mom");
+        let mut block = vec![0x00, 0x01, 0xff, 0x00];
+        block.append(&mut digest_info(Hash::SHA1, &Hash::SHA1.digest(&message)));
+        block.append(&mut vec![0x55; 128 - block.len()]);
+        let forged = RSASignature {
+            message,
+            signature: Mpz::from(&block[0..]).root(3) + Mpz::one()
+        };
+
+        assert!(parse_digest_info_lenient(&forged_digest_info(&forged)).is_ok());
+        assert!(parse_digest_info(&forged_digest_info(&forged)).is_err());
+        assert!(rsa.verify_signature_with(&forged, SignatureScheme::PKCS15Lenient(Hash::SHA1)));
+        assert!(!rsa.verify_signature_with(&forged, SignatureScheme::PKCS15DER(Hash::SHA1)));
+        assert!(!rsa.verify_signature_with(&forged, SignatureScheme::PKCS15(Hash::SHA1)));
+    }
+}
\ No newline at end of file
@@ -20,6 +20,7 @@ lazy_static! {
 pub struct DiffieHellman {
     pub p: Mpz,
     pub g: Mpz,
+    pub q: Option<Mpz>,
     pub public_key: Mpz,
     pub private_key: Mpz,
     s: Mpz,
@@ -32,6 +33,7 @@ impl DiffieHellman {
         let mut ret = DiffieHellman {
             p: DEFAULT_P.clone(),
             g: DEFAULT_G.clone(),
+            q: None,
             public_key: Mpz::zero(),
             private_key: Mpz::zero(),
             s: Mpz::zero(),
@@ -48,6 +50,25 @@ impl DiffieHellman {
         let mut ret = DiffieHellman {
             p: p.clone(),
             g: g.clone(),
+            q: None,
+            public_key: Mpz::zero(),
+            private_key: Mpz::zero(),
+            s: Mpz::zero(),
+            aes_key: vec![]
+        };
+
+        ret.generate_keys();
+
+        return ret;
+    }
+
+    ///Generates a new Diffie-Hellman struct with the given parameters, where g generates a subgroup of order q.
+    ///The private key is then chosen from [0, q) rather than [0, p).
+    pub fn new_from_params_with_order(p: &Mpz, g: &Mpz, q: &Mpz) -> DiffieHellman {
+        let mut ret = DiffieHellman {
+            p: p.clone(),
+            g: g.clone(),
+            q: Some(q.clone()),
             public_key: Mpz::zero(),
             private_key: Mpz::zero(),
             s: Mpz::zero(),
@@ -64,6 +85,7 @@ impl DiffieHellman {
         return DiffieHellman {
             p: p.clone(),
             g: g.clone(),
+            q: None,
             public_key: public_key.clone(),
             private_key: Mpz::zero(),
             s: Mpz::zero(),
@@ -74,13 +96,17 @@ impl DiffieHellman {
     ///Generates a random private/public keypair
     fn generate_keys(&mut self) {
         let mut bytes: Vec<u8> = vec![];
-        let target_len = (self.p.bit_length() / 8) + 1; //Want at least 1 more byte than bits
+        let modulus = match &self.q {
+            None => self.p.clone(),
+            Some(q) => q.clone()
+        };
+        let target_len = (modulus.bit_length() / 8) + 1; //Want at least 1 more byte than bits
 
         for _i in 0..target_len {
             bytes.push(random());
         }
 
-        self.private_key = Mpz::from_str_radix(&bytes_to_hex(&bytes), 16).unwrap() % &self.p;
+        self.private_key = Mpz::from_str_radix(&bytes_to_hex(&bytes), 16).unwrap() % &modulus;
         self.public_key = Mpz::powm(&self.g, &self.private_key, &self.p);
     }
 
@@ -136,6 +162,18 @@ mod tests {
         assert_ne!(dh.public_key, zero);
     }
 
+    #[test]
+    fn test_new_from_params_with_order() {
+        let test_p = Mpz::from(8675309);
+        let test_g = Mpz::from(2);
+        let test_q = Mpz::from(1234);
+        let dh = DiffieHellman::new_from_params_with_order(&test_p, &test_g, &test_q);
+
+        assert_eq!(dh.q, Some(test_q.clone()));
+        assert!(dh.private_key < test_q);
+        assert_eq!(dh.public_key, Mpz::powm(&test_g, &dh.private_key, &test_p));
+    }
+
     #[test]
     fn test_key_exchange() {
         let mut dh1 = DiffieHellman::new();
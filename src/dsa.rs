@@ -71,24 +71,34 @@ impl DSA {
         let mut k = Mpz::from(&bytes[0..]);
         k = k.modulus(&self.q);
 
-        //Try to compute r = g^k mod p. If r = 0, retry with different k.
-        let r = self.g.powm(&k, &self.p).modulus(&self.q);
+        //If k turns out to be unusable, retry with different k
+        return match self.sign_message_with_nonce(message, &k) {
+            None => self.sign_message(message),
+            Some(signature) => signature
+        };
+    }
+
+    ///Signs the given message using the given nonce k.
+    ///Returns None if k does not produce a valid signature.
+    pub fn sign_message_with_nonce(&self, message: &Vec<u8>, k: &Mpz) -> Option<DSASignature> {
+        //Compute r = g^k mod p, which must be nonzero
+        let r = self.g.powm(k, &self.p).modulus(&self.q);
         if r == Mpz::zero() {
-            return self.sign_message(message);
+            return None;
         }
 
-        //Calculate s = k^-1 * (hash(message) + x*r)) mod q. If s = 0, retry with different k.
+        //Calculate s = k^-1 * (hash(message) + x*r)) mod q
         let h = Mpz::from(&Hash::SHA1.digest(message)[0..]);
-        return match inverse_mod(&k, &self.q) {
-            None => return self.sign_message(message),
-            Some(k_inv) => DSASignature {
+        return match inverse_mod(k, &self.q) {
+            None => None,
+            Some(k_inv) => Some(DSASignature {
                 message: message.clone(),
                 r: r.clone(),
                 s: (&k_inv * (&h + (&self.x * &r))).modulus(&self.q),
                 p: self.p.clone(),
                 q: self.q.clone(),
                 g: self.g.clone()
-            }
+            })
         };
     }
 
@@ -149,4 +159,16 @@ mod tests {
         assert_eq!(d.q, q);
         assert_eq!(d.g, g);
     }
+
+    #[test]
+    fn test_sign_message_with_nonce() {
+        let d = DSA::new();
+        let message = ascii_to_bytes("TEST MESSAGE: for DSA signature and verification");
+        let k = Mpz::from(12345);
+        let signature = d.sign_message_with_nonce(&message, &k).unwrap();
+
+        assert!(d.verify_signature(&signature));
+        assert_eq!(signature.r, d.g.powm(&k, &d.p).modulus(&d.q));
+        assert!(d.sign_message_with_nonce(&message, &Mpz::zero()).is_none());
+    }
 }
\ No newline at end of file
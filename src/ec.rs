@@ -0,0 +1,548 @@
+use gmp::mpz::Mpz;
+use rand::random;
+use crate::math_tools::{kangaroo_in_group, kangaroo_jump_count, sqrt_mod};
+
+lazy_static! {
+    ///Cryptopals set 8 curve: y^2 = x^3 - 95051x + 11279326
+    pub static ref CRYPTOPALS_CURVE: Curve = Curve {
+        p: Mpz::from_str_radix("233970423115425145524320034830162017933", 10).unwrap(),
+        a: Mpz::from(-95051),
+        b: Mpz::from(11279326),
+        g: Point::Affine(Mpz::from(182), Mpz::from_str_radix("85518893674295321206118380980485522083", 10).unwrap()),
+        n: Mpz::from_str_radix("29246302889428143187362802287225875743", 10).unwrap()
+    };
+    ///Cryptopals set 8 curve in Montgomery form: v^2 = u^3 + 534u^2 + u
+    pub static ref CRYPTOPALS_MONTGOMERY_CURVE: MontgomeryCurve = MontgomeryCurve {
+        p: Mpz::from_str_radix("233970423115425145524320034830162017933", 10).unwrap(),
+        a: Mpz::from(534),
+        b: Mpz::one(),
+        g: Point::Affine(Mpz::from(4), Mpz::from_str_radix("85518893674295321206118380980485522083", 10).unwrap()),
+        n: Mpz::from_str_radix("29246302889428143187362802287225875743", 10).unwrap()
+    };
+    ///NIST P-256 (secp256r1)
+    pub static ref P256: Curve = Curve {
+        p: Mpz::from_str_radix("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff", 16).unwrap(),
+        a: Mpz::from(-3),
+        b: Mpz::from_str_radix("5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b", 16).unwrap(),
+        g: Point::Affine(
+            Mpz::from_str_radix("6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296", 16).unwrap(),
+            Mpz::from_str_radix("4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5", 16).unwrap()
+        ),
+        n: Mpz::from_str_radix("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551", 16).unwrap()
+    };
+}
+
+///Point on an elliptic curve in affine coordinates
+#[derive(Clone, Debug, PartialEq)]
+pub enum Point {
+    Infinity,
+    Affine(Mpz, Mpz)
+}
+
+///Point in Jacobian coordinates (X, Y, Z), representing the affine point (X/Z^2, Y/Z^3).
+///Any point with Z = 0 is the point at infinity.
+#[derive(Clone, Debug)]
+pub struct JacobianPoint {
+    pub x: Mpz,
+    pub y: Mpz,
+    pub z: Mpz
+}
+
+///Short Weierstrass curve y^2 = x^3 + ax + b over GF(p), with base point g of order n
+#[derive(Clone, Debug, PartialEq)]
+pub struct Curve {
+    pub p: Mpz,
+    pub a: Mpz,
+    pub b: Mpz,
+    pub g: Point,
+    pub n: Mpz
+}
+
+impl Curve {
+    ///Reduces a value into the range [0, p)
+    fn reduce(&self, x: &Mpz) -> Mpz {
+        return x.modulus(&self.p);
+    }
+
+    ///Computes the inverse of x modulo p. Will panic if x is not invertible.
+    fn invert(&self, x: &Mpz) -> Mpz {
+        return match self.reduce(x).invert(&self.p) {
+            None => panic!("Tried to invert a non-invertible field element"),
+            Some(i) => i
+        }
+    }
+
+    ///Returns true if the point is the point at infinity or satisfies the curve equation
+    pub fn is_on_curve(&self, point: &Point) -> bool {
+        return match point {
+            Point::Infinity => true,
+            Point::Affine(x, y) => {
+                if x < &Mpz::zero() || x >= &self.p || y < &Mpz::zero() || y >= &self.p {
+                    return false;
+                }
+                let lhs = self.reduce(&(y * y));
+                let rhs = self.reduce(&((x * x * x) + (&self.a * x) + &self.b));
+                lhs == rhs
+            }
+        }
+    }
+
+    ///Validates a received public point: it must be a finite point on this curve in the subgroup generated by g
+    pub fn validate_point(&self, point: &Point) -> bool {
+        return *point != Point::Infinity
+            && self.is_on_curve(point)
+            && self.scalar_mul(point, &self.n) == Point::Infinity;
+    }
+
+    ///Returns the inverse -P of a point
+    pub fn negate(&self, point: &Point) -> Point {
+        return match point {
+            Point::Infinity => Point::Infinity,
+            Point::Affine(x, y) => Point::Affine(x.clone(), self.reduce(&-y))
+        }
+    }
+
+    ///Adds two points using the affine group law.
+    ///The formulas do not use b, so points on any curve sharing this curve's a can be added (as the invalid-curve attack does).
+    ///Will panic if the points share an x coordinate without being equal or opposite, which means they lie on different curves.
+    pub fn add(&self, p1: &Point, p2: &Point) -> Point {
+        let (x1, y1, x2, y2) = match (p1, p2) {
+            (Point::Infinity, _) => return p2.clone(),
+            (_, Point::Infinity) => return p1.clone(),
+            (Point::Affine(x1, y1), Point::Affine(x2, y2)) => (x1, y1, x2, y2)
+        };
+
+        //P + (-P) = O
+        if x1 == x2 && self.reduce(&(y1 + y2)) == Mpz::zero() {
+            return Point::Infinity;
+        }
+
+        //The chord through points of different curves with the same x coordinate is vertical, and has no slope
+        if x1 == x2 && p1 != p2 {
+            panic!("Cannot add points with the same x coordinate from different curves!");
+        }
+
+        //Slope of the tangent line when doubling, or of the chord otherwise
+        let m = if p1 == p2 {
+            self.reduce(&((Mpz::from(3) * x1 * x1 + &self.a) * self.invert(&(Mpz::from(2) * y1))))
+        }
+        else {
+            self.reduce(&((y2 - y1) * self.invert(&(x2 - x1))))
+        };
+
+        let x3 = self.reduce(&(&m * &m - x1 - x2));
+        let y3 = self.reduce(&(&m * (x1 - &x3) - y1));
+        return Point::Affine(x3, y3);
+    }
+
+    ///Doubles a point using the affine group law
+    pub fn double(&self, point: &Point) -> Point {
+        return self.add(point, point);
+    }
+
+    ///Converts an affine point to Jacobian coordinates
+    pub fn to_jacobian(&self, point: &Point) -> JacobianPoint {
+        return match point {
+            Point::Infinity => JacobianPoint {x: Mpz::one(), y: Mpz::one(), z: Mpz::zero()},
+            Point::Affine(x, y) => JacobianPoint {x: x.clone(), y: y.clone(), z: Mpz::one()}
+        }
+    }
+
+    ///Converts a Jacobian point back to affine coordinates
+    pub fn to_affine(&self, point: &JacobianPoint) -> Point {
+        if self.reduce(&point.z) == Mpz::zero() {
+            return Point::Infinity;
+        }
+
+        let z_inv = self.invert(&point.z);
+        let z_inv_sq = self.reduce(&(&z_inv * &z_inv));
+        let x = self.reduce(&(&point.x * &z_inv_sq));
+        let y = self.reduce(&(&point.y * &z_inv_sq * &z_inv));
+        return Point::Affine(x, y);
+    }
+
+    ///Doubles a point in Jacobian coordinates
+    pub fn double_jacobian(&self, point: &JacobianPoint) -> JacobianPoint {
+        if self.reduce(&point.z) == Mpz::zero() || self.reduce(&point.y) == Mpz::zero() {
+            return JacobianPoint {x: Mpz::one(), y: Mpz::one(), z: Mpz::zero()};
+        }
+
+        let y_sq = self.reduce(&(&point.y * &point.y));
+        let z_sq = self.reduce(&(&point.z * &point.z));
+        let s = self.reduce(&(Mpz::from(4) * &point.x * &y_sq));
+        let m = self.reduce(&(Mpz::from(3) * &point.x * &point.x + &self.a * &z_sq * &z_sq));
+
+        let x = self.reduce(&(&m * &m - Mpz::from(2) * &s));
+        let y = self.reduce(&(&m * (&s - &x) - Mpz::from(8) * &y_sq * &y_sq));
+        let z = self.reduce(&(Mpz::from(2) * &point.y * &point.z));
+        return JacobianPoint {x, y, z};
+    }
+
+    ///Adds two points in Jacobian coordinates
+    pub fn add_jacobian(&self, p1: &JacobianPoint, p2: &JacobianPoint) -> JacobianPoint {
+        if self.reduce(&p1.z) == Mpz::zero() {
+            return p2.clone();
+        }
+        if self.reduce(&p2.z) == Mpz::zero() {
+            return p1.clone();
+        }
+
+        let z1_sq = self.reduce(&(&p1.z * &p1.z));
+        let z2_sq = self.reduce(&(&p2.z * &p2.z));
+        let u1 = self.reduce(&(&p1.x * &z2_sq));
+        let u2 = self.reduce(&(&p2.x * &z1_sq));
+        let s1 = self.reduce(&(&p1.y * &z2_sq * &p2.z));
+        let s2 = self.reduce(&(&p2.y * &z1_sq * &p1.z));
+
+        //Same x coordinate: either P + (-P) = O or a doubling
+        if u1 == u2 {
+            return if s1 != s2 {
+                JacobianPoint {x: Mpz::one(), y: Mpz::one(), z: Mpz::zero()}
+            }
+            else {
+                self.double_jacobian(p1)
+            }
+        }
+
+        let h = self.reduce(&(&u2 - &u1));
+        let r = self.reduce(&(&s2 - &s1));
+        let h_sq = self.reduce(&(&h * &h));
+        let h_cu = self.reduce(&(&h_sq * &h));
+        let u1_h_sq = self.reduce(&(&u1 * &h_sq));
+
+        let x = self.reduce(&(&r * &r - &h_cu - Mpz::from(2) * &u1_h_sq));
+        let y = self.reduce(&(&r * (&u1_h_sq - &x) - &s1 * &h_cu));
+        let z = self.reduce(&(&h * &p1.z * &p2.z));
+        return JacobianPoint {x, y, z};
+    }
+
+    ///Computes k*P by double-and-add in Jacobian coordinates. Negative k multiplies -P by |k|.
+    pub fn scalar_mul(&self, point: &Point, k: &Mpz) -> Point {
+        if k < &Mpz::zero() {
+            return self.scalar_mul(&self.negate(point), &-k);
+        }
+
+        let base = self.to_jacobian(point);
+        let mut result = self.to_jacobian(&Point::Infinity);
+        for i in (0..k.bit_length()).rev() {
+            result = self.double_jacobian(&result);
+            if k.tstbit(i) {
+                result = self.add_jacobian(&result, &base);
+            }
+        }
+
+        return self.to_affine(&result);
+    }
+
+    ///Finds a point with the given x coordinate, or returns None if x^3 + ax + b is not a square mod p
+    pub fn lift_x(&self, x: &Mpz) -> Option<Point> {
+        let x = self.reduce(x);
+        let rhs = self.reduce(&(&x * &x * &x + &self.a * &x + &self.b));
+        return match sqrt_mod(&rhs, &self.p) {
+            None => None,
+            Some(y) => Some(Point::Affine(x, y))
+        }
+    }
+
+    ///Generates a random finite point on the curve (not necessarily in the subgroup generated by g)
+    pub fn random_point(&self) -> Point {
+        loop {
+            let mut bytes: Vec<u8> = vec![];
+            for _i in 0..(self.p.bit_length() / 8) + 1 {
+                bytes.push(random());
+            }
+            if let Some(point) = self.lift_x(&Mpz::from(&bytes[0..])) {
+                return point;
+            }
+        }
+    }
+
+    ///Generates a random scalar in the range [1, n)
+    pub fn random_scalar(&self) -> Mpz {
+        return random_scalar(&self.n);
+    }
+
+    ///Finds x in [a, b] with x*g = y using Pollard's kangaroo algorithm, or returns None if there is no such x.
+    ///Jumps are powers of two chosen by the x coordinate, scaled so the mean jump is about sqrt(b - a)/2.
+    pub fn kangaroo(&self, g: &Point, y: &Point, a: &Mpz, b: &Mpz) -> Option<Mpz> {
+        return kangaroo_in_group(g, y, a, b, kangaroo_jump_count(a, b),
+            |p1: &Point, p2: &Point| self.add(p1, p2),
+            |point: &Point, k: &Mpz| self.scalar_mul(point, k),
+            |point: &Point| match point {
+                Point::Infinity => Mpz::zero(),
+                Point::Affine(x, _) => x.clone()
+            });
+    }
+}
+
+///Montgomery curve Bv^2 = u^3 + Au^2 + u over GF(p), with base point g of order n.
+///Points use the same representation as Weierstrass points, with (u, v) coordinates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MontgomeryCurve {
+    pub p: Mpz,
+    pub a: Mpz,
+    pub b: Mpz,
+    pub g: Point,
+    pub n: Mpz
+}
+
+impl MontgomeryCurve {
+    ///Returns the u coordinate of the base point
+    pub fn base_u(&self) -> Mpz {
+        return match &self.g {
+            Point::Infinity => panic!("Montgomery curve has no finite base point"),
+            Point::Affine(u, _) => u.clone()
+        }
+    }
+
+    ///Computes the inverse of x modulo p. Will panic if x is not invertible.
+    fn invert(&self, x: &Mpz) -> Mpz {
+        return match x.modulus(&self.p).invert(&self.p) {
+            None => panic!("Tried to invert a non-invertible field element"),
+            Some(i) => i
+        }
+    }
+
+    ///Computes u^3 + Au^2 + u, which is B times a square iff u is the coordinate of a point on the curve
+    fn rhs(&self, u: &Mpz) -> Mpz {
+        return (u * u * u + &self.a * u * u + u).modulus(&self.p);
+    }
+
+    ///Returns true if u is the coordinate of a point on this curve rather than its quadratic twist
+    pub fn is_on_curve(&self, u: &Mpz) -> bool {
+        let value = (self.rhs(u) * self.invert(&self.b)).modulus(&self.p);
+        let exponent = (&self.p - Mpz::one()) / Mpz::from(2);
+        return value == Mpz::zero() || value.powm(&exponent, &self.p) == Mpz::one();
+    }
+
+    ///Finds a point with the given u coordinate, or returns None if u is on the twist
+    pub fn lift_u(&self, u: &Mpz) -> Option<Point> {
+        let u = u.modulus(&self.p);
+        let v_squared = (self.rhs(&u) * self.invert(&self.b)).modulus(&self.p);
+        return match sqrt_mod(&v_squared, &self.p) {
+            None => None,
+            Some(v) => Some(Point::Affine(u, v))
+        }
+    }
+
+    ///Computes the u coordinate of k*P from the u coordinate of P with the Montgomery ladder.
+    ///Never uses v or B, so it works equally well (and without complaint) for points on the twist.
+    ///The point at infinity is returned as 0.
+    pub fn ladder(&self, u: &Mpz, k: &Mpz) -> Mpz {
+        let p = &self.p;
+        let (mut u2, mut w2) = (Mpz::one(), Mpz::zero());
+        let (mut u3, mut w3) = (u.modulus(p), Mpz::one());
+
+        for i in (0..p.bit_length()).rev() {
+            let bit = k.tstbit(i);
+            if bit {
+                std::mem::swap(&mut u2, &mut u3);
+                std::mem::swap(&mut w2, &mut w3);
+            }
+
+            let new_u3 = (&u2 * &u3 - &w2 * &w3).pow(2).modulus(p);
+            let new_w3 = (u * (&u2 * &w3 - &w2 * &u3).pow(2)).modulus(p);
+            let new_u2 = (&u2 * &u2 - &w2 * &w2).pow(2).modulus(p);
+            let new_w2 = (Mpz::from(4) * &u2 * &w2 * (&u2 * &u2 + &self.a * &u2 * &w2 + &w2 * &w2)).modulus(p);
+            u2 = new_u2;
+            w2 = new_w2;
+            u3 = new_u3;
+            w3 = new_w3;
+
+            if bit {
+                std::mem::swap(&mut u2, &mut u3);
+                std::mem::swap(&mut w2, &mut w3);
+            }
+        }
+
+        return (u2 * w2.powm(&(p - Mpz::from(2)), p)).modulus(p);
+    }
+
+    ///Returns the equivalent short Weierstrass curve
+    pub fn to_weierstrass(&self) -> Curve {
+        let p = &self.p;
+        let a = ((Mpz::from(3) - &self.a * &self.a) * self.invert(&(Mpz::from(3) * &self.b * &self.b))).modulus(p);
+        let b = ((Mpz::from(2) * self.a.pow(3) - Mpz::from(9) * &self.a) * self.invert(&(Mpz::from(27) * self.b.pow(3)))).modulus(p);
+        let mut curve = Curve {p: p.clone(), a, b, g: Point::Infinity, n: self.n.clone()};
+        curve.g = self.point_to_weierstrass(&self.g);
+        return curve;
+    }
+
+    ///Maps (u, v) to (u/B + A/3B, v/B) on the equivalent Weierstrass curve
+    pub fn point_to_weierstrass(&self, point: &Point) -> Point {
+        return match point {
+            Point::Infinity => Point::Infinity,
+            Point::Affine(u, v) => {
+                let b_inv = self.invert(&self.b);
+                let shift = (&self.a * self.invert(&(Mpz::from(3) * &self.b))).modulus(&self.p);
+                Point::Affine((u * &b_inv + shift).modulus(&self.p), (v * &b_inv).modulus(&self.p))
+            }
+        }
+    }
+
+    ///Maps (x, y) on the equivalent Weierstrass curve back to (B(x - A/3B), By)
+    pub fn point_from_weierstrass(&self, point: &Point) -> Point {
+        return match point {
+            Point::Infinity => Point::Infinity,
+            Point::Affine(x, y) => {
+                let shift = (&self.a * self.invert(&(Mpz::from(3) * &self.b))).modulus(&self.p);
+                Point::Affine((&self.b * (x - shift)).modulus(&self.p), (&self.b * y).modulus(&self.p))
+            }
+        }
+    }
+
+    ///Generates a random scalar in the range [1, n)
+    pub fn random_scalar(&self) -> Mpz {
+        return random_scalar(&self.n);
+    }
+}
+
+///Generates a random scalar in the range [1, n)
+fn random_scalar(n: &Mpz) -> Mpz {
+    loop {
+        let mut bytes: Vec<u8> = vec![];
+        for _i in 0..(n.bit_length() / 8) + 1 {
+            bytes.push(random());
+        }
+        let k = Mpz::from(&bytes[0..]).modulus(n);
+        if k != Mpz::zero() {
+            return k;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_points() {
+        for curve in [&*CRYPTOPALS_CURVE, &*P256].iter() {
+            assert!(curve.is_on_curve(&curve.g));
+            assert!(curve.validate_point(&curve.g));
+            assert_eq!(curve.scalar_mul(&curve.g, &curve.n), Point::Infinity);
+        }
+    }
+
+    #[test]
+    fn test_p256_scalar_mul() {
+        let two_g = Point::Affine(
+            Mpz::from_str_radix("7cf27b188d034f7e8a52380304b51ac3c08969e277f21b35a60b48fc47669978", 16).unwrap(),
+            Mpz::from_str_radix("07775510db8ed040293d9ac69f7430dbba7dade63ce982299e04b79d227873d1", 16).unwrap()
+        );
+        let three_g = Point::Affine(
+            Mpz::from_str_radix("5ecbe4d1a6330a44c8f7ef951d4bf165e6c6b721efada985fb41661bc6e7fd6c", 16).unwrap(),
+            Mpz::from_str_radix("8734640c4998ff7e374b06ce1a64a2ecd82ab036384fb83d9a79b127a27d5032", 16).unwrap()
+        );
+        let k = Mpz::from_str_radix("c51e4753afdec1e6b6c6a5b992f43f8dd0c7a8933072708b6522468b2ffb06fd", 16).unwrap();
+        let k_g = Point::Affine(
+            Mpz::from_str_radix("942c9f408ead9d82d34a1b9a6a827ebe3e2ddf782b448d23be1b6143988ccef4", 16).unwrap(),
+            Mpz::from_str_radix("8c9eaf6c0d14d992fc63bad3e2496be2eee61cb5b97f65f428ca94a5d0ee19a1", 16).unwrap()
+        );
+
+        assert_eq!(P256.double(&P256.g), two_g);
+        assert_eq!(P256.add(&two_g, &P256.g), three_g);
+        assert_eq!(P256.scalar_mul(&P256.g, &Mpz::from(3)), three_g);
+        assert_eq!(P256.scalar_mul(&P256.g, &k), k_g);
+    }
+
+    #[test]
+    fn test_group_law() {
+        let curve = &*CRYPTOPALS_CURVE;
+        let a = curve.scalar_mul(&curve.g, &curve.random_scalar());
+        let b = curve.scalar_mul(&curve.g, &curve.random_scalar());
+
+        assert_eq!(curve.add(&a, &b), curve.add(&b, &a));
+        assert_eq!(curve.add(&a, &curve.negate(&a)), Point::Infinity);
+        assert_eq!(curve.add(&a, &Point::Infinity), a);
+        assert_eq!(curve.scalar_mul(&a, &Mpz::from(-1)), curve.negate(&a));
+        assert_eq!(curve.scalar_mul(&a, &Mpz::zero()), Point::Infinity);
+
+        let jacobian_sum = curve.add_jacobian(&curve.to_jacobian(&a), &curve.to_jacobian(&b));
+        assert_eq!(curve.to_affine(&jacobian_sum), curve.add(&a, &b));
+        let jacobian_double = curve.double_jacobian(&curve.to_jacobian(&a));
+        assert_eq!(curve.to_affine(&jacobian_double), curve.double(&a));
+    }
+
+    #[test]
+    #[should_panic(expected="Cannot add points with the same x coordinate from different curves!")]
+    fn test_add_different_curves() {
+        let curve = &*CRYPTOPALS_CURVE;
+        if let Point::Affine(x, y) = &curve.g {
+            curve.add(&curve.g, &Point::Affine(x.clone(), y + Mpz::one()));
+        }
+    }
+
+    #[test]
+    fn test_scalar_mul_distributes() {
+        let curve = &*CRYPTOPALS_CURVE;
+        let k1 = curve.random_scalar();
+        let k2 = curve.random_scalar();
+
+        let sum = curve.add(&curve.scalar_mul(&curve.g, &k1), &curve.scalar_mul(&curve.g, &k2));
+        assert_eq!(curve.scalar_mul(&curve.g, &(&k1 + &k2)), sum);
+        assert_eq!(curve.scalar_mul(&curve.scalar_mul(&curve.g, &k1), &k2), curve.scalar_mul(&curve.g, &(&k1 * &k2)));
+    }
+
+    #[test]
+    fn test_validate_point() {
+        let curve = &*CRYPTOPALS_CURVE;
+        assert!(!curve.validate_point(&Point::Infinity));
+        assert!(!curve.validate_point(&Point::Affine(Mpz::from(182), Mpz::from(1))));
+        assert!(!curve.is_on_curve(&Point::Affine(Mpz::from(182), &curve.p + Mpz::one())));
+
+        //Multiples of the base point are always valid
+        let point = curve.scalar_mul(&curve.g, &curve.random_scalar());
+        assert!(curve.validate_point(&point));
+    }
+
+    #[test]
+    fn test_random_point() {
+        let curve = &*CRYPTOPALS_CURVE;
+        let point = curve.random_point();
+        assert!(curve.is_on_curve(&point));
+
+        if let Point::Affine(x, _) = &point {
+            let lifted = curve.lift_x(x).unwrap();
+            assert!(lifted == point || lifted == curve.negate(&point));
+        }
+    }
+
+    #[test]
+    fn test_montgomery_conversion() {
+        let curve = &*CRYPTOPALS_MONTGOMERY_CURVE;
+        let weierstrass = curve.to_weierstrass();
+        assert_eq!(weierstrass.a, CRYPTOPALS_CURVE.a.modulus(&curve.p));
+        assert_eq!(weierstrass.b, CRYPTOPALS_CURVE.b);
+        assert_eq!(weierstrass.g, CRYPTOPALS_CURVE.g);
+
+        let point = CRYPTOPALS_CURVE.scalar_mul(&CRYPTOPALS_CURVE.g, &CRYPTOPALS_CURVE.random_scalar());
+        assert_eq!(curve.point_to_weierstrass(&curve.point_from_weierstrass(&point)), point);
+    }
+
+    #[test]
+    fn test_ladder() {
+        let curve = &*CRYPTOPALS_MONTGOMERY_CURVE;
+        let k = curve.random_scalar();
+
+        assert_eq!(curve.ladder(&curve.base_u(), &curve.n), Mpz::zero());
+        assert!(curve.is_on_curve(&curve.base_u()));
+
+        //The ladder must agree with full scalar multiplication on the Weierstrass curve
+        let expected = curve.point_from_weierstrass(&CRYPTOPALS_CURVE.scalar_mul(&CRYPTOPALS_CURVE.g, &k));
+        match expected {
+            Point::Affine(u, _) => assert_eq!(curve.ladder(&curve.base_u(), &k), u),
+            Point::Infinity => panic!("Random scalar gave the point at infinity")
+        }
+    }
+
+    #[test]
+    fn test_kangaroo() {
+        let curve = &*CRYPTOPALS_CURVE;
+        let x = Mpz::from(1000000) + Mpz::from(random::<u32>() % 1000000);
+        let y = curve.scalar_mul(&curve.g, &x);
+
+        assert_eq!(curve.kangaroo(&curve.g, &y, &Mpz::from(1000000), &Mpz::from(2000000)), Some(x));
+        assert_eq!(curve.kangaroo(&curve.g, &y, &Mpz::from(3000000), &Mpz::from(4000000)), None);
+    }
+}
\ No newline at end of file
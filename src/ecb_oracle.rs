@@ -0,0 +1,202 @@
+use crate::aes::detect_ecb_with_block_size;
+
+///Largest block size to check for
+const MAX_BLOCK_SIZE: usize = 64;
+
+///Byte used to build the alignment marker blocks
+const MARKER_BYTE: u8 = 0xff;
+
+///Byte used for filler before and after the marker blocks
+const FILLER_BYTE: u8 = 0x00;
+
+///Number of oracle calls to make while trying to align the marker before giving up
+const MAX_ALIGNMENT_ATTEMPTS: usize = 10000;
+
+///Wraps an ECB encryption oracle (which may add a prefix of fixed or random length) so that each query returns
+///only the encryption of the payload and what follows it. Each payload is preceded by filler and two marker blocks,
+///and the query is repeated until the marker blocks appear, which means the payload starts on a block boundary.
+struct AlignedOracle<F: Fn(&[u8]) -> Vec<u8>> {
+    oracle: F,
+    block_size: usize,
+    marker: Vec<u8>,
+    filler_length: usize
+}
+
+impl<F: Fn(&[u8]) -> Vec<u8>> AlignedOracle<F> {
+    ///Sets up the aligned oracle, finding the encryption of a marker block.
+    ///Will panic if the oracle does not appear to use ECB mode.
+    fn new(oracle: F, block_size: usize) -> AlignedOracle<F> {
+        //However the input is aligned, this gives at least three identical blocks in a row
+        let ciphertext = oracle(&vec![MARKER_BYTE; block_size * 4]);
+        if !detect_ecb_with_block_size(&ciphertext, block_size) {
+            panic!("Oracle does not appear to use ECB mode.");
+        }
+
+        let blocks: Vec<&[u8]> = ciphertext.chunks(block_size).collect();
+        for i in 0..blocks.len() - 2 {
+            if blocks[i] == blocks[i + 1] && blocks[i] == blocks[i + 2] {
+                return AlignedOracle {
+                    marker: blocks[i].to_vec(),
+                    oracle,
+                    block_size,
+                    filler_length: 0
+                };
+            }
+        }
+
+        panic!("Oracle does not appear to use ECB mode.");
+    }
+
+    ///Returns the encryption of payload || suffix || padding, stripping off everything before the payload.
+    ///Filler lengths are tried in turn, starting from the last one that worked.
+    fn query(&mut self, payload: &[u8]) -> Vec<u8> {
+        let b = self.block_size;
+        for attempt in 0..MAX_ALIGNMENT_ATTEMPTS {
+            let filler_length = (self.filler_length + attempt) % b;
+
+            //Filler of at least one block on each side keeps partial marker blocks from being mistaken for the marker
+            let mut input = vec![FILLER_BYTE; b + filler_length];
+            input.append(&mut vec![MARKER_BYTE; b * 2]);
+            input.append(&mut vec![FILLER_BYTE; b]);
+            input.extend_from_slice(payload);
+
+            let ciphertext = (self.oracle)(&input);
+            for i in 0..(ciphertext.len() / b).saturating_sub(1) {
+                if ciphertext[i*b..(i+1)*b] == self.marker[..] && ciphertext[(i+1)*b..(i+2)*b] == self.marker[..] {
+                    self.filler_length = filler_length;
+                    return ciphertext[(i+3)*b..].to_vec();
+                }
+            }
+        }
+
+        panic!("Could not align oracle input to a block boundary.");
+    }
+}
+
+///Finds the block size of an encryption oracle as the smallest jump in ciphertext length as the input grows
+pub fn detect_block_size<F: Fn(&[u8]) -> Vec<u8>>(oracle: &F) -> usize {
+    let mut lengths: Vec<usize> = (0..=MAX_BLOCK_SIZE * 2).map(|i| oracle(&vec![FILLER_BYTE; i]).len()).collect();
+    lengths.sort();
+    lengths.dedup();
+
+    return match lengths.windows(2).map(|w| w[1] - w[0]).min() {
+        None => panic!("Oracle ciphertext length does not depend on input length."),
+        Some(block_size) => block_size
+    };
+}
+
+///Recovers the secret suffix appended by an ECB encryption oracle, one byte at a time.
+///The oracle may prepend a prefix of fixed or random length. Block size and suffix length are detected automatically.
+///Will panic if the oracle does not appear to use ECB mode.
+pub fn ecb_byte_at_a_time<F: Fn(&[u8]) -> Vec<u8>>(oracle: F) -> Vec<u8> {
+    let block_size = detect_block_size(&oracle);
+    let mut aligned = AlignedOracle::new(oracle, block_size);
+
+    //Find the suffix length from how much input is needed to add a block to the output
+    let base_length = aligned.query(&vec![]).len();
+    let mut suffix_length = 0;
+    for i in 1..=block_size {
+        if aligned.query(&vec![FILLER_BYTE; i]).len() > base_length {
+            suffix_length = base_length - i;
+            break;
+        }
+    }
+
+    //Shift each byte of the suffix to the end of a block, then match it against a block for every possible value.
+    //All 256 guesses are sent in a single query, ahead of the shifted suffix.
+    let mut suffix: Vec<u8> = vec![];
+    for i in 0..suffix_length {
+        let mut known = vec![FILLER_BYTE; block_size - 1];
+        known.extend_from_slice(&suffix);
+        let known = known[known.len() - (block_size - 1)..].to_vec();
+
+        let mut payload: Vec<u8> = vec![];
+        for byte in 0..=255 {
+            payload.extend_from_slice(&known);
+            payload.push(byte);
+        }
+        payload.append(&mut vec![FILLER_BYTE; block_size - 1 - (i % block_size)]);
+
+        let ciphertext = aligned.query(&payload);
+        let blocks: Vec<&[u8]> = ciphertext.chunks(block_size).collect();
+        let target = blocks[256 + i / block_size];
+        match blocks[0..256].iter().position(|block| *block == target) {
+            None => panic!("Could not find suffix byte {}.", i),
+            Some(byte) => suffix.push(byte as u8)
+        }
+    }
+
+    return suffix;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::random;
+    use crate::aes::{encrypt_ecb, encrypt_cbc};
+    use crate::converter::ascii_to_bytes;
+    use crate::hash::Hash;
+    use crate::padding::pkcs7_pad;
+
+    static KEY: [u8; 16] = [0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c];
+    static SECRET: &str = "Byte-at-a-time decryption works with any prefix, as long as the mode is ECB.";
+
+    ///Encrypts prefix || message || secret with AES-ECB
+    fn aes_oracle(prefix: &[u8], message: &[u8]) -> Vec<u8> {
+        let mut plaintext = prefix.to_vec();
+        plaintext.extend_from_slice(message);
+        plaintext.append(&mut ascii_to_bytes(SECRET));
+        return encrypt_ecb(&pkcs7_pad(&plaintext, 16), &KEY.to_vec());
+    }
+
+    ///Toy 8 byte block "cipher" in ECB mode. It cannot be decrypted, but the attack never needs to.
+    fn toy_oracle(message: &[u8]) -> Vec<u8> {
+        let mut plaintext = ascii_to_bytes("prefix");
+        plaintext.extend_from_slice(message);
+        plaintext.append(&mut ascii_to_bytes(SECRET));
+        let mut ciphertext = vec![];
+        for block in pkcs7_pad(&plaintext, 8).chunks(8) {
+            let mut input = KEY.to_vec();
+            input.extend_from_slice(block);
+            ciphertext.extend_from_slice(&Hash::SHA1.digest(&input)[0..8]);
+        }
+        return ciphertext;
+    }
+
+    #[test]
+    fn test_detect_block_size() {
+        assert_eq!(detect_block_size(&|m: &[u8]| aes_oracle(&[], m)), 16);
+        assert_eq!(detect_block_size(&toy_oracle), 8);
+    }
+
+    #[test]
+    fn test_no_prefix() {
+        assert_eq!(ecb_byte_at_a_time(|m: &[u8]| aes_oracle(&[], m)), ascii_to_bytes(SECRET));
+    }
+
+    #[test]
+    fn test_fixed_prefix() {
+        let prefix = ascii_to_bytes("A fixed prefix which is longer than a block");
+        assert_eq!(ecb_byte_at_a_time(|m: &[u8]| aes_oracle(&prefix, m)), ascii_to_bytes(SECRET));
+    }
+
+    #[test]
+    fn test_random_prefix() {
+        let oracle = |m: &[u8]| {
+            let prefix: Vec<u8> = (0..random::<usize>() % 40).map(|_| random()).collect();
+            aes_oracle(&prefix, m)
+        };
+        assert_eq!(ecb_byte_at_a_time(oracle), ascii_to_bytes(SECRET));
+    }
+
+    #[test]
+    fn test_block_size_8() {
+        assert_eq!(ecb_byte_at_a_time(toy_oracle), ascii_to_bytes(SECRET));
+    }
+
+    #[test]
+    #[should_panic(expected="Oracle does not appear to use ECB mode.")]
+    fn test_not_ecb() {
+        ecb_byte_at_a_time(|m: &[u8]| encrypt_cbc(&pkcs7_pad(&m.to_vec(), 16), &KEY.to_vec(), &KEY.to_vec()));
+    }
+}
\ No newline at end of file
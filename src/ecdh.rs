@@ -0,0 +1,180 @@
+use gmp::mpz::Mpz;
+use crate::converter::hex_to_bytes;
+use crate::ec::{Curve, MontgomeryCurve, Point, CRYPTOPALS_CURVE, CRYPTOPALS_MONTGOMERY_CURVE};
+use crate::hash::Hash;
+use crate::mac::{create_prefix_mac, MAC};
+
+pub struct ECDH {
+    pub curve: Curve,
+    pub public_key: Point,
+    pub private_key: Mpz,
+    s: Point,
+    mac_key: Vec<u8>
+}
+
+///Derives a symmetric key from a shared point. Both coordinates are hashed so that
+///k*P and -k*P (which share an x coordinate) give different keys.
+pub fn derive_key(shared: &Point) -> Vec<u8> {
+    let mut bytes: Vec<u8> = vec![];
+    if let Point::Affine(x, y) = shared {
+        bytes.append(&mut hex_to_bytes(&x.to_str_radix(16)));
+        bytes.append(&mut hex_to_bytes(&y.to_str_radix(16)));
+    }
+    return Hash::SHA1.digest(&bytes)[0..16].to_vec();
+}
+
+///Derives a symmetric key from the u coordinate of a shared Montgomery point
+pub fn derive_key_x_only(shared: &Mpz) -> Vec<u8> {
+    return Hash::SHA1.digest(&hex_to_bytes(&shared.to_str_radix(16)))[0..16].to_vec();
+}
+
+impl ECDH {
+    ///Generates a new ECDH struct with a random keypair on the cryptopals set 8 curve
+    pub fn new() -> ECDH {
+        return ECDH::new_from_curve(&CRYPTOPALS_CURVE);
+    }
+
+    ///Generates a new ECDH struct with a random keypair on the given curve
+    pub fn new_from_curve(curve: &Curve) -> ECDH {
+        let private_key = curve.random_scalar();
+        return ECDH {
+            curve: curve.clone(),
+            public_key: curve.scalar_mul(&curve.g, &private_key),
+            private_key,
+            s: Point::Infinity,
+            mac_key: vec![]
+        };
+    }
+
+    ///Generates a new ECDH struct with the given public key
+    pub fn new_from_public_key(curve: &Curve, public_key: &Point) -> ECDH {
+        return ECDH {
+            curve: curve.clone(),
+            public_key: public_key.clone(),
+            private_key: Mpz::zero(),
+            s: Point::Infinity,
+            mac_key: vec![]
+        };
+    }
+
+    ///Perform ECDH key exchange with another instance.
+    ///Only p and a are checked, since the group law never uses b - received points are not validated.
+    pub fn exchange_keys(&mut self, other: &mut ECDH) {
+        if self.curve.p != other.curve.p || self.curve.a != other.curve.a {
+            panic!("Tried to perform an ECDH exchange with incompatible parameters");
+        }
+
+        self.s = self.curve.scalar_mul(&other.public_key, &self.private_key);
+        other.s = other.curve.scalar_mul(&self.public_key, &other.private_key);
+
+        self.mac_key = derive_key(&self.s);
+        other.mac_key = derive_key(&other.s);
+    }
+
+    ///Generate a prefix MAC using the generated session key
+    pub fn sign_message(&self, message: &Vec<u8>) -> MAC {
+        return create_prefix_mac(&message, &self.mac_key, Hash::SHA256);
+    }
+}
+
+///X-only ECDH over a Montgomery curve. Public keys are bare u coordinates, so nothing
+///checks whether a received u lies on the curve or on its twist.
+pub struct MontgomeryECDH {
+    pub curve: MontgomeryCurve,
+    pub public_key: Mpz,
+    pub private_key: Mpz,
+    s: Mpz,
+    mac_key: Vec<u8>
+}
+
+impl MontgomeryECDH {
+    ///Generates a new x-only ECDH struct with a random keypair on the cryptopals Montgomery curve
+    pub fn new() -> MontgomeryECDH {
+        let curve = CRYPTOPALS_MONTGOMERY_CURVE.clone();
+        let private_key = curve.random_scalar();
+        return MontgomeryECDH {
+            public_key: curve.ladder(&curve.base_u(), &private_key),
+            curve,
+            private_key,
+            s: Mpz::zero(),
+            mac_key: vec![]
+        };
+    }
+
+    ///Generates a new x-only ECDH struct with the given public key
+    pub fn new_from_public_key(curve: &MontgomeryCurve, public_key: &Mpz) -> MontgomeryECDH {
+        return MontgomeryECDH {
+            curve: curve.clone(),
+            public_key: public_key.clone(),
+            private_key: Mpz::zero(),
+            s: Mpz::zero(),
+            mac_key: vec![]
+        };
+    }
+
+    ///Perform x-only ECDH key exchange with another instance
+    pub fn exchange_keys(&mut self, other: &mut MontgomeryECDH) {
+        if self.curve.p != other.curve.p || self.curve.a != other.curve.a {
+            panic!("Tried to perform an ECDH exchange with incompatible parameters");
+        }
+
+        self.s = self.curve.ladder(&other.public_key, &self.private_key);
+        other.s = other.curve.ladder(&self.public_key, &other.private_key);
+
+        self.mac_key = derive_key_x_only(&self.s);
+        other.mac_key = derive_key_x_only(&other.s);
+    }
+
+    ///Generate a prefix MAC using the generated session key
+    pub fn sign_message(&self, message: &Vec<u8>) -> MAC {
+        return create_prefix_mac(&message, &self.mac_key, Hash::SHA256);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ec::P256;
+
+    #[test]
+    fn test_new() {
+        let ecdh = ECDH::new();
+
+        assert_eq!(ecdh.curve, *CRYPTOPALS_CURVE);
+        assert_ne!(ecdh.private_key, Mpz::zero());
+        assert!(ecdh.curve.validate_point(&ecdh.public_key));
+    }
+
+    #[test]
+    fn test_key_exchange() {
+        let mut ecdh1 = ECDH::new();
+        let mut ecdh2 = ECDH::new();
+
+        ecdh1.exchange_keys(&mut ecdh2);
+
+        assert_eq!(ecdh1.s, ecdh2.s);
+        assert_ne!(ecdh1.s, Point::Infinity);
+        assert_eq!(ecdh1.mac_key, ecdh2.mac_key);
+    }
+
+    #[test]
+    #[should_panic(expected="Tried to perform an ECDH exchange with incompatible parameters")]
+    fn test_key_exchange_mismatch_params() {
+        let mut ecdh1 = ECDH::new_from_curve(&P256);
+        let mut ecdh2 = ECDH::new();
+
+        ecdh1.exchange_keys(&mut ecdh2);
+    }
+
+    #[test]
+    fn test_montgomery_key_exchange() {
+        let mut ecdh1 = MontgomeryECDH::new();
+        let mut ecdh2 = MontgomeryECDH::new();
+
+        ecdh1.exchange_keys(&mut ecdh2);
+
+        assert_eq!(ecdh1.s, ecdh2.s);
+        assert_ne!(ecdh1.s, Mpz::zero());
+        assert_eq!(ecdh1.mac_key, ecdh2.mac_key);
+    }
+}
\ No newline at end of file
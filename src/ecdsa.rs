@@ -0,0 +1,182 @@
+use gmp::mpz::Mpz;
+use crate::ec::{Curve, Point, CRYPTOPALS_CURVE};
+use crate::hash::Hash;
+
+pub struct ECDSA {
+    pub d: Mpz,
+    pub q: Point,
+    pub curve: Curve
+}
+
+pub struct ECDSASignature {
+    pub message: Vec<u8>,
+    pub r: Mpz,
+    pub s: Mpz,
+    pub curve: Curve
+}
+
+///Hashes the message with SHA256 and keeps the leftmost bits, up to the bit length of n
+pub fn hash_message(message: &Vec<u8>, n: &Mpz) -> Mpz {
+    let h = Mpz::from(&Hash::SHA256.digest(message)[0..]);
+    let excess = 256 - std::cmp::min(256, n.bit_length());
+    return h >> excess;
+}
+
+impl ECDSA {
+    ///Creates a new ECDSA instance on the cryptopals set 8 curve
+    pub fn new() -> ECDSA {
+        return ECDSA::new_from_curve(&CRYPTOPALS_CURVE);
+    }
+
+    ///Creates a new ECDSA instance on the given curve
+    pub fn new_from_curve(curve: &Curve) -> ECDSA {
+        let d = curve.random_scalar();
+        return ECDSA {
+            q: curve.scalar_mul(&curve.g, &d),
+            d,
+            curve: curve.clone()
+        };
+    }
+
+    ///Creates an ECDSA instance from an existing keypair
+    pub fn new_from_keys(curve: &Curve, d: &Mpz, q: &Point) -> ECDSA {
+        return ECDSA {
+            d: d.clone(),
+            q: q.clone(),
+            curve: curve.clone()
+        };
+    }
+
+    ///Signs the given message using this instance's private key
+    pub fn sign_message(&self, message: &Vec<u8>) -> ECDSASignature {
+        //Generate random k mod n. If k does not give a valid signature, retry with different k.
+        let k = self.curve.random_scalar();
+        return match self.sign_message_with_nonce(message, &k) {
+            None => self.sign_message(message),
+            Some(signature) => signature
+        };
+    }
+
+    ///Signs the given message using the given nonce k.
+    ///Returns None if k does not produce a valid signature.
+    pub fn sign_message_with_nonce(&self, message: &Vec<u8>, k: &Mpz) -> Option<ECDSASignature> {
+        //Compute r = x(k*G) mod n, which must be nonzero
+        let n = &self.curve.n;
+        let r = match self.curve.scalar_mul(&self.curve.g, k) {
+            Point::Infinity => return None,
+            Point::Affine(x, _) => x.modulus(n)
+        };
+        if r == Mpz::zero() {
+            return None;
+        }
+
+        //Calculate s = k^-1 * (hash(message) + d*r) mod n, which must also be nonzero
+        let h = hash_message(message, n);
+        let k_inv = match k.invert(n) {
+            None => return None,
+            Some(k_inv) => k_inv
+        };
+        let s = (k_inv * (&h + &self.d * &r)).modulus(n);
+        if s == Mpz::zero() {
+            return None;
+        }
+
+        return Some(ECDSASignature {
+            message: message.clone(),
+            r,
+            s,
+            curve: self.curve.clone()
+        });
+    }
+
+    ///Verifies the message using the signature's curve parameters and this instance's public key
+    pub fn verify_signature(&self, signature: &ECDSASignature) -> bool {
+        let curve = &signature.curve;
+        let n = &curve.n;
+
+        //Validate r, s
+        if signature.r <= Mpz::zero() || signature.s <= Mpz::zero() || &signature.r >= n || &signature.s >= n {
+            return false;
+        }
+
+        let h = hash_message(&signature.message, n);
+        return match signature.s.invert(n) {
+            None => false,
+            Some(w) => {
+                let u1 = (&h * &w).modulus(n);
+                let u2 = (&signature.r * &w).modulus(n);
+                match curve.add(&curve.scalar_mul(&curve.g, &u1), &curve.scalar_mul(&self.q, &u2)) {
+                    Point::Infinity => false,
+                    Point::Affine(x, _) => x.modulus(n) == signature.r
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::ascii_to_bytes;
+    use crate::ec::P256;
+
+    #[test]
+    fn test_sign_verify() {
+        let e = ECDSA::new();
+        let message = ascii_to_bytes("TEST MESSAGE: for ECDSA signature and verification");
+        let mut signature = e.sign_message(&message);
+        assert!(e.verify_signature(&signature));
+        signature.r += Mpz::one();
+        assert!(!e.verify_signature(&signature));
+        signature.r -= Mpz::one();
+        signature.s -= Mpz::one();
+        assert!(!e.verify_signature(&signature));
+    }
+
+    #[test]
+    fn test_sign_verify_p256() {
+        let e = ECDSA::new_from_curve(&P256);
+        let message = ascii_to_bytes("TEST MESSAGE: for ECDSA signature and verification");
+        let signature = e.sign_message(&message);
+        assert!(e.verify_signature(&signature));
+        assert!(!ECDSA::new_from_curve(&P256).verify_signature(&signature));
+    }
+
+    #[test]
+    fn test_sign_message_with_nonce() {
+        let e = ECDSA::new();
+        let message = ascii_to_bytes("TEST MESSAGE: for ECDSA signature and verification");
+        let k = Mpz::from(12345);
+        let signature = e.sign_message_with_nonce(&message, &k).unwrap();
+
+        assert!(e.verify_signature(&signature));
+        match e.curve.scalar_mul(&e.curve.g, &k) {
+            Point::Infinity => panic!("Nonce should not give the point at infinity"),
+            Point::Affine(x, _) => assert_eq!(x.modulus(&e.curve.n), signature.r)
+        }
+        assert!(e.sign_message_with_nonce(&message, &Mpz::zero()).is_none());
+    }
+
+    #[test]
+    fn test_new() {
+        let e = ECDSA::new();
+        assert_eq!(e.curve, *CRYPTOPALS_CURVE);
+        assert_eq!(e.q, e.curve.scalar_mul(&e.curve.g, &e.d));
+    }
+
+    #[test]
+    fn test_verify_known_signature() {
+        //P-256 signature of "sample" with SHA-256, made with Python cryptography 48.0.0 (ec.ECDSA(SHA256()))
+        let d = Mpz::from_str_radix("c51e4753afdec1e6b6c6a5b992f43f8dd0c7a8933072708b6522468b2ffb06fd", 16).unwrap();
+        let e = ECDSA::new_from_keys(&P256, &d, &P256.scalar_mul(&P256.g, &d));
+        let mut signature = ECDSASignature {
+            message: ascii_to_bytes("sample"),
+            r: Mpz::from_str_radix("ee0aee245713f31497f13c2ec32f19cf06471253f900c60c0b200bddcffb9cdf", 16).unwrap(),
+            s: Mpz::from_str_radix("8c53fc6d8dddf700212cac9826e7ac9a0dc85c0cf4551dac7c6a4b2a525409e9", 16).unwrap(),
+            curve: P256.clone()
+        };
+        assert!(e.verify_signature(&signature));
+        signature.message = ascii_to_bytes("sample!");
+        assert!(!e.verify_signature(&signature));
+    }
+}
\ No newline at end of file
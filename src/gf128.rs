@@ -0,0 +1,220 @@
+use std::ops::{Add, Sub, Mul, Div};
+use rand::random;
+use crate::bit_matrix::BitMatrix;
+
+///Reduction constant for x^128 + x^7 + x^2 + x + 1, in GCM bit order
+const R: u128 = 0xe1 << 120;
+
+///Element of GF(2^128) under the GCM polynomial x^128 + x^7 + x^2 + x + 1.
+///Bits are stored in GCM order, so the most significant bit is the coefficient of x^0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GF128(pub u128);
+
+impl GF128 {
+    ///Returns the additive identity
+    pub fn zero() -> GF128 {
+        return GF128(0);
+    }
+
+    ///Returns the multiplicative identity
+    pub fn one() -> GF128 {
+        return GF128(1 << 127);
+    }
+
+    ///Returns a uniformly random field element
+    pub fn random() -> GF128 {
+        return GF128(random());
+    }
+
+    ///Reads a field element from a block of up to 16 bytes, zero-padding short blocks on the right.
+    ///Will panic if the block is longer than 16 bytes.
+    pub fn from_bytes(block: &[u8]) -> GF128 {
+        if block.len() > 16 {
+            panic!("Block of length {} is too long for a GF(2^128) element!", block.len());
+        }
+
+        let mut bytes = [0 as u8; 16];
+        bytes[0..block.len()].copy_from_slice(block);
+        return GF128(u128::from_be_bytes(bytes));
+    }
+
+    ///Converts the field element into its 16-byte block representation
+    pub fn to_bytes(&self) -> Vec<u8> {
+        return self.0.to_be_bytes().to_vec();
+    }
+
+    ///Converts the element into a bit vector, where entry i is the coefficient of x^i
+    pub fn to_bits(&self) -> Vec<bool> {
+        return (0..128).map(|i| (self.0 >> (127 - i)) & 1 == 1).collect();
+    }
+
+    ///Reads an element from a 128-entry bit vector, where entry i is the coefficient of x^i.
+    ///Will panic if the vector does not have 128 entries.
+    pub fn from_bits(bits: &Vec<bool>) -> GF128 {
+        if bits.len() != 128 {
+            panic!("Bit vector of length {} passed as a GF(2^128) element!", bits.len());
+        }
+
+        let mut value: u128 = 0;
+        for i in 0..128 {
+            if bits[i] {
+                value |= 1 << (127 - i);
+            }
+        }
+        return GF128(value);
+    }
+
+    ///Returns the 128x128 GF(2) matrix M with M * bits(y) = bits(self * y)
+    pub fn multiplication_matrix(&self) -> BitMatrix {
+        let columns = (0..128).map(|j| (*self * GF128(1 << (127 - j))).to_bits()).collect();
+        return BitMatrix::from_columns(&columns);
+    }
+
+    ///Returns the 128x128 GF(2) matrix S with S * bits(y) = bits(y^2).
+    ///Squaring is linear over GF(2) since (a + b)^2 = a^2 + b^2 in characteristic 2.
+    pub fn squaring_matrix() -> BitMatrix {
+        let columns = (0..128).map(|j| GF128(1 << (127 - j)).pow(2).to_bits()).collect();
+        return BitMatrix::from_columns(&columns);
+    }
+
+    ///Returns true if this is the zero element
+    pub fn is_zero(&self) -> bool {
+        return self.0 == 0;
+    }
+
+    ///Computes self^exponent by square-and-multiply
+    pub fn pow(&self, exponent: u128) -> GF128 {
+        let mut result = GF128::one();
+        let mut base = *self;
+        let mut e = exponent;
+
+        while e > 0 {
+            if e & 1 == 1 {
+                result = result * base;
+            }
+            base = base * base;
+            e >>= 1;
+        }
+
+        return result;
+    }
+
+    ///Computes the multiplicative inverse as self^(2^128 - 2).
+    ///Will panic if called on zero.
+    pub fn inverse(&self) -> GF128 {
+        if self.is_zero() {
+            panic!("Zero has no inverse in GF(2^128)!");
+        }
+        return self.pow(u128::MAX - 1);
+    }
+}
+
+///Addition is XOR in characteristic 2
+impl Add for GF128 {
+    type Output = GF128;
+
+    fn add(self, other: GF128) -> GF128 {
+        return GF128(self.0 ^ other.0);
+    }
+}
+
+///Subtraction is identical to addition in characteristic 2
+impl Sub for GF128 {
+    type Output = GF128;
+
+    fn sub(self, other: GF128) -> GF128 {
+        return GF128(self.0 ^ other.0);
+    }
+}
+
+///Carryless multiplication modulo the GCM polynomial (NIST SP 800-38D, algorithm 1)
+impl Mul for GF128 {
+    type Output = GF128;
+
+    fn mul(self, other: GF128) -> GF128 {
+        let mut z: u128 = 0;
+        let mut v: u128 = other.0;
+
+        for i in 0..128 {
+            if (self.0 >> (127 - i)) & 1 == 1 {
+                z ^= v;
+            }
+            v = if v & 1 == 1 {(v >> 1) ^ R} else {v >> 1};
+        }
+
+        return GF128(z);
+    }
+}
+
+///Division by multiplying with the inverse. Will panic if dividing by zero.
+impl Div for GF128 {
+    type Output = GF128;
+
+    fn div(self, other: GF128) -> GF128 {
+        return self * other.inverse();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::hex_to_bytes;
+
+    #[test]
+    fn test_from_to_bytes() {
+        let bytes = hex_to_bytes("66e94bd4ef8a2c3b884cfa59ca342b2e");
+        assert_eq!(GF128::from_bytes(&bytes).to_bytes(), bytes);
+        assert_eq!(GF128::from_bytes(&[0x80]), GF128::one());
+    }
+
+    #[test]
+    #[should_panic(expected="Block of length 17 is too long for a GF(2^128) element!")]
+    fn test_from_bytes_too_long() {
+        GF128::from_bytes(&[0; 17]);
+    }
+
+    #[test]
+    fn test_mul() {
+        //x * x^127 wraps around to x^7 + x^2 + x + 1
+        let x = GF128(1 << 126);
+        let x127 = GF128(1);
+        assert_eq!(x * x127, GF128(R));
+
+        //Known product from the GCM specification (test case 2: H * C)
+        let h = GF128::from_bytes(&hex_to_bytes("66e94bd4ef8a2c3b884cfa59ca342b2e"));
+        let c = GF128::from_bytes(&hex_to_bytes("0388dace60b6a392f328c2b971b2fe78"));
+        assert_eq!((h * c).to_bytes(), hex_to_bytes("5e2ec746917062882c85b0685353deb7"));
+        assert_eq!(h * c, c * h);
+        assert_eq!(h * GF128::one(), h);
+    }
+
+    #[test]
+    fn test_to_from_bits() {
+        let a = GF128::random();
+        let bits = GF128::one().to_bits();
+        assert!(bits[0]);
+        assert_eq!(bits.iter().filter(|b| **b).count(), 1);
+        assert_eq!(GF128::from_bits(&a.to_bits()), a);
+    }
+
+    #[test]
+    fn test_linear_maps() {
+        let a = GF128::random();
+        let b = GF128::random();
+        assert_eq!(GF128::from_bits(&a.multiplication_matrix().mul_vector(&b.to_bits())), a * b);
+        assert_eq!(GF128::from_bits(&GF128::squaring_matrix().mul_vector(&b.to_bits())), b * b);
+    }
+
+    #[test]
+    fn test_inverse() {
+        let a = GF128::from_bytes(&hex_to_bytes("feffe9928665731c6d6a8f9467308308"));
+        assert_eq!(a * a.inverse(), GF128::one());
+        assert_eq!((a / a), GF128::one());
+    }
+
+    #[test]
+    #[should_panic(expected="Zero has no inverse in GF(2^128)!")]
+    fn test_inverse_zero() {
+        GF128::zero().inverse();
+    }
+}
@@ -7,11 +7,111 @@ lazy_static! {
     static ref BAD_HASH_24_KEY: Vec<u8> = ascii_to_bytes("MURDEROUS PICKLE");
 }
 
+///SHA-1 initial state
+const SHA1_INIT: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+///SHA-256 initial state
+const SHA256_INIT: [u32; 8] = [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19];
+
+///SHA-256 round constants
+const SHA256_K: [u32; 64] =
+    [0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2];
+
+///SHA-224 initial state
+const SHA224_INIT: [u32; 8] = [0xc1059ed8, 0x367cd507, 0x3070dd17, 0xf70e5939, 0xffc00b31, 0x68581511, 0x64f98fa7, 0xbefa4fa4];
+
+///SHA-384 initial state
+const SHA384_INIT: [u64; 8] = [0xcbbb9d5dc1059ed8, 0x629a292a367cd507, 0x9159015a3070dd17, 0x152fecd8f70e5939,
+    0x67332667ffc00b31, 0x8eb44a8768581511, 0xdb0c2e0d64f98fa7, 0x47b5481dbefa4fa4];
+
+///SHA-512 initial state
+const SHA512_INIT: [u64; 8] = [0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+    0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179];
+
+///SHA-512/256 initial state
+const SHA512_256_INIT: [u64; 8] = [0x22312194fc2bf72c, 0x9f555fa3c84c64c2, 0x2393b86b6f53b151, 0x963877195940eabd,
+    0x96283ee2a88effe3, 0xbe5e1e2553863992, 0x2b0199fc2c85b8aa, 0x0eb72ddc81c52ca2];
+
+///SHA-512 round constants
+const SHA512_K: [u64; 80] =
+    [0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+    0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+    0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+    0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+    0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+    0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+    0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+    0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+    0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817];
+
+///MD4 initial state, which MD5 shares
+const MD4_INIT: [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+
+///MD5 round constants, the integer parts of 2^32 * |sin(i + 1)|
+const MD5_K: [u32; 64] =
+    [0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391];
+
+///Keccak-f[1600] round constants
+const KECCAK_RC: [u64; 24] =
+    [0x0000000000000001, 0x0000000000008082, 0x800000000000808a, 0x8000000080008000,
+    0x000000000000808b, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008a, 0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800a, 0x800000008000000a,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008];
+
+///Keccak-f[1600] rotation offsets, indexed by lane x + 5y
+const KECCAK_ROTATIONS: [u32; 25] =
+    [0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14];
+
+///MD5 per-round left rotation amounts
+const MD5_SHIFTS: [[u32; 4]; 4] = [[7, 12, 17, 22], [5, 9, 14, 20], [4, 11, 16, 23], [6, 10, 15, 21]];
+
 ///Enum of all implemented hash functions
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[allow(non_camel_case_types)]
 pub enum Hash {
     SHA1,
+    SHA224,
     SHA256,
+    SHA384,
+    SHA512,
+    SHA512_256,
     MD4,
+    MD5,
+    SHA3_224,
+    SHA3_256,
+    SHA3_384,
+    SHA3_512,
+    ///Extendable output function, with the given output length in bytes
+    SHAKE128(usize),
+    ///Extendable output function, with the given output length in bytes
+    SHAKE256(usize),
     BAD16,
     BAD24
 }
@@ -19,20 +119,32 @@ pub enum Hash {
 ///Generic implementation which calls the appropriate digest method depending on the hash enum
 impl Hash {
     pub fn digest(&self, message: &Vec<u8>) -> Vec<u8> {
-        return match self {
-            Hash::SHA1 => digest_sha1(&message),
-            Hash::SHA256 => digest_sha256(&message),
-            Hash::MD4 => digest_md4(&message),
-            Hash::BAD16 => digest_bad_hash_16(&message),
-            Hash::BAD24 => digest_bad_hash_24(&message)
-        }
+        let mut hasher = self.hasher();
+        hasher.update(message);
+        return hasher.finalize();
+    }
+
+    ///Creates an incremental hasher for this hash function
+    pub fn hasher(&self) -> Hasher {
+        return Hasher::new(*self);
     }
 
     pub fn block_length(&self) -> usize {
         return match self {
             Hash::SHA1 => 64,
+            Hash::SHA224 => 64,
             Hash::SHA256 => 64,
+            Hash::SHA384 => 128,
+            Hash::SHA512 => 128,
+            Hash::SHA512_256 => 128,
             Hash::MD4 => 64,
+            Hash::MD5 => 64,
+            Hash::SHA3_224 => 144,
+            Hash::SHA3_256 => 136,
+            Hash::SHA3_384 => 104,
+            Hash::SHA3_512 => 72,
+            Hash::SHAKE128(_) => 168,
+            Hash::SHAKE256(_) => 136,
             Hash::BAD16 => 16,
             Hash::BAD24 => 16
         }
@@ -41,112 +153,379 @@ impl Hash {
     pub fn hash_length(&self) -> usize {
         return match self {
             Hash::SHA1 => 20,
+            Hash::SHA224 => 28,
             Hash::SHA256 => 32,
+            Hash::SHA384 => 48,
+            Hash::SHA512 => 64,
+            Hash::SHA512_256 => 32,
             Hash::MD4 => 16,
+            Hash::MD5 => 16,
+            Hash::SHA3_224 => 28,
+            Hash::SHA3_256 => 32,
+            Hash::SHA3_384 => 48,
+            Hash::SHA3_512 => 64,
+            Hash::SHAKE128(length) => *length,
+            Hash::SHAKE256(length) => *length,
             Hash::BAD16 => 2,
             Hash::BAD24 => 3
         }
     }
+
+    ///Returns the padding appended to a message of the given length (in bytes) before its final blocks are hashed
+    pub fn padding(&self, message_length: u64) -> Vec<u8> {
+        //Padding only depends on the length, so it is built after however many bytes are left in the last block
+        let offset = (message_length % self.block_length() as u64) as usize;
+        let mut padding = vec![];
+        let bit_length = message_length.wrapping_mul(8);
+        match self {
+            Hash::SHA1 | Hash::SHA224 | Hash::SHA256 | Hash::MD4 | Hash::MD5 => {
+                padding.push(0x80);
+                while (offset + padding.len()) % 64 != 56 {
+                    padding.push(0);
+                }
+                if *self == Hash::MD4 || *self == Hash::MD5 {
+                    padding.append(&mut bit_length.to_le_bytes().to_vec());
+                }
+                else {
+                    padding.append(&mut bit_length.to_be_bytes().to_vec());
+                }
+            },
+            Hash::SHA384 | Hash::SHA512 | Hash::SHA512_256 => {
+                //The SHA-512 family has 128-byte blocks and a 128-bit length field
+                padding.push(0x80);
+                while (offset + padding.len()) % 128 != 112 {
+                    padding.push(0);
+                }
+                padding.append(&mut (message_length as u128 * 8).to_be_bytes().to_vec());
+            },
+            Hash::SHA3_224 | Hash::SHA3_256 | Hash::SHA3_384 | Hash::SHA3_512 |
+            Hash::SHAKE128(_) | Hash::SHAKE256(_) => {
+                //Keccak pad10*1, with the domain separation bits for SHA-3 or SHAKE in front of it
+                let rate = self.block_length();
+                padding.push(match self {
+                    Hash::SHAKE128(_) | Hash::SHAKE256(_) => 0x1f,
+                    _ => 0x06
+                });
+                while (offset + padding.len()) % rate != 0 {
+                    padding.push(0);
+                }
+                *padding.last_mut().unwrap() |= 0x80;
+            },
+            Hash::BAD16 | Hash::BAD24 => {
+                while (offset + padding.len()) % 16 != 0 {
+                    padding.push(0x55);
+                }
+            }
+        }
+        return padding;
+    }
 }
 
-///Generates the SHA-1 digest of a message
-fn digest_sha1(message: &Vec<u8>) -> Vec<u8> {
-    //Call arbitrary-state function with the default SHA-1 initial state
-    digest_sha1_from_state(message, [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0], 0)
+///Intermediate state of an incremental hash, which can be exported and later resumed
+#[derive(Clone, Debug, PartialEq)]
+pub struct HashState {
+    ///Chaining value, laid out the same way as the digest
+    pub chaining_value: Vec<u8>,
+    ///Number of message bytes processed so far, including buffered ones
+    pub byte_count: u64,
+    ///Message bytes which do not fill a whole block yet
+    pub buffer: Vec<u8>
 }
 
-///Generates the SHA-1 digest of a message from some specific initial state
-pub fn digest_sha1_from_state(message: &Vec<u8>, h_init: [u32;5], total_length: u64) -> Vec<u8> {
-    let mut h: [u32;5] = h_init;
-    let ml: u64 = if total_length == 0 {(message.len() * 8) as u64} else {total_length};
+///Chaining value of a hash in progress, in the form its compression function works with
+#[derive(Clone)]
+enum ChainingValue {
+    SHA1([u32; 5]),
+    SHA256([u32; 8]),
+    SHA512([u64; 8]),
+    MD4([u32; 4]),
+    MD5([u32; 4]),
+    Keccak([u64; 25]),
+    Bad(Vec<u8>)
+}
 
-    //Pre-process message for digest
-    let mut processed_message = message.clone();
+///Incremental hasher, which digests a message in pieces instead of all at once
+pub struct Hasher {
+    hash: Hash,
+    chaining_value: ChainingValue,
+    byte_count: u64,
+    buffer: Vec<u8>
+}
 
-    processed_message.push(0x80);
-    while processed_message.len() % 64 != 56 {
-        processed_message.push(0);
+impl Hasher {
+    ///Creates a hasher for the given hash function in its standard initial state
+    pub fn new(hash: Hash) -> Hasher {
+        let chaining_value = match hash {
+            Hash::SHA1 => ChainingValue::SHA1(SHA1_INIT),
+            Hash::SHA224 => ChainingValue::SHA256(SHA224_INIT),
+            Hash::SHA256 => ChainingValue::SHA256(SHA256_INIT),
+            Hash::SHA384 => ChainingValue::SHA512(SHA384_INIT),
+            Hash::SHA512 => ChainingValue::SHA512(SHA512_INIT),
+            Hash::SHA512_256 => ChainingValue::SHA512(SHA512_256_INIT),
+            Hash::MD4 => ChainingValue::MD4(MD4_INIT),
+            Hash::MD5 => ChainingValue::MD5(MD4_INIT),
+            Hash::SHA3_224 | Hash::SHA3_256 | Hash::SHA3_384 | Hash::SHA3_512 |
+            Hash::SHAKE128(_) | Hash::SHAKE256(_) => ChainingValue::Keccak([0; 25]),
+            Hash::BAD16 => ChainingValue::Bad(vec![0xbe, 0xef]),
+            Hash::BAD24 => ChainingValue::Bad(vec![0xde, 0xad, 0xbe, 0xef])
+        };
+
+        return Hasher {
+            hash,
+            chaining_value,
+            byte_count: 0,
+            buffer: vec![]
+        };
     }
-    processed_message.append(&mut ml.to_be_bytes().to_vec());
 
-    //Digest each 64-byte chunk
-    for chunk in 0..processed_message.len() / 64 {
+    ///Creates a hasher which resumes from an exported state.
+    ///Will panic if the chaining value has the wrong length for the hash function,
+    ///or the buffer does not match the byte count.
+    pub fn import_state(hash: Hash, state: &HashState) -> Hasher {
+        let value = &state.chaining_value;
+        let words = |length: usize, little_endian: bool| -> Vec<u32> {
+            if value.len() != 4 * length {
+                panic!("Illegal chaining value length {} for this hash!", value.len());
+            }
+            return value.chunks(4).map(|w| {
+                let bytes = [w[0], w[1], w[2], w[3]];
+                if little_endian {u32::from_le_bytes(bytes)} else {u32::from_be_bytes(bytes)}
+            }).collect();
+        };
 
-        //Break into words
-        let mut w= [0 as u32; 80];
-        for i in 0..16 {
-            w[i] += (processed_message[64*chunk+4*i] as u32) << 24;
-            w[i] += (processed_message[64*chunk+4*i+1] as u32) << 16;
-            w[i] += (processed_message[64*chunk+4*i+2] as u32) << 8;
-            w[i] += processed_message[64*chunk+4*i+3] as u32;
-        }
+        let chaining_value = match hash {
+            Hash::SHA1 => {
+                let mut h = [0; 5];
+                h.copy_from_slice(&words(5, false));
+                ChainingValue::SHA1(h)
+            },
+            Hash::SHA224 | Hash::SHA256 => {
+                let mut h = [0; 8];
+                h.copy_from_slice(&words(8, false));
+                ChainingValue::SHA256(h)
+            },
+            Hash::SHA384 | Hash::SHA512 | Hash::SHA512_256 => {
+                //64-bit words are read as pairs of 32-bit big-endian halves
+                let halves = words(16, false);
+                let mut h = [0; 8];
+                for i in 0..8 {
+                    h[i] = ((halves[2*i] as u64) << 32) | halves[2*i+1] as u64;
+                }
+                ChainingValue::SHA512(h)
+            },
+            Hash::MD4 => {
+                let mut h = [0; 4];
+                h.copy_from_slice(&words(4, true));
+                ChainingValue::MD4(h)
+            },
+            Hash::MD5 => {
+                let mut h = [0; 4];
+                h.copy_from_slice(&words(4, true));
+                ChainingValue::MD5(h)
+            },
+            Hash::SHA3_224 | Hash::SHA3_256 | Hash::SHA3_384 | Hash::SHA3_512 |
+            Hash::SHAKE128(_) | Hash::SHAKE256(_) => {
+                //The whole sponge state is exported, as little-endian 64-bit lanes
+                let halves = words(50, true);
+                let mut a = [0; 25];
+                for i in 0..25 {
+                    a[i] = ((halves[2*i+1] as u64) << 32) | halves[2*i] as u64;
+                }
+                ChainingValue::Keccak(a)
+            },
+            Hash::BAD16 | Hash::BAD24 => ChainingValue::Bad(value.clone())
+        };
 
-        //Generate remaining words
-        for i in 16..80 {
-            w[i] = (w[i -3] ^ w[i -8] ^ w[i -14] ^ w[i -16]).rotate_left(1);
+        if state.buffer.len() as u64 != state.byte_count % hash.block_length() as u64 {
+            panic!("Buffer length {} does not match byte count {}!", state.buffer.len(), state.byte_count);
         }
 
-        let mut a = h[0];
-        let mut b = h[1];
-        let mut c = h[2];
-        let mut d = h[3];
-        let mut e = h[4];
-        let mut f: u32;
-        let mut k: u32;
-
-        //Perform hashing steps for this block
-        for i in 0..80 {
-            if i < 20 {
-                f = (b & c) | ((!b) & d);
-                k = 0x5A827999;
-            }
-            else if i < 40 {
-                f = b ^ c ^ d;
-                k = 0x6ED9EBA1;
-            }
-            else if i < 60 {
-                f = (b & c) | (b & d) | (c & d);
-                k = 0x8F1BBCDC;
-            }
-            else {
-                f = b ^ c ^ d;
-                k = 0xCA62C1D6;
+        return Hasher {
+            hash,
+            chaining_value,
+            byte_count: state.byte_count,
+            buffer: state.buffer.clone()
+        };
+    }
+
+    ///Exports the current state, so that hashing can be resumed later with import_state
+    pub fn export_state(&self) -> HashState {
+        return HashState {
+            chaining_value: self.chaining_value_bytes(&self.chaining_value),
+            byte_count: self.byte_count,
+            buffer: self.buffer.clone()
+        };
+    }
+
+    ///Adds more data to the message being hashed
+    pub fn update(&mut self, data: &[u8]) {
+        let block_length = self.hash.block_length();
+        self.byte_count += data.len() as u64;
+
+        //Top up any buffered partial block first, then compress whole blocks straight from the input
+        let mut data = data;
+        if self.buffer.len() > 0 {
+            let needed = std::cmp::min(block_length - self.buffer.len(), data.len());
+            self.buffer.extend_from_slice(&data[0..needed]);
+            data = &data[needed..];
+            if self.buffer.len() < block_length {
+                return;
             }
 
-            let tmp = (a.rotate_left(5)).overflowing_add(f).0.overflowing_add(e).0.overflowing_add(k).0.overflowing_add(w[i]).0;
-            e = d;
-            d = c;
-            c = b.rotate_left(30);
-            b = a;
-            a = tmp;
+            let block = std::mem::replace(&mut self.buffer, vec![]);
+            self.chaining_value = compress(self.hash, &self.chaining_value, &block);
+        }
+
+        let whole_blocks = data.len() / block_length;
+        for block in data[0..whole_blocks * block_length].chunks(block_length) {
+            self.chaining_value = compress(self.hash, &self.chaining_value, block);
+        }
+        self.buffer.extend_from_slice(&data[whole_blocks * block_length..]);
+    }
+
+    ///Pads the message and returns its digest. The hasher itself is left unchanged.
+    pub fn finalize(&self) -> Vec<u8> {
+        return self.finalize_with_padding(self.hash.padding(self.byte_count));
+    }
+
+    ///Appends the given padding to the message and returns its digest
+    fn finalize_with_padding(&self, mut padding: Vec<u8>) -> Vec<u8> {
+        let mut padded = self.buffer.clone();
+        padded.append(&mut padding);
+
+        let mut chaining_value = self.chaining_value.clone();
+        for block in padded.chunks(self.hash.block_length()) {
+            chaining_value = compress(self.hash, &chaining_value, block);
         }
 
-        h[0] = h[0].overflowing_add(a).0;
-        h[1] = h[1].overflowing_add(b).0;
-        h[2] = h[2].overflowing_add(c).0;
-        h[3] = h[3].overflowing_add(d).0;
-        h[4] = h[4].overflowing_add(e).0;
+        //Sponge constructions squeeze out their output, and truncated variants only output the start of the chaining value
+        let digest = match &chaining_value {
+            ChainingValue::Keccak(a) => squeeze_keccak(a, self.hash.block_length(), self.hash.hash_length()),
+            _ => self.chaining_value_bytes(&chaining_value)
+        };
+        return match self.hash {
+            Hash::SHA224 | Hash::SHA384 | Hash::SHA512_256 => digest[0..self.hash.hash_length()].to_vec(),
+            _ => digest
+        };
     }
 
-    //Construct output digest
-    let mut hash = vec![];
-    for i in 0..5 {
-        hash.append(&mut h[i].to_be_bytes().to_vec());
+    ///Lays out a chaining value the same way as the digest
+    fn chaining_value_bytes(&self, chaining_value: &ChainingValue) -> Vec<u8> {
+        let mut bytes = vec![];
+        match chaining_value {
+            ChainingValue::SHA1(h) => h.iter().for_each(|w| bytes.extend_from_slice(&w.to_be_bytes())),
+            ChainingValue::SHA256(h) => h.iter().for_each(|w| bytes.extend_from_slice(&w.to_be_bytes())),
+            ChainingValue::SHA512(h) => h.iter().for_each(|w| bytes.extend_from_slice(&w.to_be_bytes())),
+            ChainingValue::MD4(h) => h.iter().for_each(|w| bytes.extend_from_slice(&w.to_le_bytes())),
+            ChainingValue::MD5(h) => h.iter().for_each(|w| bytes.extend_from_slice(&w.to_le_bytes())),
+            ChainingValue::Keccak(a) => a.iter().for_each(|w| bytes.extend_from_slice(&w.to_le_bytes())),
+            ChainingValue::Bad(h) => bytes = h.clone()
+        }
+        return bytes;
     }
+}
 
-    return hash;
+///Runs the compression function of the given hash on a single block
+fn compress(hash: Hash, chaining_value: &ChainingValue, block: &[u8]) -> ChainingValue {
+    return match (hash, chaining_value) {
+        (Hash::SHA1, ChainingValue::SHA1(h)) => ChainingValue::SHA1(compress_sha1(h, block)),
+        (Hash::SHA224, ChainingValue::SHA256(h)) => ChainingValue::SHA256(compress_sha256(h, block)),
+        (Hash::SHA256, ChainingValue::SHA256(h)) => ChainingValue::SHA256(compress_sha256(h, block)),
+        (Hash::SHA384, ChainingValue::SHA512(h)) => ChainingValue::SHA512(compress_sha512(h, block)),
+        (Hash::SHA512, ChainingValue::SHA512(h)) => ChainingValue::SHA512(compress_sha512(h, block)),
+        (Hash::SHA512_256, ChainingValue::SHA512(h)) => ChainingValue::SHA512(compress_sha512(h, block)),
+        (Hash::MD4, ChainingValue::MD4(h)) => ChainingValue::MD4(compress_md4(h, block)),
+        (Hash::MD5, ChainingValue::MD5(h)) => ChainingValue::MD5(compress_md5(h, block)),
+        (Hash::SHA3_224, ChainingValue::Keccak(a)) | (Hash::SHA3_256, ChainingValue::Keccak(a)) |
+        (Hash::SHA3_384, ChainingValue::Keccak(a)) | (Hash::SHA3_512, ChainingValue::Keccak(a)) |
+        (Hash::SHAKE128(_), ChainingValue::Keccak(a)) | (Hash::SHAKE256(_), ChainingValue::Keccak(a)) => ChainingValue::Keccak(absorb_keccak(a, block)),
+        (Hash::BAD16, ChainingValue::Bad(h)) => ChainingValue::Bad(compress_bad_hash(h, block, &BAD_HASH_16_KEY, 2)),
+        (Hash::BAD24, ChainingValue::Bad(h)) => ChainingValue::Bad(compress_bad_hash(h, block, &BAD_HASH_24_KEY, 3)),
+        _ => panic!("Chaining value does not match hash function!")
+    };
 }
 
+///Generates the SHA-1 digest of a message from some specific initial state.
+///If total_length is nonzero, it is used as the message bit length in the padding,
+///so that the message can continue one which has already been hashed.
+pub fn digest_sha1_from_state(message: &Vec<u8>, h_init: [u32;5], total_length: u64) -> Vec<u8> {
+    let mut chaining_value = vec![];
+    h_init.iter().for_each(|w| chaining_value.extend_from_slice(&w.to_be_bytes()));
+    return digest_from_state(Hash::SHA1, message, chaining_value, total_length);
+}
+
+///SHA-1 compression function
+fn compress_sha1(h_init: &[u32;5], block: &[u8]) -> [u32;5] {
+    let mut h = *h_init;
 
+    //Break into words
+    let mut w= [0 as u32; 80];
+    for i in 0..16 {
+        w[i] += (block[4*i] as u32) << 24;
+        w[i] += (block[4*i+1] as u32) << 16;
+        w[i] += (block[4*i+2] as u32) << 8;
+        w[i] += block[4*i+3] as u32;
+    }
 
-///Generates the MD4 digest of a message
-fn digest_md4(message: &Vec<u8>) -> Vec<u8> {
-    //Call arbitrary-state function with the MD4 initial state
-    return digest_md4_from_state(&message, [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476], 0);
+    //Generate remaining words
+    for i in 16..80 {
+        w[i] = (w[i -3] ^ w[i -8] ^ w[i -14] ^ w[i -16]).rotate_left(1);
+    }
+
+    let mut a = h[0];
+    let mut b = h[1];
+    let mut c = h[2];
+    let mut d = h[3];
+    let mut e = h[4];
+    let mut f: u32;
+    let mut k: u32;
+
+    //Perform hashing steps for this block
+    for i in 0..80 {
+        if i < 20 {
+            f = (b & c) | ((!b) & d);
+            k = 0x5A827999;
+        }
+        else if i < 40 {
+            f = b ^ c ^ d;
+            k = 0x6ED9EBA1;
+        }
+        else if i < 60 {
+            f = (b & c) | (b & d) | (c & d);
+            k = 0x8F1BBCDC;
+        }
+        else {
+            f = b ^ c ^ d;
+            k = 0xCA62C1D6;
+        }
+
+        let tmp = (a.rotate_left(5)).overflowing_add(f).0.overflowing_add(e).0.overflowing_add(k).0.overflowing_add(w[i]).0;
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = tmp;
+    }
+
+    h[0] = h[0].overflowing_add(a).0;
+    h[1] = h[1].overflowing_add(b).0;
+    h[2] = h[2].overflowing_add(c).0;
+    h[3] = h[3].overflowing_add(d).0;
+    h[4] = h[4].overflowing_add(e).0;
+
+    return h;
 }
 
-///Generates the MD4 digest of a message using the given internal state
+///Generates the MD4 digest of a message using the given internal state.
+///If total_length is nonzero, it is used as the message bit length in the padding.
 pub fn digest_md4_from_state(message: &Vec<u8>, init_buffer: [u32;4], total_length: u64) -> Vec<u8> {
+    let mut chaining_value = vec![];
+    init_buffer.iter().for_each(|w| chaining_value.extend_from_slice(&w.to_le_bytes()));
+    return digest_from_state(Hash::MD4, message, chaining_value, total_length);
+}
+
+///MD4 compression function
+fn compress_md4(init_buffer: &[u32;4], block: &[u8]) -> [u32;4] {
     ///MD4 helper functions
     fn f(x: u32, y: u32, z: u32) -> u32 {(x&y)|((!x)&z)}
     fn g(x: u32, y: u32, z: u32) -> u32 {(x&y)|(y&z)|(x&z)}
@@ -155,225 +534,353 @@ pub fn digest_md4_from_state(message: &Vec<u8>, init_buffer: [u32;4], total_leng
         return base.overflowing_add(message).0.overflowing_add(offset).0.overflowing_add(function).0.rotate_left(shift);
     }
 
-    let mut padded_message = message.clone();
-    let ml: u64 = if total_length == 0 {(message.len() * 8) as u64} else {total_length};
+    //Initialize buffers
+    let mut a = init_buffer[0];
+    let mut b = init_buffer[1];
+    let mut c = init_buffer[2];
+    let mut d = init_buffer[3];
+
+    let mut x = [0 as u32; 16];
+    for j in 0..16 {
+        x[j] += (block[4*j+3] as u32) << 24;
+        x[j] += (block[4*j+2] as u32) << 16;
+        x[j] += (block[4*j+1] as u32) << 8;
+        x[j] += block[4*j] as u32;
+    }
+
+    //Message word order for each round
+    let round_1_words = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+    let round_2_words = [0, 4, 8, 12, 1, 5, 9, 13, 2, 6, 10, 14, 3, 7, 11, 15];
+    let round_3_words = [0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15];
 
-    //Pad message
-    padded_message.push(0x80);
-    while padded_message.len() % 64 != 56 {
-        padded_message.push(0);
+    //Round 1
+    for i in 0..4 {
+        a = md4_add(a, x[round_1_words[4*i]], 0,f(b, c, d), 3);
+        d = md4_add(d, x[round_1_words[4*i+1]], 0, f(a, b, c), 7);
+        c = md4_add(c, x[round_1_words[4*i+2]], 0, f(d, a, b), 11);
+        b = md4_add(b, x[round_1_words[4*i+3]], 0, f(c, d, a), 19);
     }
 
-    //Append little-endian length
-    padded_message.append(&mut ml.to_le_bytes().to_vec());
+    //Round 2
+    for i in 0..4 {
+        a = md4_add(a, x[round_2_words[4*i]], 0x5a827999, g(b, c, d), 3);
+        d = md4_add(d, x[round_2_words[4*i+1]], 0x5a827999, g(a, b, c), 5);
+        c = md4_add(c, x[round_2_words[4*i+2]], 0x5a827999, g(d, a, b), 9);
+        b = md4_add(b, x[round_2_words[4*i+3]], 0x5a827999, g(c, d, a), 13);
+    }
+
+    //Round 3
+    for i in 0..4 {
+        a = md4_add(a, x[round_3_words[4*i]], 0x6ed9eba1, h(b, c, d), 3);
+        d = md4_add(d, x[round_3_words[4*i+1]], 0x6ed9eba1, h(a, b, c), 9);
+        c = md4_add(c, x[round_3_words[4*i+2]], 0x6ed9eba1, h(d, a, b), 11);
+        b = md4_add(b, x[round_3_words[4*i+3]], 0x6ed9eba1, h(c, d, a), 15);
+    }
+
+    return [
+        a.overflowing_add(init_buffer[0]).0,
+        b.overflowing_add(init_buffer[1]).0,
+        c.overflowing_add(init_buffer[2]).0,
+        d.overflowing_add(init_buffer[3]).0
+    ];
+}
+
+///Generates the MD5 digest of a message using the given internal state.
+///If total_length is nonzero, it is used as the message bit length in the padding.
+pub fn digest_md5_from_state(message: &Vec<u8>, init_buffer: [u32;4], total_length: u64) -> Vec<u8> {
+    let mut chaining_value = vec![];
+    init_buffer.iter().for_each(|w| chaining_value.extend_from_slice(&w.to_le_bytes()));
+    return digest_from_state(Hash::MD5, message, chaining_value, total_length);
+}
+
+///MD5 compression function
+fn compress_md5(init_buffer: &[u32;4], block: &[u8]) -> [u32;4] {
+    let mut x = [0 as u32; 16];
+    for j in 0..16 {
+        x[j] = u32::from_le_bytes([block[4*j], block[4*j+1], block[4*j+2], block[4*j+3]]);
+    }
 
-    //Initialize buffers
     let mut a = init_buffer[0];
     let mut b = init_buffer[1];
     let mut c = init_buffer[2];
     let mut d = init_buffer[3];
 
-    //Process message 16 32-bit words at a time
-    for i in 0..padded_message.len() / 64 {
-        let a_init = a;
-        let b_init = b;
-        let c_init = c;
-        let d_init = d;
-
-        let mut x = [0 as u32; 16];
-        for j in 0..16 {
-            x[j] += (padded_message[64*i+4*j+3] as u32) << 24;
-            x[j] += (padded_message[64*i+4*j+2] as u32) << 16;
-            x[j] += (padded_message[64*i+4*j+1] as u32) << 8;
-            x[j] += padded_message[64*i+4*j] as u32;
-        }
+    //Each round uses a different mixing function and message word order
+    for i in 0..64 {
+        let (f, word) = match i / 16 {
+            0 => ((b & c) | ((!b) & d), i),
+            1 => ((d & b) | ((!d) & c), (5*i + 1) % 16),
+            2 => (b ^ c ^ d, (3*i + 5) % 16),
+            _ => (c ^ (b | (!d)), (7*i) % 16)
+        };
 
-        //Message word order for each round
-        let round_1_words = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
-        let round_2_words = [0, 4, 8, 12, 1, 5, 9, 13, 2, 6, 10, 14, 3, 7, 11, 15];
-        let round_3_words = [0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15];
-
-        //Round 1
-        for i in 0..4 {
-            a = md4_add(a, x[round_1_words[4*i]], 0,f(b, c, d), 3);
-            d = md4_add(d, x[round_1_words[4*i+1]], 0, f(a, b, c), 7);
-            c = md4_add(c, x[round_1_words[4*i+2]], 0, f(d, a, b), 11);
-            b = md4_add(b, x[round_1_words[4*i+3]], 0, f(c, d, a), 19);
-        }
+        let tmp = a.overflowing_add(f).0.overflowing_add(MD5_K[i]).0.overflowing_add(x[word]).0;
+        a = d;
+        d = c;
+        c = b;
+        b = b.overflowing_add(tmp.rotate_left(MD5_SHIFTS[i / 16][i % 4])).0;
+    }
 
-        //Round 2
-        for i in 0..4 {
-            a = md4_add(a, x[round_2_words[4*i]], 0x5a827999, g(b, c, d), 3);
-            d = md4_add(d, x[round_2_words[4*i+1]], 0x5a827999, g(a, b, c), 5);
-            c = md4_add(c, x[round_2_words[4*i+2]], 0x5a827999, g(d, a, b), 9);
-            b = md4_add(b, x[round_2_words[4*i+3]], 0x5a827999, g(c, d, a), 13);
-        }
+    return [
+        a.overflowing_add(init_buffer[0]).0,
+        b.overflowing_add(init_buffer[1]).0,
+        c.overflowing_add(init_buffer[2]).0,
+        d.overflowing_add(init_buffer[3]).0
+    ];
+}
 
-        //Round 3
-        for i in 0..4 {
-            a = md4_add(a, x[round_3_words[4*i]], 0x6ed9eba1, h(b, c, d), 3);
-            d = md4_add(d, x[round_3_words[4*i+1]], 0x6ed9eba1, h(a, b, c), 9);
-            c = md4_add(c, x[round_3_words[4*i+2]], 0x6ed9eba1, h(d, a, b), 11);
-            b = md4_add(b, x[round_3_words[4*i+3]], 0x6ed9eba1, h(c, d, a), 15);
-        }
+///Generates the SHA-224 digest of a message from the given initial state.
+///If total_length is nonzero, it is used as the message bit length in the padding.
+pub fn digest_sha224_from_state(message: &Vec<u8>, h_init: [u32;8], total_length: u64) -> Vec<u8> {
+    let mut chaining_value = vec![];
+    h_init.iter().for_each(|w| chaining_value.extend_from_slice(&w.to_be_bytes()));
+    return digest_from_state(Hash::SHA224, message, chaining_value, total_length);
+}
+
+///Generates the SHA256 digest of a message from the given initial state.
+///If total_length is nonzero, it is used as the message bit length in the padding.
+pub fn digest_sha256_from_state(message: &Vec<u8>, h_init: [u32;8], total_length: u64) -> Vec<u8> {
+    let mut chaining_value = vec![];
+    h_init.iter().for_each(|w| chaining_value.extend_from_slice(&w.to_be_bytes()));
+    return digest_from_state(Hash::SHA256, message, chaining_value, total_length);
+}
+
+///SHA-256 compression function
+fn compress_sha256(h_init: &[u32;8], block: &[u8]) -> [u32;8] {
+    let mut h_arr = *h_init;
+
+    //Initialize schedule array
+    let mut w = [0 as u32;64];
+    for i in 0..16 {
+        w[i] += (block[4*i] as u32) << 24;
+        w[i] += (block[4*i+1] as u32) << 16;
+        w[i] += (block[4*i+2] as u32) << 8;
+        w[i] += block[4*i+3] as u32;
+    }
+    for i in 16..64 {
+        let s0: u32 = w[i-15].rotate_right(7) ^ w[i-15].rotate_right(18) ^ (w[i-15] >> 3);
+        let s1: u32 = w[i-2].rotate_right(17) ^ w[i-2].rotate_right(19) ^ (w[i-2] >> 10);
+        w[i] = w[i-16].overflowing_add(s0).0.overflowing_add(w[i-7]).0.overflowing_add(s1).0;
+    }
+
+    let mut a = h_arr[0];
+    let mut b = h_arr[1];
+    let mut c = h_arr[2];
+    let mut d = h_arr[3];
+    let mut e = h_arr[4];
+    let mut f = h_arr[5];
+    let mut g = h_arr[6];
+    let mut h = h_arr[7];
 
-        a = a.overflowing_add(a_init).0;
-        b = b.overflowing_add(b_init).0;
-        c = c.overflowing_add(c_init).0;
-        d = d.overflowing_add(d_init).0;
+    //Perform hash steps
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ ((!e) & g);
+        let tmp1 = h.overflowing_add(s1).0.overflowing_add(ch).0.overflowing_add(SHA256_K[i]).0.overflowing_add(w[i]).0;
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let tmp2 = s0.overflowing_add(maj).0;
+
+        h = g;
+        g = f;
+        f = e;
+        e = d.overflowing_add(tmp1).0;
+        d = c;
+        c = b;
+        b = a;
+        a = tmp1.overflowing_add(tmp2).0;
     }
 
-    //Construct output digest
-    let mut hash = vec![];
-    hash.append(&mut a.to_le_bytes().to_vec());
-    hash.append(&mut b.to_le_bytes().to_vec());
-    hash.append(&mut c.to_le_bytes().to_vec());
-    hash.append(&mut d.to_le_bytes().to_vec());
+    h_arr[0] = h_arr[0].overflowing_add(a).0;
+    h_arr[1] = h_arr[1].overflowing_add(b).0;
+    h_arr[2] = h_arr[2].overflowing_add(c).0;
+    h_arr[3] = h_arr[3].overflowing_add(d).0;
+    h_arr[4] = h_arr[4].overflowing_add(e).0;
+    h_arr[5] = h_arr[5].overflowing_add(f).0;
+    h_arr[6] = h_arr[6].overflowing_add(g).0;
+    h_arr[7] = h_arr[7].overflowing_add(h).0;
 
-    return hash;
+    return h_arr;
 }
 
-///Generates the SHA256 digest of a message
-fn digest_sha256(message: &Vec<u8>) -> Vec<u8> {
-    //Calls arbitrary-state function with the SHA-256 initial state
-    return digest_sha256_from_state(message, [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19], 0);
+///Generates the SHA-384 digest of a message from the given initial state.
+///If total_length is nonzero, it is used as the message bit length in the padding.
+pub fn digest_sha384_from_state(message: &Vec<u8>, h_init: [u64;8], total_length: u64) -> Vec<u8> {
+    let mut chaining_value = vec![];
+    h_init.iter().for_each(|w| chaining_value.extend_from_slice(&w.to_be_bytes()));
+    return digest_from_state(Hash::SHA384, message, chaining_value, total_length);
 }
 
-///Generates the SHA256 digest of a message from the given initial state
-fn digest_sha256_from_state(message: &Vec<u8>, h_init: [u32;8], total_length: u64) -> Vec<u8> {
-    //Initialize hash fields
-    let mut h_arr = h_init.clone();
-    let k: [u32;64] =
-    [0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
-    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
-    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
-    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
-    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
-    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
-    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
-    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2];
+///Generates the SHA-512 digest of a message from the given initial state.
+///If total_length is nonzero, it is used as the message bit length in the padding.
+pub fn digest_sha512_from_state(message: &Vec<u8>, h_init: [u64;8], total_length: u64) -> Vec<u8> {
+    let mut chaining_value = vec![];
+    h_init.iter().for_each(|w| chaining_value.extend_from_slice(&w.to_be_bytes()));
+    return digest_from_state(Hash::SHA512, message, chaining_value, total_length);
+}
 
-    //Generate padded message for hashing
-    let mut padded_message = message.clone();
-    let ml: u64 = if total_length == 0 {(message.len() * 8) as u64} else {total_length};
-    padded_message.push(0x80);
-    while padded_message.len() % 64 != 56 {
-        padded_message.push(0);
-    }
-    padded_message.append(&mut ml.to_be_bytes().to_vec());
-
-    //Digest each 64-byte chunk
-    for block in 0..padded_message.len() / 64 {
-        //Initialize schedule array
-        let mut w = [0 as u32;64];
-        for i in 0..16 {
-            w[i] += (padded_message[64*block+4*i] as u32) << 24;
-            w[i] += (padded_message[64*block+4*i+1] as u32) << 16;
-            w[i] += (padded_message[64*block+4*i+2] as u32) << 8;
-            w[i] += padded_message[64*block+4*i+3] as u32;
-        }
-        for i in 16..64 {
-            let s0: u32 = w[i-15].rotate_right(7) ^ w[i-15].rotate_right(18) ^ (w[i-15] >> 3);
-            let s1: u32 = w[i-2].rotate_right(17) ^ w[i-2].rotate_right(19) ^ (w[i-2] >> 10);
-            w[i] = w[i-16].overflowing_add(s0).0.overflowing_add(w[i-7]).0.overflowing_add(s1).0;
-        }
+///Generates the SHA-512/256 digest of a message from the given initial state.
+///If total_length is nonzero, it is used as the message bit length in the padding.
+pub fn digest_sha512_256_from_state(message: &Vec<u8>, h_init: [u64;8], total_length: u64) -> Vec<u8> {
+    let mut chaining_value = vec![];
+    h_init.iter().for_each(|w| chaining_value.extend_from_slice(&w.to_be_bytes()));
+    return digest_from_state(Hash::SHA512_256, message, chaining_value, total_length);
+}
 
-        let mut a = h_arr[0];
-        let mut b = h_arr[1];
-        let mut c = h_arr[2];
-        let mut d = h_arr[3];
-        let mut e = h_arr[4];
-        let mut f = h_arr[5];
-        let mut g = h_arr[6];
-        let mut h = h_arr[7];
-
-        //Perform hash steps
-        for i in 0..64 {
-            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
-            let ch = (e & f) ^ ((!e) & g);
-            let tmp1 = h.overflowing_add(s1).0.overflowing_add(ch).0.overflowing_add(k[i]).0.overflowing_add(w[i]).0;
-            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
-            let maj = (a & b) ^ (a & c) ^ (b & c);
-            let tmp2 = s0.overflowing_add(maj).0;
-
-            h = g;
-            g = f;
-            f = e;
-            e = d.overflowing_add(tmp1).0;
-            d = c;
-            c = b;
-            b = a;
-            a = tmp1.overflowing_add(tmp2).0;
-        }
+///SHA-512 compression function, shared by SHA-384 and SHA-512/256
+fn compress_sha512(h_init: &[u64;8], block: &[u8]) -> [u64;8] {
+    let mut h_arr = *h_init;
+
+    //Initialize schedule array
+    let mut w = [0 as u64; 80];
+    for i in 0..16 {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&block[8*i..8*(i+1)]);
+        w[i] = u64::from_be_bytes(bytes);
+    }
+    for i in 16..80 {
+        let s0: u64 = w[i-15].rotate_right(1) ^ w[i-15].rotate_right(8) ^ (w[i-15] >> 7);
+        let s1: u64 = w[i-2].rotate_right(19) ^ w[i-2].rotate_right(61) ^ (w[i-2] >> 6);
+        w[i] = w[i-16].overflowing_add(s0).0.overflowing_add(w[i-7]).0.overflowing_add(s1).0;
+    }
 
-        h_arr[0] = h_arr[0].overflowing_add(a).0;
-        h_arr[1] = h_arr[1].overflowing_add(b).0;
-        h_arr[2] = h_arr[2].overflowing_add(c).0;
-        h_arr[3] = h_arr[3].overflowing_add(d).0;
-        h_arr[4] = h_arr[4].overflowing_add(e).0;
-        h_arr[5] = h_arr[5].overflowing_add(f).0;
-        h_arr[6] = h_arr[6].overflowing_add(g).0;
-        h_arr[7] = h_arr[7].overflowing_add(h).0;
+    let mut a = h_arr[0];
+    let mut b = h_arr[1];
+    let mut c = h_arr[2];
+    let mut d = h_arr[3];
+    let mut e = h_arr[4];
+    let mut f = h_arr[5];
+    let mut g = h_arr[6];
+    let mut h = h_arr[7];
+
+    //Perform hash steps
+    for i in 0..80 {
+        let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+        let ch = (e & f) ^ ((!e) & g);
+        let tmp1 = h.overflowing_add(s1).0.overflowing_add(ch).0.overflowing_add(SHA512_K[i]).0.overflowing_add(w[i]).0;
+        let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let tmp2 = s0.overflowing_add(maj).0;
+
+        h = g;
+        g = f;
+        f = e;
+        e = d.overflowing_add(tmp1).0;
+        d = c;
+        c = b;
+        b = a;
+        a = tmp1.overflowing_add(tmp2).0;
     }
 
-    let mut hash = vec![];
     for i in 0..8 {
-        hash.append(&mut h_arr[i].to_be_bytes().to_vec());
+        h_arr[i] = h_arr[i].overflowing_add([a, b, c, d, e, f, g, h][i]).0;
     }
 
-    return hash;
+    return h_arr;
 }
 
-///Generates bad 16-bit hash from the given message
-fn digest_bad_hash_16(message: &Vec<u8>) -> Vec<u8> {
-    //Calls arbitrary-state function with default value
-    return digest_bad_hash_16_from_state(message, &vec![0xbe, 0xef]);
-}
+///Keccak-f[1600] permutation on a state of 25 lanes, indexed x + 5y
+fn keccak_f(state: &mut [u64; 25]) {
+    for round in 0..24 {
+        //Theta: XOR each lane with the parities of two neighbouring columns
+        let mut c = [0 as u64; 5];
+        for x in 0..5 {
+            c[x] = state[x] ^ state[x+5] ^ state[x+10] ^ state[x+15] ^ state[x+20];
+        }
+        for x in 0..5 {
+            let d = c[(x+4) % 5] ^ c[(x+1) % 5].rotate_left(1);
+            for y in 0..5 {
+                state[x + 5*y] ^= d;
+            }
+        }
 
-///Generates bad 16-bit hash from the given message and initial state
-pub fn digest_bad_hash_16_from_state(message: &Vec<u8>, state: &Vec<u8>) -> Vec<u8> {
-    //Create message padded to a multiple of 16 bytes
-    let mut padded_message = message.clone();
-    while padded_message.len() % 16 != 0 {
-        padded_message.push(0x55);
+        //Rho and pi: rotate each lane and move it to its new position
+        let mut b = [0 as u64; 25];
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5*((2*x + 3*y) % 5)] = state[x + 5*y].rotate_left(KECCAK_ROTATIONS[x + 5*y]);
+            }
+        }
+
+        //Chi: the only nonlinear step, mixing each row
+        for x in 0..5 {
+            for y in 0..5 {
+                state[x + 5*y] = b[x + 5*y] ^ ((!b[(x+1) % 5 + 5*y]) & b[(x+2) % 5 + 5*y]);
+            }
+        }
+
+        //Iota: break the symmetry between rounds
+        state[0] ^= KECCAK_RC[round];
     }
+}
 
-    //Digest each 16-byte chunk and return final result
-    let mut hash = state.clone();
-    for i in 0..padded_message.len()/16 {
-        let message_block = padded_message[16*i..16*(i+1)].to_vec();
-        let xor = xor_repeating(&message_block, &hash);
-        let encrypted = encrypt_ecb(&xor, &BAD_HASH_16_KEY);
-        hash = encrypted[0..2].to_vec();
+///Absorbs one block (of the sponge's rate) into the Keccak state
+fn absorb_keccak(state: &[u64; 25], block: &[u8]) -> [u64; 25] {
+    let mut a = *state;
+    for (i, lane) in block.chunks(8).enumerate() {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(lane);
+        a[i] ^= u64::from_le_bytes(bytes);
     }
+    keccak_f(&mut a);
+    return a;
+}
 
-    return hash;
+///Squeezes the given number of output bytes out of the Keccak state, permuting again after each block
+fn squeeze_keccak(state: &[u64; 25], rate: usize, length: usize) -> Vec<u8> {
+    let mut a = *state;
+    let mut output = vec![];
+    loop {
+        for lane in a[0..rate/8].iter() {
+            output.extend_from_slice(&lane.to_le_bytes());
+        }
+        if output.len() >= length {
+            output.truncate(length);
+            return output;
+        }
+        keccak_f(&mut a);
+    }
 }
 
-///Generates bad 24-bit hash from the given message
-fn digest_bad_hash_24(message: &Vec<u8>) -> Vec<u8> {
-    //Calls arbitrary-state function with default value
-    return digest_bad_hash_24_from_state(message, &vec![0xde, 0xad, 0xbe, 0xef]);
+///Generates bad 16-bit hash from the given message and initial state
+pub fn digest_bad_hash_16_from_state(message: &Vec<u8>, state: &Vec<u8>) -> Vec<u8> {
+    return digest_from_state(Hash::BAD16, message, state.clone(), 0);
 }
 
 ///Generates bad 24-bit hash from the given message and initial state
 pub fn digest_bad_hash_24_from_state(message: &Vec<u8>, state: &Vec<u8>) -> Vec<u8> {
-    //Create message padded to a multiple of 16 bytes
-    let mut padded_message = message.clone();
-    while padded_message.len() % 16 != 0 {
-        padded_message.push(0x55);
-    }
+    return digest_from_state(Hash::BAD24, message, state.clone(), 0);
+}
 
-    //Digest each 16-byte chunk and return final result
-    let mut hash = state.clone();
-    for i in 0..padded_message.len()/16 {
-        let message_block = padded_message[16*i..16*(i+1)].to_vec();
-        let xor = xor_repeating(&message_block, &hash);
-        let encrypted = encrypt_ecb(&xor, &BAD_HASH_24_KEY);
-        hash = encrypted[0..3].to_vec();
-    }
+///Bad hash compression function: encrypts the block XORed with the state, and keeps the first few bytes
+fn compress_bad_hash(state: &Vec<u8>, block: &[u8], key: &Vec<u8>, length: usize) -> Vec<u8> {
+    let xor = xor_repeating(&block.to_vec(), state);
+    let encrypted = encrypt_ecb(&xor, key);
+    return encrypted[0..length].to_vec();
+}
 
-    return hash;
+///Digests a message starting from the given chaining value.
+///A nonzero total_length (in bits) replaces the length field at the end of the padding,
+///which is otherwise laid out for the message alone, as if it continued an earlier message.
+fn digest_from_state(hash: Hash, message: &Vec<u8>, chaining_value: Vec<u8>, total_length: u64) -> Vec<u8> {
+    let mut hasher = Hasher::import_state(hash, &HashState {
+        chaining_value,
+        byte_count: 0,
+        buffer: vec![]
+    });
+    hasher.update(message);
+
+    let mut padding = hash.padding(message.len() as u64);
+    if total_length != 0 {
+        let length_field = match hash {
+            Hash::MD4 | Hash::MD5 => total_length.to_le_bytes().to_vec(),
+            Hash::SHA384 | Hash::SHA512 | Hash::SHA512_256 => (total_length as u128).to_be_bytes().to_vec(),
+            _ => total_length.to_be_bytes().to_vec()
+        };
+        let start = padding.len() - length_field.len();
+        padding[start..].copy_from_slice(&length_field);
+    }
+    return hasher.finalize_with_padding(padding);
 }
 
 #[cfg(test)]
@@ -383,33 +890,215 @@ mod tests {
 
     #[test]
     fn test_sha1() {
-        assert_eq!(bytes_to_hex(&digest_sha1(&ascii_to_bytes("abc"))), "a9993e364706816aba3e25717850c26c9cd0d89d");
-        assert_eq!(bytes_to_hex(&digest_sha1(&ascii_to_bytes(""))), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
-        assert_eq!(bytes_to_hex(&digest_sha1(&ascii_to_bytes("abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"))), "84983e441c3bd26ebaae4aa1f95129e5e54670f1");
-        assert_eq!(bytes_to_hex(&digest_sha1(&ascii_to_bytes("abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu"))), "a49b2446a02c645bf419f995b67091253a04a259");
-        assert_eq!(bytes_to_hex(&digest_sha1(&ascii_to_bytes(&"a".repeat(1000000)))), "34aa973cd4c4daa4f61eeb2bdbad27316534016f");
+        assert_eq!(bytes_to_hex(&Hash::SHA1.digest(&ascii_to_bytes("abc"))), "a9993e364706816aba3e25717850c26c9cd0d89d");
+        assert_eq!(bytes_to_hex(&Hash::SHA1.digest(&ascii_to_bytes(""))), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        assert_eq!(bytes_to_hex(&Hash::SHA1.digest(&ascii_to_bytes("abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"))), "84983e441c3bd26ebaae4aa1f95129e5e54670f1");
+        assert_eq!(bytes_to_hex(&Hash::SHA1.digest(&ascii_to_bytes("abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu"))), "a49b2446a02c645bf419f995b67091253a04a259");
+        assert_eq!(bytes_to_hex(&Hash::SHA1.digest(&ascii_to_bytes(&"a".repeat(1000000)))), "34aa973cd4c4daa4f61eeb2bdbad27316534016f");
     }
 
     #[test]
     fn test_md4() {
-        assert_eq!(bytes_to_hex(&digest_md4(&ascii_to_bytes(""))), "31d6cfe0d16ae931b73c59d7e0c089c0");
-        assert_eq!(bytes_to_hex(&digest_md4(&ascii_to_bytes("a"))), "bde52cb31de33e46245e05fbdbd6fb24");
-        assert_eq!(bytes_to_hex(&digest_md4(&ascii_to_bytes("abc"))), "a448017aaf21d8525fc10ae87aa6729d");
-        assert_eq!(bytes_to_hex(&digest_md4(&ascii_to_bytes("message digest"))), "d9130a8164549fe818874806e1c7014b");
-        assert_eq!(bytes_to_hex(&digest_md4(&ascii_to_bytes("abcdefghijklmnopqrstuvwxyz"))), "d79e1c308aa5bbcdeea8ed63df412da9");
-        assert_eq!(bytes_to_hex(&digest_md4(&ascii_to_bytes("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789"))), "43f8582f241db351ce627e153e7f0e4");
-        assert_eq!(bytes_to_hex(&digest_md4(&ascii_to_bytes("12345678901234567890123456789012345678901234567890123456789012345678901234567890"))), "e33b4ddc9c38f2199c3e7b164fcc0536");
+        assert_eq!(bytes_to_hex(&Hash::MD4.digest(&ascii_to_bytes(""))), "31d6cfe0d16ae931b73c59d7e0c089c0");
+        assert_eq!(bytes_to_hex(&Hash::MD4.digest(&ascii_to_bytes("a"))), "bde52cb31de33e46245e05fbdbd6fb24");
+        assert_eq!(bytes_to_hex(&Hash::MD4.digest(&ascii_to_bytes("abc"))), "a448017aaf21d8525fc10ae87aa6729d");
+        assert_eq!(bytes_to_hex(&Hash::MD4.digest(&ascii_to_bytes("message digest"))), "d9130a8164549fe818874806e1c7014b");
+        assert_eq!(bytes_to_hex(&Hash::MD4.digest(&ascii_to_bytes("abcdefghijklmnopqrstuvwxyz"))), "d79e1c308aa5bbcdeea8ed63df412da9");
+        assert_eq!(bytes_to_hex(&Hash::MD4.digest(&ascii_to_bytes("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789"))), "43f8582f241db351ce627e153e7f0e4");
+        assert_eq!(bytes_to_hex(&Hash::MD4.digest(&ascii_to_bytes("12345678901234567890123456789012345678901234567890123456789012345678901234567890"))), "e33b4ddc9c38f2199c3e7b164fcc0536");
     }
 
     #[test]
     fn test_sha256() {
-        assert_eq!(bytes_to_hex(&digest_sha256(&ascii_to_bytes(""))).to_uppercase(), "E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855");
-        assert_eq!(bytes_to_hex(&digest_sha256(&ascii_to_bytes("a"))).to_uppercase(), "CA978112CA1BBDCAFAC231B39A23DC4DA786EFF8147C4E72B9807785AFEE48BB");
-        assert_eq!(bytes_to_hex(&digest_sha256(&ascii_to_bytes("abc"))).to_uppercase(), "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD");
-        assert_eq!(bytes_to_hex(&digest_sha256(&ascii_to_bytes("message digest"))).to_uppercase(), "F7846F55CF23E14EEBEAB5B4E1550CAD5B509E3348FBC4EFA3A1413D393CB650");
-        assert_eq!(bytes_to_hex(&digest_sha256(&ascii_to_bytes("abcdefghijklmnopqrstuvwxyz"))).to_uppercase(), "71C480DF93D6AE2F1EFAD1447C66C9525E316218CF51FC8D9ED832F2DAF18B73");
-        assert_eq!(bytes_to_hex(&digest_sha256(&ascii_to_bytes("abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"))).to_uppercase(), "248D6A61D20638B8E5C026930C3E6039A33CE45964FF2167F6ECEDD419DB06C1");
-        assert_eq!(bytes_to_hex(&digest_sha256(&ascii_to_bytes("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789"))).to_uppercase(), "DB4BFCBD4DA0CD85A60C3C37D3FBD8805C77F15FC6B1FDFE614EE0A7C8FDB4C0");
-        assert_eq!(bytes_to_hex(&digest_sha256(&ascii_to_bytes("12345678901234567890123456789012345678901234567890123456789012345678901234567890"))).to_uppercase(), "F371BC4A311F2B009EEF952DD83CA80E2B60026C8E935592D0F9C308453C813E");
+        assert_eq!(bytes_to_hex(&Hash::SHA256.digest(&ascii_to_bytes(""))).to_uppercase(), "E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855");
+        assert_eq!(bytes_to_hex(&Hash::SHA256.digest(&ascii_to_bytes("a"))).to_uppercase(), "CA978112CA1BBDCAFAC231B39A23DC4DA786EFF8147C4E72B9807785AFEE48BB");
+        assert_eq!(bytes_to_hex(&Hash::SHA256.digest(&ascii_to_bytes("abc"))).to_uppercase(), "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD");
+        assert_eq!(bytes_to_hex(&Hash::SHA256.digest(&ascii_to_bytes("message digest"))).to_uppercase(), "F7846F55CF23E14EEBEAB5B4E1550CAD5B509E3348FBC4EFA3A1413D393CB650");
+        assert_eq!(bytes_to_hex(&Hash::SHA256.digest(&ascii_to_bytes("abcdefghijklmnopqrstuvwxyz"))).to_uppercase(), "71C480DF93D6AE2F1EFAD1447C66C9525E316218CF51FC8D9ED832F2DAF18B73");
+        assert_eq!(bytes_to_hex(&Hash::SHA256.digest(&ascii_to_bytes("abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"))).to_uppercase(), "248D6A61D20638B8E5C026930C3E6039A33CE45964FF2167F6ECEDD419DB06C1");
+        assert_eq!(bytes_to_hex(&Hash::SHA256.digest(&ascii_to_bytes("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789"))).to_uppercase(), "DB4BFCBD4DA0CD85A60C3C37D3FBD8805C77F15FC6B1FDFE614EE0A7C8FDB4C0");
+        assert_eq!(bytes_to_hex(&Hash::SHA256.digest(&ascii_to_bytes("12345678901234567890123456789012345678901234567890123456789012345678901234567890"))).to_uppercase(), "F371BC4A311F2B009EEF952DD83CA80E2B60026C8E935592D0F9C308453C813E");
+    }
+
+    #[test]
+    fn test_md5() {
+        assert_eq!(bytes_to_hex(&Hash::MD5.digest(&ascii_to_bytes(""))), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(bytes_to_hex(&Hash::MD5.digest(&ascii_to_bytes("abc"))), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(bytes_to_hex(&Hash::MD5.digest(&ascii_to_bytes("message digest"))), "f96b697d7cb7938d525a2f31aaf161d0");
+        assert_eq!(bytes_to_hex(&Hash::MD5.digest(&ascii_to_bytes("12345678901234567890123456789012345678901234567890123456789012345678901234567890"))), "57edf4a22be3c955ac49da2e2107b67a");
+        assert_eq!(bytes_to_hex(&Hash::MD5.digest(&ascii_to_bytes(&"a".repeat(1000000)))), "7707d6ae4e027c70eea2a935c2296f21");
+    }
+
+    #[test]
+    fn test_sha224() {
+        assert_eq!(bytes_to_hex(&Hash::SHA224.digest(&ascii_to_bytes(""))), "d14a028c2a3a2bc9476102bb288234c415a2b01f828ea62ac5b3e42f");
+        assert_eq!(bytes_to_hex(&Hash::SHA224.digest(&ascii_to_bytes("abc"))), "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7");
+        assert_eq!(bytes_to_hex(&Hash::SHA224.digest(&ascii_to_bytes("abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"))), "75388b16512776cc5dba5da1fd890150b0c6455cb4f58b1952522525");
+        assert_eq!(bytes_to_hex(&Hash::SHA224.digest(&ascii_to_bytes(&"a".repeat(1000000)))), "20794655980c91d8bbb4c1ea97618a4bf03f42581948b2ee4ee7ad67");
+    }
+
+    #[test]
+    fn test_sha384() {
+        assert_eq!(bytes_to_hex(&Hash::SHA384.digest(&ascii_to_bytes(""))), "38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da274edebfe76f65fbd51ad2f14898b95b");
+        assert_eq!(bytes_to_hex(&Hash::SHA384.digest(&ascii_to_bytes("abc"))), "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7");
+        assert_eq!(bytes_to_hex(&Hash::SHA384.digest(&ascii_to_bytes("abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu"))), "9330c33f71147e83d192fc782cd1b4753111b173b3b05d22fa08086e3b0f712fcc7c71a557e2db966c3e9fa91746039");
+        assert_eq!(bytes_to_hex(&Hash::SHA384.digest(&ascii_to_bytes(&"a".repeat(1000000)))), "9d0e1809716474cb086e834e310a4a1ced149e9c00f248527972cec5704c2a5b07b8b3dc38ecc4ebae97ddd87f3d8985");
+    }
+
+    #[test]
+    fn test_sha512() {
+        assert_eq!(bytes_to_hex(&Hash::SHA512.digest(&ascii_to_bytes(""))), "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e");
+        assert_eq!(bytes_to_hex(&Hash::SHA512.digest(&ascii_to_bytes("abc"))), "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f");
+        assert_eq!(bytes_to_hex(&Hash::SHA512.digest(&ascii_to_bytes("abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu"))), "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909");
+        assert_eq!(bytes_to_hex(&Hash::SHA512.digest(&ascii_to_bytes(&"a".repeat(1000000)))), "e718483d0ce769644e2e42c7bc15b4638e1f98b13b2044285632a803afa973ebde0ff244877ea60a4cb0432ce577c31beb009c5c2c49aa2e4eadb217ad8cc09b");
+    }
+
+    #[test]
+    fn test_sha512_256() {
+        assert_eq!(bytes_to_hex(&Hash::SHA512_256.digest(&ascii_to_bytes(""))), "c672b8d1ef56ed28ab87c3622c5114069bdd3ad7b8f9737498d0c01ecef0967a");
+        assert_eq!(bytes_to_hex(&Hash::SHA512_256.digest(&ascii_to_bytes("abc"))), "53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23");
+        assert_eq!(bytes_to_hex(&Hash::SHA512_256.digest(&ascii_to_bytes("abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu"))), "3928e184fb8690f840da3988121d31be65cb9d3ef83ee6146feac861e19b563a");
+    }
+
+    #[test]
+    fn test_sha3() {
+        assert_eq!(bytes_to_hex(&Hash::SHA3_224.digest(&ascii_to_bytes(""))), "6b4e03423667dbb73b6e15454f0eb1abd4597f9a1b078e3f5b5a6bc7");
+        assert_eq!(bytes_to_hex(&Hash::SHA3_224.digest(&ascii_to_bytes("abc"))), "e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf");
+        assert_eq!(bytes_to_hex(&Hash::SHA3_256.digest(&ascii_to_bytes(""))), "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a");
+        assert_eq!(bytes_to_hex(&Hash::SHA3_256.digest(&ascii_to_bytes("abc"))), "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532");
+        assert_eq!(bytes_to_hex(&Hash::SHA3_256.digest(&ascii_to_bytes(&"a".repeat(200)))), "cce34485baf2bf2aca99b94833892a4f52896d3d153f7b840cc4f9fe695f1387");
+        assert_eq!(bytes_to_hex(&Hash::SHA3_384.digest(&ascii_to_bytes(""))), "c63a75b845e4f7d01107d852e4c2485c51a50aaaa94fc61995e71bbee983a2ac3713831264adb47fb6bd1e058d5f004");
+        assert_eq!(bytes_to_hex(&Hash::SHA3_384.digest(&ascii_to_bytes("abc"))), "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b298d88cea927ac7f539f1edf228376d25");
+        assert_eq!(bytes_to_hex(&Hash::SHA3_512.digest(&ascii_to_bytes(""))), "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a615b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26");
+        assert_eq!(bytes_to_hex(&Hash::SHA3_512.digest(&ascii_to_bytes("abc"))), "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0");
+        assert_eq!(bytes_to_hex(&Hash::SHA3_512.digest(&ascii_to_bytes(&"a".repeat(200)))), "eae6c85c6904f11075de9f9d5e1064371d000510fa3d2d79d40cf9be34892fb01859d0a0234e138bcb0ad5c84f6c0dca226a414b0c9a2897cb695f5185fe36ec");
+    }
+
+    #[test]
+    fn test_shake() {
+        assert_eq!(bytes_to_hex(&Hash::SHAKE128(32).digest(&ascii_to_bytes(""))), "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26");
+        assert_eq!(bytes_to_hex(&Hash::SHAKE128(32).digest(&ascii_to_bytes("abc"))), "5881092dd818bf5cf8a3ddb793fbcba74097d5c526a6d35f97b83351940f2cc8");
+        assert_eq!(bytes_to_hex(&Hash::SHAKE256(64).digest(&ascii_to_bytes(""))), "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762fd75dc4ddd8c0f200cb05019d67b592f6fc821c49479ab48640292eacb3b7c4be");
+        assert_eq!(bytes_to_hex(&Hash::SHAKE256(64).digest(&ascii_to_bytes("abc"))), "483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739d5a15bef186a5386c75744c0527e1faa9f8726e462a12a4feb06bd8801e751e4");
+
+        //Longer outputs squeeze more than one block, and shorter ones are prefixes of them
+        let long = Hash::SHAKE128(200).digest(&ascii_to_bytes("abc"));
+        assert_eq!(long.len(), 200);
+        assert_eq!(bytes_to_hex(&long[190..].to_vec()), "1fcd4818cb006aa5b4cd");
+        assert_eq!(Hash::SHAKE128(5).digest(&ascii_to_bytes("abc")), long[0..5].to_vec());
+        assert_eq!(Hash::SHAKE128(200).hash_length(), 200);
+    }
+
+    #[test]
+    fn test_from_state() {
+        //Starting from the initial state gives the normal digest
+        let message = ascii_to_bytes("abc");
+        assert_eq!(digest_md5_from_state(&message, MD4_INIT, 0), Hash::MD5.digest(&message));
+        assert_eq!(digest_sha224_from_state(&message, SHA224_INIT, 0), Hash::SHA224.digest(&message));
+        assert_eq!(digest_sha256_from_state(&message, SHA256_INIT, 0), Hash::SHA256.digest(&message));
+        assert_eq!(digest_sha384_from_state(&message, SHA384_INIT, 0), Hash::SHA384.digest(&message));
+        assert_eq!(digest_sha512_from_state(&message, SHA512_INIT, 0), Hash::SHA512.digest(&message));
+        assert_eq!(digest_sha512_256_from_state(&message, SHA512_256_INIT, 0), Hash::SHA512_256.digest(&message));
+
+        //Resuming after a full block gives the digest of the whole message
+        let long_message = vec![0x61; 131];
+        let mut hasher = Hash::SHA512.hasher();
+        hasher.update(&long_message[0..128]);
+        let mut h = [0; 8];
+        for i in 0..8 {
+            let mut bytes = [0; 8];
+            bytes.copy_from_slice(&hasher.export_state().chaining_value[8*i..8*(i+1)]);
+            h[i] = u64::from_be_bytes(bytes);
+        }
+        assert_eq!(digest_sha512_from_state(&long_message[128..].to_vec(), h, 131 * 8), Hash::SHA512.digest(&long_message));
+    }
+
+    #[test]
+    fn test_from_state_any_length() {
+        //Only the length field of the padding changes, so lengths which do not fit the message are still accepted
+        let message = ascii_to_bytes("abc");
+        let mut padded = message.clone();
+        padded.push(0x80);
+        padded.append(&mut vec![0; 52]);
+        padded.append(&mut 8u64.to_be_bytes().to_vec());
+        let mut hasher = Hash::SHA1.hasher();
+        hasher.update(&padded);
+        assert_eq!(digest_sha1_from_state(&message, SHA1_INIT, 8), hasher.export_state().chaining_value);
+
+        assert_ne!(digest_md4_from_state(&message, MD4_INIT, 8 * 100 + 3), Hash::MD4.digest(&message));
+        assert_ne!(digest_sha512_from_state(&message, SHA512_INIT, 8 * 130), Hash::SHA512.digest(&message));
+    }
+
+    #[test]
+    fn test_padding() {
+        let padding = Hash::SHA1.padding(3);
+        assert_eq!(padding.len(), 61);
+        assert_eq!(padding[0], 0x80);
+        assert_eq!(padding[53..].to_vec(), 24u64.to_be_bytes().to_vec());
+        assert_eq!(Hash::MD5.padding(64)[56..].to_vec(), 512u64.to_le_bytes().to_vec());
+        assert_eq!(Hash::SHA512.padding(0).len(), 128);
+        assert_eq!(Hash::SHA256.padding(56).len(), 72);
+        assert_eq!(Hash::SHA3_256.padding(135), vec![0x86]);
+        assert_eq!(Hash::BAD16.padding(32), vec![]);
+    }
+
+    #[test]
+    fn test_hasher_update() {
+        let message = ascii_to_bytes(&"The quick brown fox jumps over the lazy dog. ".repeat(10));
+        for hash in [Hash::SHA1, Hash::SHA224, Hash::SHA256, Hash::SHA384, Hash::SHA512, Hash::SHA512_256, Hash::MD4, Hash::MD5,
+            Hash::SHA3_224, Hash::SHA3_256, Hash::SHA3_384, Hash::SHA3_512, Hash::SHAKE128(40), Hash::SHAKE256(40), Hash::BAD16, Hash::BAD24].iter() {
+            //Feed the message in uneven pieces, so that pieces straddle block boundaries
+            let mut hasher = hash.hasher();
+            let mut start = 0;
+            let mut piece = 1;
+            while start < message.len() {
+                let end = std::cmp::min(start + piece, message.len());
+                hasher.update(&message[start..end]);
+                start = end;
+                piece = piece * 3 % 71 + 1;
+            }
+
+            assert_eq!(hasher.finalize(), hash.digest(&message));
+        }
+    }
+
+    #[test]
+    fn test_hasher_export_import() {
+        let message = ascii_to_bytes(&"Resumable hashing. ".repeat(20));
+        for hash in [Hash::SHA1, Hash::SHA224, Hash::SHA256, Hash::SHA384, Hash::SHA512, Hash::SHA512_256, Hash::MD4, Hash::MD5,
+            Hash::SHA3_224, Hash::SHA3_256, Hash::SHA3_384, Hash::SHA3_512, Hash::SHAKE128(40), Hash::SHAKE256(40), Hash::BAD16, Hash::BAD24].iter() {
+            let mut first = hash.hasher();
+            first.update(&message[0..150]);
+            let state = first.export_state();
+            assert_eq!(state.byte_count, 150);
+            assert_eq!(state.buffer.len(), 150 % hash.block_length());
+
+            let mut second = Hasher::import_state(*hash, &state);
+            second.update(&message[150..]);
+            assert_eq!(second.finalize(), hash.digest(&message));
+        }
+    }
+
+    #[test]
+    fn test_hasher_state_is_digest() {
+        //After padding, the chaining value is the digest itself, which is what length extension relies on
+        let mut hasher = Hash::SHA1.hasher();
+        hasher.update(&ascii_to_bytes("abc"));
+        hasher.update(&vec![0x80]);
+        hasher.update(&vec![0; 52]);
+        hasher.update(&24u64.to_be_bytes());
+        assert_eq!(bytes_to_hex(&hasher.export_state().chaining_value), "a9993e364706816aba3e25717850c26c9cd0d89d");
+    }
+
+    #[test]
+    #[should_panic(expected="Illegal chaining value length 16 for this hash!")]
+    fn test_hasher_import_bad_state() {
+        Hasher::import_state(Hash::SHA1, &HashState {chaining_value: vec![0; 16], byte_count: 0, buffer: vec![]});
+    }
+
+    #[test]
+    #[should_panic(expected="Buffer length 3 does not match byte count 64!")]
+    fn test_hasher_import_bad_buffer() {
+        Hasher::import_state(Hash::MD4, &HashState {chaining_value: vec![0; 16], byte_count: 64, buffer: vec![0; 3]});
     }
 }
\ No newline at end of file
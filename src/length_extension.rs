@@ -0,0 +1,118 @@
+use std::ops::Range;
+use crate::hash::{Hash, Hasher, HashState};
+use crate::mac::MAC;
+
+///Forged continuation of a hashed message, for one guess of the original message length
+pub struct LengthExtension {
+    ///Guessed length in bytes of the original message, including any secret prefix
+    pub original_length: usize,
+    ///Bytes to append to the original message: its glue padding, then the suffix
+    pub appended: Vec<u8>,
+    ///Digest of the original message followed by the appended bytes
+    pub digest: Vec<u8>
+}
+
+///Extends a Merkle-Damgard digest with the given suffix, without knowing the original message.
+///Since the padding depends on the original length, one extension is produced for every guessed length in the range.
+///Will panic if the hash function does not output its whole internal state.
+pub fn length_extend(hash: Hash, original_digest: &Vec<u8>, original_lengths: Range<usize>, suffix: &Vec<u8>) -> Vec<LengthExtension> {
+    match hash {
+        Hash::SHA1 | Hash::SHA256 | Hash::SHA512 | Hash::MD4 | Hash::MD5 | Hash::BAD16 => {},
+        _ => panic!("Length extension is not possible with this hash function!")
+    }
+
+    let mut extensions = vec![];
+    for original_length in original_lengths {
+        //After the glue padding, the digest is exactly the state the hash was in, on a block boundary
+        let mut appended = hash.padding(original_length as u64);
+        let mut hasher = Hasher::import_state(hash, &HashState {
+            chaining_value: original_digest.clone(),
+            byte_count: (original_length + appended.len()) as u64,
+            buffer: vec![]
+        });
+        hasher.update(suffix);
+        appended.extend_from_slice(suffix);
+
+        extensions.push(LengthExtension {
+            original_length,
+            appended,
+            digest: hasher.finalize()
+        });
+    }
+
+    return extensions;
+}
+
+///Forges a secret-prefix MAC on the original message followed by glue padding and the suffix.
+///Each key length in the range is tried until the verify function accepts the forgery.
+pub fn forge_prefix_mac<F: Fn(&MAC) -> bool>(hash: Hash, mac: &MAC, key_lengths: Range<usize>, suffix: &Vec<u8>, verify: F) -> Option<MAC> {
+    let original_lengths = key_lengths.start + mac.message.len()..key_lengths.end + mac.message.len();
+    for extension in length_extend(hash, &mac.signature, original_lengths, suffix) {
+        let mut message = mac.message.clone();
+        message.extend_from_slice(&extension.appended);
+        let forged = MAC {
+            message,
+            signature: extension.digest
+        };
+
+        if verify(&forged) {
+            return Some(forged);
+        }
+    }
+
+    return None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::ascii_to_bytes;
+    use crate::mac::{create_prefix_mac, verify_prefix_max};
+
+    #[test]
+    fn test_length_extend() {
+        let original = ascii_to_bytes("secret key and message");
+        let suffix = ascii_to_bytes(";admin=true;");
+        for hash in [Hash::SHA1, Hash::SHA256, Hash::SHA512, Hash::MD4, Hash::MD5, Hash::BAD16].iter() {
+            let extensions = length_extend(*hash, &hash.digest(&original), 20..25, &suffix);
+            assert_eq!(extensions.len(), 5);
+
+            //Only the extension with the right length guess matches the real hash
+            for extension in extensions.iter() {
+                let mut extended = original.clone();
+                extended.extend_from_slice(&extension.appended);
+                assert!(extension.appended.ends_with(&suffix));
+                assert_eq!(hash.digest(&extended) == extension.digest, extension.original_length == original.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_forge_prefix_mac() {
+        let key = ascii_to_bytes("unknown key");
+        let message = ascii_to_bytes("comment1=cooking%20MCs;userdata=foo");
+        let suffix = ascii_to_bytes(";admin=true;");
+        for hash in [Hash::SHA1, Hash::SHA256, Hash::SHA512, Hash::MD4, Hash::MD5].iter() {
+            let mac = create_prefix_mac(&message, &key, *hash);
+            let forged = forge_prefix_mac(*hash, &mac, 0..32, &suffix, |m| verify_prefix_max(m, &key, *hash)).unwrap();
+            assert!(forged.message.starts_with(&message));
+            assert!(forged.message.ends_with(&suffix));
+            assert!(verify_prefix_max(&forged, &key, *hash));
+        }
+    }
+
+    #[test]
+    fn test_forge_prefix_mac_wrong_range() {
+        let key = ascii_to_bytes("unknown key");
+        let message = ascii_to_bytes("comment1=cooking%20MCs;userdata=foo");
+        let mac = create_prefix_mac(&message, &key, Hash::SHA1);
+        let verify = |m: &MAC| verify_prefix_max(m, &key, Hash::SHA1);
+        assert!(forge_prefix_mac(Hash::SHA1, &mac, 0..key.len(), &ascii_to_bytes(";admin=true;"), verify).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected="Length extension is not possible with this hash function!")]
+    fn test_length_extend_sha3() {
+        length_extend(Hash::SHA3_256, &vec![0; 32], 0..16, &ascii_to_bytes("suffix"));
+    }
+}
\ No newline at end of file
@@ -9,6 +9,21 @@ pub struct MAC {
     pub signature: Vec<u8>
 }
 
+///Compares two byte strings in time which depends only on their lengths, not on where they first differ
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    //Accumulate every difference instead of stopping at the first one
+    let mut difference: u8 = 0;
+    for i in 0..a.len() {
+        difference |= a[i] ^ b[i];
+    }
+    //A volatile read stops the compiler from turning the loop back into an early exit
+    return unsafe {std::ptr::read_volatile(&difference)} == 0;
+}
+
 ///Creates a secret-prefix MAC for the given message using the given key + hash function
 pub fn create_prefix_mac(message: &Vec<u8>, key: &Vec<u8>, hash_function: Hash) -> MAC {
     let mut concat = key.clone();
@@ -25,22 +40,20 @@ pub fn verify_prefix_max(mac: &MAC, key: &Vec<u8>, hash_function: Hash) -> bool
     let mut concat = key.clone();
     concat.append(&mut mac.message.clone());
     let expected_hash = hash_function.digest(&concat);
-    return expected_hash == mac.signature;
+    return constant_time_eq(&expected_hash, &mac.signature);
 }
 
 ///Creates an HMAC for the given message using the given key + hash function
 pub fn create_hmac(message: &Vec<u8>, key: &Vec<u8>, hash_function: Hash) -> MAC {
-    let mut padded_key= vec![];
-
-    //Generate K'
-    if padded_key.len() <= hash_function.block_length() {
-        padded_key = key.clone();
-        while padded_key.len() < hash_function.block_length() {
-            padded_key.push(0);
-        }
+    //Generate K': keys longer than a block are hashed first, then zero padded to the block length
+    let mut padded_key = if key.len() > hash_function.block_length() {
+        hash_function.digest(&key)
     }
     else {
-        padded_key = hash_function.digest(&key);
+        key.clone()
+    };
+    while padded_key.len() < hash_function.block_length() {
+        padded_key.push(0);
     }
 
     //Compute inner block: (K' ^ ipad) || m
@@ -60,17 +73,15 @@ pub fn create_hmac(message: &Vec<u8>, key: &Vec<u8>, hash_function: Hash) -> MAC
 
 ///Validates an HMAC using the given key + hash function
 pub fn verify_hmac(mac: &MAC, key: &Vec<u8>, hash_function: Hash) -> bool {
-    let mut padded_key= vec![];
-
-    //Generate K'
-    if padded_key.len() <= hash_function.block_length() {
-        padded_key = key.clone();
-        while padded_key.len() < hash_function.block_length() {
-            padded_key.push(0);
-        }
+    //Generate K': keys longer than a block are hashed first, then zero padded to the block length
+    let mut padded_key = if key.len() > hash_function.block_length() {
+        hash_function.digest(&key)
     }
     else {
-        padded_key = hash_function.digest(&key);
+        key.clone()
+    };
+    while padded_key.len() < hash_function.block_length() {
+        padded_key.push(0);
     }
 
     //Compute inner block: (K' ^ ipad) || m
@@ -84,7 +95,7 @@ pub fn verify_hmac(mac: &MAC, key: &Vec<u8>, hash_function: Hash) -> bool {
     //Compute expected signature: H(outer block)
     let expected_signature = hash_function.digest(&outer_block);
 
-    return mac.signature == expected_signature;
+    return constant_time_eq(&mac.signature, &expected_signature);
 }
 
 ///Creates a CBC-MAC signature for the message with the given secret key and IV.
@@ -111,16 +122,72 @@ pub fn verify_cbc_mac(mac: &MAC, key: &Vec<u8>, iv: &Vec<u8>) -> bool {
     let num_blocks = ciphertext.len() / 16;
     let expected_signature = ciphertext[(num_blocks-1)*16..num_blocks*16].to_vec();
 
-    return expected_signature == mac.signature;
+    return constant_time_eq(&expected_signature, &mac.signature);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::converter::{ascii_to_bytes, bytes_to_hex, hex_to_bytes};
-    use crate::hash::Hash;
+    use crate::hash::{Hash, Hasher, HashState, digest_sha256_from_state};
     use crate::aes::encrypt_cbc;
     use crate::padding::pkcs7_pad;
+    use std::time::Instant;
+
+    ///Byte-by-byte comparison which does the same work per byte as constant_time_eq, but stops at the first difference
+    fn leaky_eq(a: &[u8], b: &[u8]) -> bool {
+        if a.len() != b.len() {
+            return false;
+        }
+
+        for i in 0..a.len() {
+            if a[i] != b[i] {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    ///Times guesses at the first byte of a value whose bytes are each repeated STRETCH times, so that comparing one
+    ///byte is long enough to time. Every candidate is sampled in turn, and the median times are compared.
+    ///Returns whether the real first byte takes more than twice as long as any wrong one.
+    fn first_byte_stands_out<F: Fn(&[u8], &[u8]) -> bool>(compare: F, real: &Vec<u8>) -> bool {
+        const STRETCH: usize = 1 << 16;
+        let stretched: Vec<u8> = real.iter().flat_map(|byte| vec![*byte; STRETCH]).collect();
+        let mut times = vec![vec![]; 64];
+        let mut accepted = 0;
+        for _i in 0..11 {
+            for byte in 0..64 {
+                let mut guess = vec![byte as u8; STRETCH];
+                guess.resize(stretched.len(), 0);
+                let start = Instant::now();
+                accepted += compare(&guess, &stretched) as usize;
+                times[byte].push(start.elapsed());
+            }
+        }
+        assert_eq!(accepted, 0);
+
+        let medians: Vec<_> = times.iter_mut().map(|t| {t.sort(); t[t.len() / 2]}).collect();
+        let slowest_wrong = (0..64).filter(|byte| *byte != real[0] as usize).map(|byte| medians[byte]).max().unwrap();
+        return medians[real[0] as usize] > slowest_wrong * 2;
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(&[], &[]));
+        assert!(constant_time_eq(&[1, 2, 3], &[1, 2, 3]));
+        assert!(!constant_time_eq(&[1, 2, 3], &[1, 2, 4]));
+        assert!(!constant_time_eq(&[1, 2, 3], &[0, 2, 3]));
+        assert!(!constant_time_eq(&[1, 2, 3], &[1, 2]));
+    }
+
+    #[test]
+    fn test_constant_time_eq_timing() {
+        //The leaky comparison takes longer when the first byte is right, so its timings give that byte away
+        let real = vec![0x2a, 0x17];
+        assert!(first_byte_stands_out(leaky_eq, &real));
+        assert!(!first_byte_stands_out(constant_time_eq, &real));
+    }
 
     #[test]
     fn test_create_prefix_mac() {
@@ -153,6 +220,79 @@ mod tests {
         assert!(!verify_prefix_max(&mac2, &key, Hash::SHA1));
     }
 
+    #[test]
+    fn test_prefix_mac_length_extension_sha256() {
+        //The SHA-256 digest is its whole state, so hashing can be resumed to forge a MAC for an extended message
+        let message = ascii_to_bytes("This is a test message to be signed");
+        let key = ascii_to_bytes("woop woop woop");
+        let suffix = ascii_to_bytes(";admin=true;");
+        let mac = create_prefix_mac(&message, &key, Hash::SHA256);
+
+        let mut h = [0 as u32; 8];
+        for i in 0..8 {
+            h[i] = u32::from_be_bytes([mac.signature[4*i], mac.signature[4*i+1], mac.signature[4*i+2], mac.signature[4*i+3]]);
+        }
+
+        let mut forged_message = message.clone();
+        forged_message.push(0x80);
+        while (key.len() + forged_message.len()) % 64 != 56 {
+            forged_message.push(0);
+        }
+        forged_message.append(&mut (((key.len() + message.len()) * 8) as u64).to_be_bytes().to_vec());
+        forged_message.append(&mut suffix.clone());
+
+        let forged = MAC {
+            signature: digest_sha256_from_state(&suffix, h, ((key.len() + forged_message.len()) * 8) as u64),
+            message: forged_message
+        };
+        assert!(verify_prefix_max(&forged, &key, Hash::SHA256));
+    }
+
+    #[test]
+    fn test_prefix_mac_length_extension_sha3() {
+        //The SHA-3 digest is only part of the sponge state, so the same forgery fails for every key length
+        let message = ascii_to_bytes("This is a test message to be signed");
+        let key = ascii_to_bytes("woop woop woop");
+        let suffix = ascii_to_bytes(";admin=true;");
+        let mac = create_prefix_mac(&message, &key, Hash::SHA3_256);
+        let rate = Hash::SHA3_256.block_length();
+
+        for key_length in 0..64 {
+            //Best guess at the state after the original message: the digest, with the unknown rest taken as zero
+            let mut chaining_value = mac.signature.clone();
+            chaining_value.resize(200, 0);
+
+            //Glue padding is SHA-3's own pad10*1, so the suffix starts a new block
+            let mut forged_message = message.clone();
+            forged_message.push(0x06);
+            while (key_length + forged_message.len()) % rate != 0 {
+                forged_message.push(0);
+            }
+            *forged_message.last_mut().unwrap() |= 0x80;
+
+            let mut hasher = Hasher::import_state(Hash::SHA3_256, &HashState {
+                chaining_value,
+                byte_count: (key_length + forged_message.len()) as u64,
+                buffer: vec![]
+            });
+            hasher.update(&suffix);
+            forged_message.append(&mut suffix.clone());
+
+            let forged = MAC {
+                message: forged_message,
+                signature: hasher.finalize()
+            };
+            assert!(!verify_prefix_max(&forged, &key, Hash::SHA3_256));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected="Illegal chaining value length 32 for this hash!")]
+    fn test_prefix_mac_sha3_digest_is_not_state() {
+        let mac = create_prefix_mac(&ascii_to_bytes("message"), &ascii_to_bytes("key"), Hash::SHA3_256);
+        Hasher::import_state(Hash::SHA3_256, &HashState {chaining_value: mac.signature, byte_count: 136, buffer: vec![]});
+    }
+
     #[test]
     fn test_create_hmac() {
         let message = ascii_to_bytes("The quick brown fox jumps over the lazy dog");
@@ -163,6 +303,29 @@ mod tests {
         assert_eq!(bytes_to_hex(&mac.signature), "de7c9b85b8b78aa6bc8a7a36f70a90701c9db4d9");
     }
 
+    #[test]
+    fn test_create_hmac_other_hashes() {
+        let message = ascii_to_bytes("The quick brown fox jumps over the lazy dog");
+        let key = ascii_to_bytes("key");
+
+        assert_eq!(bytes_to_hex(&create_hmac(&message, &key, Hash::MD5).signature), "80070713463e7749b90c2dc24911e275");
+        assert_eq!(bytes_to_hex(&create_hmac(&message, &key, Hash::SHA512).signature), "b42af09057bac1e2d41708e48a902e09b5ff7f12ab428a4fe86653c73dd248fb82f948a549f7b791a5b41915ee4d1ec3935357e4e2317250d0372afa2ebeeb3a");
+    }
+
+    #[test]
+    fn test_create_hmac_long_key() {
+        //RFC 4231 test case 6: keys longer than the block length are hashed first
+        let message = ascii_to_bytes("Test Using Larger Than Block-Size Key - Hash Key First");
+        let key = vec![0xaa; 131];
+
+        assert_eq!(bytes_to_hex(&create_hmac(&message, &key, Hash::SHA1).signature), "90d0dace1c1bdc957339307803160335bde6df2b");
+        assert_eq!(bytes_to_hex(&create_hmac(&message, &key, Hash::SHA224).signature), "95e9a0db962095adaebe9b2d6f0dbce2d499f112f2d2b7273fa6870e");
+        assert_eq!(bytes_to_hex(&create_hmac(&message, &key, Hash::SHA256).signature), "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54");
+        assert_eq!(bytes_to_hex(&create_hmac(&message, &key, Hash::SHA384).signature), "4ece084485813e9088d2c63a041bc5b44f9ef1012a2b588f3cd11f05033ac4c60c2ef6ab4030fe8296248df163f44952");
+        assert_eq!(bytes_to_hex(&create_hmac(&message, &key, Hash::SHA512).signature), "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f3526b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598");
+        assert_eq!(bytes_to_hex(&create_hmac(&message, &key, Hash::MD5).signature), "bfecaf4efff90a3a668f3922fec3762d");
+    }
+
     #[test]
     fn test_verify_hmac() {
         let message = ascii_to_bytes("The quick brown fox jumps over the lazy dog");
@@ -10,32 +10,38 @@
 //Use Rocket & http crates for challenges that involve requests to an HTTP server.
 #[macro_use] extern crate rocket;
 
-/* Dependencies for timing attack code
-use crate::converter::bytes_to_hex;
-use std::fs::File;
-use std::io::Read;
-use crate::mac::create_hmac;
-use crate::hash::Hash;
-*/
-
 
 //Alphabetic cipher module for other crypto challenges
 mod alphabetic;
 
 //Utility modules for the challenges to use
 mod aes;
+mod bit_matrix;
 mod converter;
+mod der;
 mod diffie_hellman;
 mod dsa;
+mod ec;
+mod ecb_oracle;
+mod ecdh;
+mod ecdsa;
+mod gf128;
 mod hash;
+mod length_extension;
 mod mac;
+mod manger_attack;
 mod math_tools;
 mod mt19937;
+mod oracle_server;
 mod padding;
+mod padding_oracle;
+mod polynomial;
 mod rc4;
 mod rsa;
+mod rsa_fault;
 mod srp;
 mod srp_simple;
+mod timing_attack;
 mod xor;
 
 //Modules containing challenge solutions
@@ -70,9 +76,7 @@ mod challenge27;
 mod challenge29;
 mod challenge30;
 mod challenge31;
-//Challenge 32 code is in challenge31 module (just made the tweaks in-place)
-//Commented-out code in main routine should be used to run the timing channel attack
-//since it takes so long the test suite gives up. (Approx runtime on my machine: 4hr)
+//Challenge 32 code is in challenge31 module (same server, with a shorter delay)
 //Challenge 33 was implementing the diffie_hellman module - no additional code needed
 mod challenge34;
 mod challenge35;
@@ -98,21 +102,24 @@ mod challenge54;
 mod challenge55;
 mod challenge56;
 mod challenge57;
+mod challenge58;
+mod challenge59;
+mod challenge60;
+mod challenge61;
+mod challenge62;
+mod challenge63;
+mod challenge64;
 
 fn main() {
-    /*let file = File::open("challenge10.txt");
-    let mut contents: Vec<u8> = vec![];
-
-    match file {
-        Ok(mut f) => {
-            f.read_to_end(&mut contents).unwrap();
-        },
-        Err(_) => ()
-    };
-
-    let mac = create_hmac(&contents, &challenge31::KEY, Hash::MD4);
-    println!("Expected: {}", bytes_to_hex(&mac.signature));
-    println!("Timing attack yielded: {}", bytes_to_hex(&challenge31::challenge31()));*/
+    //Running with "serve [port]" hosts the challenge oracles over HTTP on localhost
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() > 1 && args[1] == "serve" {
+        let port = match args.get(2) {
+            None => oracle_server::DEFAULT_PORT,
+            Some(port) => port.parse().expect("Port must be a number.")
+        };
+        oracle_server::launch(port);
+    }
 }
 
 
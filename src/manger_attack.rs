@@ -0,0 +1,119 @@
+use gmp::mpz::Mpz;
+use crate::converter::hex_to_bytes;
+
+///Returns lowest value x >= numerator/denominator
+fn ceiling(numerator: &Mpz, denominator: &Mpz) -> Mpz {
+    let quotient = numerator / denominator;
+    return if &quotient * denominator != *numerator {
+        &quotient + Mpz::one()
+    }
+    else {
+        quotient
+    }
+}
+
+///Recovers the plaintext of an RSA ciphertext with Manger's chosen-ciphertext attack.
+///The oracle decrypts a ciphertext and returns true if the plaintext is below B = 2^(8(k-1)) for a k byte modulus,
+///i.e. if its first byte is zero. OAEP decryption leaks this if the first byte is checked apart from the rest.
+///The plaintext must also be below B, as every OAEP padded message is. Returns the plaintext as k bytes.
+///Will panic if n < 2B, as the attack does not work then.
+pub fn manger_attack<F: Fn(&Mpz) -> bool>(n: &Mpz, e: &Mpz, ciphertext: &Mpz, oracle: F) -> Vec<u8> {
+    let k = (n.bit_length() + 7) / 8;
+    let big_b = Mpz::one() << 8 * (k - 1);
+    let two_b = &big_b << 1;
+    if *n < two_b {
+        panic!("Modulus is too close to a whole number of bytes for Manger's attack.");
+    }
+
+    //Checks if f * m is below B, by asking the oracle to decrypt f^e * c
+    let below_b = |f: &Mpz| oracle(&(ciphertext * f.powm(e, n)).modulus(n));
+
+    //Step 1: double f1 until f1 * m is in [B, 2B), so (f1/2) * m is in [B/2, B)
+    let mut f1 = Mpz::from(2);
+    while below_b(&f1) {
+        f1 = f1 << 1;
+    }
+    let half_f1 = &f1 >> 1;
+
+    //Step 2: step f2 by f1/2 from just above n/B until f2 * m wraps around into [n, n + B)
+    let mut f2 = ((n + &big_b) / &big_b) * &half_f1;
+    while !below_b(&f2) {
+        f2 = f2 + &half_f1;
+    }
+
+    //Step 3: narrow down m in [n/f2, (n + B)/f2], choosing each multiplier so that f3 * m lands near the
+    //boundary at i * n + B, where the oracle's answer halves the range
+    let mut m_min = ceiling(n, &f2);
+    let mut m_max = (n + &big_b) / &f2;
+    while m_min < m_max {
+        let f_tmp = &two_b / (&m_max - &m_min);
+        let i = (&f_tmp * &m_min) / n;
+        let boundary = &i * n + &big_b;
+        let f3 = ceiling(&(&i * n), &m_min);
+        if below_b(&f3) {
+            m_max = &boundary / &f3;
+        }
+        else {
+            m_min = ceiling(&boundary, &f3);
+        }
+    }
+
+    let plaintext = hex_to_bytes(&m_min.to_str_radix(16));
+    let mut padded = vec![0x00; k - plaintext.len()];
+    padded.extend_from_slice(&plaintext);
+    return padded;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use crate::converter::ascii_to_bytes;
+    use crate::hash::Hash;
+    use crate::padding::{oaep_pad, oaep_unpad};
+    use crate::rsa::RSA;
+
+    ///Decrypts the ciphertext and reports whether the first byte of the plaintext was zero, as an OAEP decryption
+    ///which rejects that case with a distinct error (or more quickly) would
+    fn first_byte_oracle(rsa: &RSA, ciphertext: &Mpz) -> bool {
+        let k = (rsa.n.bit_length() + 7) / 8;
+        //RSA decryption drops leading zero bytes, so a zero first byte makes the plaintext shorter
+        return rsa.decrypt(&hex_to_bytes(&ciphertext.to_str_radix(16))).len() < k;
+    }
+
+    #[test]
+    fn test_manger_attack() {
+        let rsa = RSA::builder(1024).public_exponent(&Mpz::from(65537)).build();
+        let message = ascii_to_bytes("Manger's attack needs about one query per bit");
+        let label = ascii_to_bytes("cryptopals");
+        let padded = oaep_pad(&message, &label, 1024, Hash::SHA256);
+        let ciphertext = Mpz::from(&rsa.encrypt(&padded)[0..]);
+
+        let queries = Cell::new(0);
+        let oracle = |c: &Mpz| {
+            queries.set(queries.get() + 1);
+            first_byte_oracle(&rsa, c)
+        };
+
+        let recovered = manger_attack(&rsa.n, &rsa.e, &ciphertext, oracle);
+        assert_eq!(recovered, padded);
+        assert_eq!(oaep_unpad(&recovered, &label, 1024, Hash::SHA256).unwrap(), message);
+        assert!(queries.get() < 2000);
+    }
+
+    #[test]
+    fn test_manger_attack_small_exponent() {
+        let rsa = RSA::builder(1024).public_exponent(&Mpz::from(17)).build();
+        let padded = oaep_pad(&ascii_to_bytes("Small exponents make no difference"), &vec![], 1024, Hash::SHA1);
+        let ciphertext = Mpz::from(&rsa.encrypt(&padded)[0..]);
+
+        assert_eq!(manger_attack(&rsa.n, &rsa.e, &ciphertext, |c: &Mpz| first_byte_oracle(&rsa, c)), padded);
+    }
+
+    #[test]
+    #[should_panic(expected="Modulus is too close to a whole number of bytes for Manger's attack.")]
+    fn test_manger_attack_short_modulus() {
+        let n = (Mpz::one() << 1024) + Mpz::one();
+        manger_attack(&n, &Mpz::from(3), &Mpz::from(2), |_c: &Mpz| true);
+    }
+}
\ No newline at end of file
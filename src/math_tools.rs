@@ -61,6 +61,288 @@ pub fn chinese_remainder_theorem(residues: &Vec<Mpz>, moduli: &Vec<Mpz>) -> Mpz
     return chinese_remainder_theorem(&new_residues, &new_moduli);
 }
 
+///Chooses k for the kangaroo jump function f(y) = 2^(y mod k), so that the mean jump
+///(2^k - 1)/k is at least sqrt(b - a)/2
+pub fn kangaroo_jump_count(a: &Mpz, b: &Mpz) -> u64 {
+    let half_root = b.clone().sub(a).sqrt().div(Mpz::from(2));
+    let mut k: u64 = 1;
+    while (Mpz::one() << (k as usize)).sub(Mpz::one()) < half_root.clone().mul(Mpz::from(k)) {
+        k += 1;
+    }
+    return k;
+}
+
+///Finds x in [a, b] with g^x = y mod p using Pollard's kangaroo algorithm, or returns None if there is no such x
+pub fn pollard_kangaroo(g: &Mpz, y: &Mpz, p: &Mpz, a: &Mpz, b: &Mpz) -> Option<Mpz> {
+    return pollard_kangaroo_with_jumps(g, y, p, a, b, kangaroo_jump_count(a, b));
+}
+
+///Pollard's kangaroo algorithm with the jump function f(y) = 2^(y mod k) for the given k.
+///The tame kangaroo starts at g^b and makes 4 * (mean jump) jumps, leaving a trap at the end.
+pub fn pollard_kangaroo_with_jumps(g: &Mpz, y: &Mpz, p: &Mpz, a: &Mpz, b: &Mpz, k: u64) -> Option<Mpz> {
+    return kangaroo_in_group(g, &y.modulus(p), a, b, k,
+        |x: &Mpz, z: &Mpz| x.mul(z).modulus(p),
+        |x: &Mpz, e: &Mpz| x.powm(e, p),
+        |x: &Mpz| x.clone());
+}
+
+///Pollard's kangaroo algorithm in any group, written multiplicatively here: finds x in [a, b] with g^x = y,
+///or returns None if there is no such x. The group is given by its operation, its exponentiation (or scalar
+///multiplication), and a label mapping each element to a number, which picks the jump f(y) = 2^(label mod k).
+pub fn kangaroo_in_group<T, C, P, L>(g: &T, y: &T, a: &Mpz, b: &Mpz, k: u64, combine: C, power: P, label: L) -> Option<Mpz>
+    where T: Clone + PartialEq, C: Fn(&T, &T) -> T, P: Fn(&T, &Mpz) -> T, L: Fn(&T) -> Mpz {
+    let k_mpz = Mpz::from(k);
+    let jump = |element: &T| -> usize {
+        return Option::<u64>::from(&label(element).modulus(&k_mpz)).unwrap() as usize;
+    };
+
+    //Precompute g^(2^j) for each possible jump
+    let mut jumps = vec![g.clone()];
+    for j in 1..k as usize {
+        jumps.push(combine(&jumps[j - 1], &jumps[j - 1]));
+    }
+
+    //Run the tame kangaroo
+    let steps = 4 * ((1 << k) - 1) / k;
+    let mut tame_distance = Mpz::zero();
+    let mut tame = power(g, b);
+    for _i in 0..steps {
+        let j = jump(&tame);
+        tame_distance.add_assign(Mpz::one() << j);
+        tame = combine(&tame, &jumps[j]);
+    }
+
+    //Run the wild kangaroo until it lands in the trap or passes it
+    let limit = b.clone().sub(a).add(&tame_distance);
+    let mut wild_distance = Mpz::zero();
+    let mut wild = y.clone();
+    while wild_distance <= limit {
+        if wild == tame {
+            return Some(b.clone().add(&tame_distance).sub(&wild_distance));
+        }
+        let j = jump(&wild);
+        wild_distance.add_assign(Mpz::one() << j);
+        wild = combine(&wild, &jumps[j]);
+    }
+
+    return None;
+}
+
+///Solves g^x = y mod p with the Pohlig-Hellman algorithm, where p - 1 is the product of the given
+///distinct primes. Each prime's subgroup is searched exhaustively, so they should all be small.
+///Returns None if y is not a power of g.
+pub fn pohlig_hellman(g: &Mpz, y: &Mpz, p: &Mpz, factors: &Vec<u64>) -> Option<Mpz> {
+    let order = p.clone().sub(Mpz::one());
+    let mut residues = vec![];
+    let mut moduli = vec![];
+
+    for &r in factors {
+        //Project g and y into the subgroup of order r and search it for x mod r
+        let cofactor = order.clone().div(Mpz::from(r));
+        let g_r = g.powm(&cofactor, p);
+        let y_r = y.powm(&cofactor, p);
+
+        let mut power = Mpz::one();
+        let mut found = None;
+        for k in 0..r {
+            if power.eq(&y_r) {
+                found = Some(k);
+                break;
+            }
+            power = power.mul(&g_r).modulus(p);
+        }
+
+        match found {
+            None => return None,
+            Some(k) => {
+                residues.push(Mpz::from(k));
+                moduli.push(Mpz::from(r));
+            }
+        }
+    }
+
+    let x = chinese_remainder_theorem(&residues, &moduli);
+    if g.powm(&x, p).ne(&y.modulus(p)) {
+        return None;
+    }
+    return Some(x);
+}
+
+///Computes the dot product of two integer vectors
+fn dot_product(a: &Vec<Mpz>, b: &Vec<Mpz>) -> Mpz {
+    let mut sum = Mpz::zero();
+    for i in 0..a.len() {
+        sum.add_assign(&a[i] * &b[i]);
+    }
+    return sum;
+}
+
+///Reduces a lattice basis (given as rows) with the LLL algorithm, using delta = 99/100.
+///Uses Cohen's integral variant (A Course in Computational Algebraic Number Theory, algorithm 2.6.7),
+///which tracks the Gram-Schmidt coefficients as exact integers d_i and lambda_ij instead of rationals.
+///Will panic if the rows are linearly dependent.
+pub fn lll(basis: &Vec<Vec<Mpz>>) -> Vec<Vec<Mpz>> {
+    let n = basis.len();
+    if n < 2 {
+        return basis.clone();
+    }
+
+    //Work with 1-based indices to match the algorithm description
+    let mut b = vec![vec![]];
+    b.extend(basis.iter().cloned());
+    let mut lambda = vec![vec![Mpz::zero(); n + 1]; n + 1];
+    let mut d = vec![Mpz::zero(); n + 1];
+    d[0] = Mpz::one();
+    d[1] = dot_product(&b[1], &b[1]);
+
+    let mut k = 2;
+    let mut k_max = 1;
+    while k <= n {
+        //Incremental Gram-Schmidt
+        if k > k_max {
+            k_max = k;
+            for j in 1..=k {
+                let mut u = dot_product(&b[k], &b[j]);
+                for i in 1..j {
+                    u = (&d[i] * &u - &lambda[k][i] * &lambda[j][i]).div(&d[i - 1]);
+                }
+                if j < k {
+                    lambda[k][j] = u;
+                }
+                else {
+                    if u.eq(&Mpz::zero()) {
+                        panic!("Linearly dependent vectors passed.");
+                    }
+                    d[k] = u;
+                }
+            }
+        }
+
+        lll_reduce(&mut b, &mut lambda, &d, k, k - 1);
+
+        //Lovasz condition, scaled by 100 d_(k-1)^2 to stay in the integers
+        let left = Mpz::from(100).mul(&d[k]).mul(&d[k - 2]);
+        let right = Mpz::from(99).mul(&d[k - 1]).mul(&d[k - 1]).sub(Mpz::from(100).mul(&lambda[k][k - 1]).mul(&lambda[k][k - 1]));
+        if left < right {
+            //Swap b_k and b_(k-1), updating the coefficients
+            b.swap(k, k - 1);
+            for j in 1..k - 1 {
+                let tmp = lambda[k][j].clone();
+                lambda[k][j] = lambda[k - 1][j].clone();
+                lambda[k - 1][j] = tmp;
+            }
+            let l = lambda[k][k - 1].clone();
+            let big_b = (&d[k - 2] * &d[k] + &l * &l).div(&d[k - 1]);
+            for i in k + 1..=k_max {
+                let t = lambda[i][k].clone();
+                lambda[i][k] = (&d[k] * &lambda[i][k - 1] - &l * &t).div(&d[k - 1]);
+                lambda[i][k - 1] = (&big_b * &t + &l * &lambda[i][k]).div(&d[k]);
+            }
+            d[k - 1] = big_b;
+
+            if k > 2 {
+                k -= 1;
+            }
+        }
+        else {
+            for l in (1..k - 1).rev() {
+                lll_reduce(&mut b, &mut lambda, &d, k, l);
+            }
+            k += 1;
+        }
+    }
+
+    return b[1..].to_vec();
+}
+
+///Size-reduces b_k against b_l, subtracting the nearest integer multiple of b_l
+fn lll_reduce(b: &mut Vec<Vec<Mpz>>, lambda: &mut Vec<Vec<Mpz>>, d: &Vec<Mpz>, k: usize, l: usize) {
+    if Mpz::from(2).mul(&lambda[k][l]).abs() <= d[l] {
+        return;
+    }
+
+    //q = round(lambda_kl / d_l)
+    let q = (Mpz::from(2).mul(&lambda[k][l]).add(&d[l])).div_floor(&Mpz::from(2).mul(&d[l]));
+    for i in 0..b[k].len() {
+        let delta = &q * &b[l][i];
+        b[k][i] = &b[k][i] - delta;
+    }
+    lambda[k][l] = &lambda[k][l] - &q * &d[l];
+    for i in 1..l {
+        let delta = &q * &lambda[l][i];
+        lambda[k][i] = &lambda[k][i] - delta;
+    }
+}
+
+///Finds the distinct prime factors of n below the bound by trial division
+pub fn small_prime_factors(n: &Mpz, bound: u64) -> Vec<u64> {
+    let mut factors: Vec<u64> = vec![];
+    for r in 2..bound {
+        if n.modulus(&Mpz::from(r)).eq(&Mpz::zero()) && factors.iter().all(|f| r % f != 0) {
+            factors.push(r);
+        }
+    }
+    return factors;
+}
+
+///Finds a square root of n modulo the odd prime p using the Tonelli-Shanks algorithm.
+///Returns None if n is not a quadratic residue.
+pub fn sqrt_mod(n: &Mpz, p: &Mpz) -> Option<Mpz> {
+    let n = n.modulus(p);
+    let one = Mpz::one();
+    let p_minus_one = p.clone().sub(&one);
+    if n.eq(&Mpz::zero()) {
+        return Some(n);
+    }
+
+    //Euler's criterion: n is a residue iff n^((p-1)/2) = 1
+    let euler_exponent = p_minus_one.clone().div(Mpz::from(2));
+    if n.powm(&euler_exponent, p).ne(&one) {
+        return None;
+    }
+
+    //Write p - 1 = q * 2^s with q odd
+    let mut q = p_minus_one.clone();
+    let mut s = 0;
+    while !q.tstbit(0) {
+        q = q.div(Mpz::from(2));
+        s += 1;
+    }
+
+    //Find any quadratic non-residue z
+    let mut z = Mpz::from(2);
+    while z.powm(&euler_exponent, p).ne(&p_minus_one) {
+        z.add_assign(&one);
+    }
+
+    let mut m = s;
+    let mut c = z.powm(&q, p);
+    let mut t = n.powm(&q, p);
+    let mut r = n.powm(&q.clone().add(&one).div(Mpz::from(2)), p);
+
+    while t.ne(&one) {
+        //Find the least i with t^(2^i) = 1
+        let mut i = 0;
+        let mut t_power = t.clone();
+        while t_power.ne(&one) {
+            t_power = t_power.clone().mul(&t_power).modulus(p);
+            i += 1;
+        }
+
+        let mut b = c.clone();
+        for _j in 0..(m - i - 1) {
+            b = b.clone().mul(&b).modulus(p);
+        }
+
+        m = i;
+        c = b.clone().mul(&b).modulus(p);
+        t = t.mul(&c).modulus(p);
+        r = r.mul(&b).modulus(p);
+    }
+
+    return Some(r);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -81,4 +363,80 @@ mod tests {
     fn test_chinese_remainder_theorem() {
         assert_eq!(chinese_remainder_theorem(&vec![Mpz::from(2), Mpz::from(3), Mpz::from(2)], &vec![Mpz::from(3), Mpz::from(5), Mpz::from(7)]), Mpz::from(23));
     }
+
+    #[test]
+    fn test_sqrt_mod() {
+        //p = 1 mod 8 exercises the full Tonelli-Shanks loop
+        let p = Mpz::from(1000000009);
+        let n = Mpz::from(123456789);
+        let square = n.clone().mul(&n).modulus(&p);
+        let root = sqrt_mod(&square, &p).unwrap();
+        assert!(root.eq(&n) || root.eq(&p.clone().sub(&n)));
+
+        let p = Mpz::from_str_radix("233970423115425145524320034830162017933", 10).unwrap();
+        let root = sqrt_mod(&Mpz::from(7), &p).unwrap();
+        assert_eq!(root.clone().mul(&root).modulus(&p), Mpz::from(7));
+
+        assert_eq!(sqrt_mod(&Mpz::from(3), &Mpz::from(7)), None);
+        assert_eq!(sqrt_mod(&Mpz::zero(), &Mpz::from(7)), Some(Mpz::zero()));
+    }
+
+    #[test]
+    fn test_small_prime_factors() {
+        let n = Mpz::from(2 * 2 * 3 * 101 * 65537 as u64);
+        assert_eq!(small_prime_factors(&n, 1000), vec![2, 3, 101]);
+        assert_eq!(small_prime_factors(&Mpz::from(1009), 1000), vec![]);
+    }
+
+    #[test]
+    fn test_pollard_kangaroo() {
+        let p = Mpz::from_str_radix("11470374874925275658116663507232161402086650258453896274534991676898999262641581519101074740642369848233294239851519212341844337347119899874391456329785623", 10).unwrap();
+        let g = Mpz::from_str_radix("622952335333961296978159266084741085889881358738459939978290179936063635566740258555167783009058567397963466103140082647486611657350811560630587013183357", 10).unwrap();
+        let y = Mpz::from_str_radix("7760073848032689505395005705677365876654629189298052775754597607446617558600394076764814236081991643094239886772481052254010323780165093955236429914607119", 10).unwrap();
+
+        assert_eq!(pollard_kangaroo(&g, &y, &p, &Mpz::zero(), &Mpz::from(1 << 20)), Some(Mpz::from(705485)));
+        assert_eq!(pollard_kangaroo_with_jumps(&g, &y, &p, &Mpz::zero(), &Mpz::from(1 << 20), 12), Some(Mpz::from(705485)));
+        assert_eq!(pollard_kangaroo(&g, &y, &p, &Mpz::from(1 << 21), &Mpz::from(1 << 22)), None);
+    }
+
+    #[test]
+    fn test_pohlig_hellman() {
+        //p - 1 = 2 * 3 * 5 * 7 * 11 * 17 * 23 * 29 * 31 * 37 * 41, and 3 generates Z_p*
+        let factors = vec![2, 3, 5, 7, 11, 17, 23, 29, 31, 37, 41];
+        let p = Mpz::from(1231782443431 as u64);
+        let g = Mpz::from(3);
+        let x = Mpz::from(123456789012 as u64);
+        let y = g.powm(&x, &p);
+
+        assert_eq!(pohlig_hellman(&g, &y, &p, &factors), Some(x));
+        assert_eq!(pohlig_hellman(&g, &Mpz::zero(), &p, &factors), None);
+    }
+
+    #[test]
+    fn test_lll() {
+        //Example from the cryptopals challenge 62 description, scaled by 2 to clear the fractions
+        let to_mpz = |rows: Vec<Vec<i64>>| -> Vec<Vec<Mpz>> {
+            rows.iter().map(|row| row.iter().map(|x| Mpz::from(*x)).collect()).collect()
+        };
+        let basis = to_mpz(vec![
+            vec![-4, 0, 4, 0],
+            vec![1, -2, 0, 0],
+            vec![-2, 0, -4, 1],
+            vec![-2, 2, 2, 4]
+        ]);
+        let expected = to_mpz(vec![
+            vec![1, -2, 0, 0],
+            vec![-2, 0, -4, 1],
+            vec![-1, 0, 2, 4],
+            vec![-3, -2, 4, 0]
+        ]);
+
+        assert_eq!(lll(&basis), expected);
+    }
+
+    #[test]
+    #[should_panic(expected="Linearly dependent vectors passed.")]
+    fn test_lll_dependent() {
+        lll(&vec![vec![Mpz::one(), Mpz::one()], vec![Mpz::from(2), Mpz::from(2)]]);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,263 @@
+use rocket::Rocket;
+use rocket::config::{Config, Environment};
+use rocket::http::Status;
+use crate::converter::try_hex_to_bytes;
+use crate::{challenge12, challenge14, challenge16, challenge17, challenge26, challenge46, challenge47, challenge51};
+
+///Port to listen on if none is given
+pub const DEFAULT_PORT: u16 = 8000;
+
+///Block size of the AES CBC oracles
+const BLOCK_SIZE: usize = 16;
+
+///Formats bytes as hex with two digits for every byte, so that any client can parse it
+fn to_hex(bytes: &Vec<u8>) -> String {
+    return bytes.iter().map(|b| format!("{:02x}", b)).collect();
+}
+
+///Parses a hex parameter, rejecting the request if it is not valid hex with an even number of digits
+fn parse_hex(hex: &str) -> Result<Vec<u8>, Status> {
+    return try_hex_to_bytes(hex).map_err(|_| Status::BadRequest);
+}
+
+///Parses a hex parameter holding CBC ciphertext, rejecting the request if it is not a nonzero number of whole blocks
+fn parse_blocks(hex: &str) -> Result<Vec<u8>, Status> {
+    let bytes = parse_hex(hex)?;
+    if bytes.len() == 0 || bytes.len() % BLOCK_SIZE != 0 {
+        return Err(Status::BadRequest);
+    }
+    return Ok(bytes);
+}
+
+///Challenge 12: AES-ECB encryption of message || secret
+#[get("/challenge12/encrypt?<message>")]
+fn challenge12_encrypt(message: String) -> Result<String, Status> {
+    return Ok(to_hex(&challenge12::oracle(&parse_hex(&message)?)));
+}
+
+///Challenge 14: AES-ECB encryption of random prefix || message || secret
+#[get("/challenge14/encrypt?<message>")]
+fn challenge14_encrypt(message: String) -> Result<String, Status> {
+    return Ok(to_hex(&challenge14::oracle(&parse_hex(&message)?)));
+}
+
+///Challenge 16: AES-CBC encrypted token containing the given (escaped) user data
+#[get("/challenge16/token?<userdata>")]
+fn challenge16_token(userdata: String) -> String {
+    return to_hex(&challenge16::create_token(&userdata));
+}
+
+///Challenge 16: checks whether a token decrypts to one containing ";admin=true;"
+#[get("/challenge16/admin?<token>")]
+fn challenge16_admin(token: String) -> Result<String, Status> {
+    return Ok(challenge16::is_admin(&parse_blocks(&token)?).to_string());
+}
+
+///Challenge 17: a random secret, AES-CBC encrypted, along with its IV
+#[get("/challenge17/ciphertext")]
+fn challenge17_ciphertext() -> String {
+    let (ciphertext, iv) = challenge17::get_random_ciphertext();
+    return format!("ciphertext={}\niv={}", to_hex(&ciphertext), to_hex(&iv));
+}
+
+///Challenge 17: padding oracle, which checks whether the ciphertext decrypts with valid PKCS#7 padding
+#[get("/challenge17/check?<ciphertext>&<iv>")]
+fn challenge17_check(ciphertext: String, iv: String) -> Result<String, Status> {
+    let iv = parse_hex(&iv)?;
+    if iv.len() != BLOCK_SIZE {
+        return Err(Status::BadRequest);
+    }
+    return Ok(challenge17::padding_oracle(&parse_blocks(&ciphertext)?, &iv).to_string());
+}
+
+///Challenge 26: AES-CTR encrypted token containing the given (escaped) user data
+#[get("/challenge26/token?<userdata>")]
+fn challenge26_token(userdata: String) -> String {
+    return to_hex(&challenge26::create_token(&userdata));
+}
+
+///Challenge 26: checks whether a token decrypts to one containing ";admin=true;"
+#[get("/challenge26/admin?<token>")]
+fn challenge26_admin(token: String) -> Result<String, Status> {
+    return Ok(challenge26::is_admin(&parse_hex(&token)?).to_string());
+}
+
+///Challenge 46: RSA public key, as hex
+#[get("/challenge46/public_key")]
+fn challenge46_public_key() -> String {
+    let rsa = &challenge46::RSA_SERVER;
+    return format!("n={}\ne={}", rsa.n.to_str_radix(16), rsa.e.to_str_radix(16));
+}
+
+///Challenge 46: RSA encryption of the secret message
+#[get("/challenge46/ciphertext")]
+fn challenge46_ciphertext() -> String {
+    return to_hex(&challenge46::get_original_ciphertext());
+}
+
+///Challenge 46: parity oracle, which checks whether the ciphertext decrypts to an odd number
+#[get("/challenge46/is_odd?<ciphertext>")]
+fn challenge46_is_odd(ciphertext: String) -> Result<String, Status> {
+    return Ok(challenge46::parity_oracle(&parse_hex(&ciphertext)?).to_string());
+}
+
+///Challenge 47: RSA public key, as hex
+#[get("/challenge47/public_key")]
+fn challenge47_public_key() -> String {
+    let rsa = &challenge47::RSA_SERVER;
+    return format!("n={}\ne={}", rsa.n.to_str_radix(16), rsa.e.to_str_radix(16));
+}
+
+///Challenge 47: RSA encryption of the PKCS#1.5 padded secret message
+#[get("/challenge47/ciphertext")]
+fn challenge47_ciphertext() -> String {
+    return to_hex(&challenge47::generate_ciphertext());
+}
+
+///Challenge 47: PKCS#1.5 oracle, which checks whether the ciphertext decrypts to 00 02 ...
+#[get("/challenge47/check?<ciphertext>")]
+fn challenge47_check(ciphertext: String) -> Result<String, Status> {
+    return Ok(challenge47::pkcs_oracle(&parse_hex(&ciphertext)?).to_string());
+}
+
+///Challenge 51: length of the compressed and encrypted request containing the secret session ID and the message
+#[get("/challenge51/length?<message>")]
+fn challenge51_length(message: String) -> Result<String, Status> {
+    return Ok(challenge51::compression_oracle(&parse_hex(&message)?).to_string());
+}
+
+///Builds a server hosting every challenge oracle on localhost at the given port.
+///Binary parameters and results are hex, and yes/no oracles answer "true" or "false".
+pub fn oracle_server(port: u16) -> Rocket {
+    let config = Config::build(Environment::Development)
+        .address("127.0.0.1")
+        .port(port)
+        .finalize()
+        .expect("Invalid oracle server configuration.");
+
+    return rocket::custom(config).mount("/", routes![
+        challenge12_encrypt,
+        challenge14_encrypt,
+        challenge16_token, challenge16_admin,
+        challenge17_ciphertext, challenge17_check,
+        challenge26_token, challenge26_admin,
+        challenge46_public_key, challenge46_ciphertext, challenge46_is_odd,
+        challenge47_public_key, challenge47_ciphertext, challenge47_check,
+        challenge51_length
+    ]);
+}
+
+///Runs the oracle server on the given port. Only returns if the server fails to start.
+pub fn launch(port: u16) {
+    let error = oracle_server(port).launch();
+    panic!("Oracle server failed to launch: {}", error);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gmp::mpz::Mpz;
+    use rocket::local::Client;
+    use crate::converter::{ascii_to_bytes, bytes_to_ascii, hex_to_bytes};
+    use crate::ecb_oracle::ecb_byte_at_a_time;
+    use crate::padding::pkcs7_unpad;
+    use crate::padding_oracle::padding_oracle_decrypt;
+
+    ///Sends a GET request, returning the status and body
+    fn get(client: &Client, uri: String) -> (Status, String) {
+        let mut response = client.get(uri).dispatch();
+        return (response.status(), response.body_string().unwrap_or_default());
+    }
+
+    ///Splits a "key=value" response into the value for each line
+    fn values(body: &str) -> Vec<String> {
+        return body.lines().map(|line| line.split('=').nth(1).unwrap().to_string()).collect();
+    }
+
+    #[test]
+    fn test_to_hex() {
+        assert_eq!(to_hex(&vec![0x00, 0x0f, 0xa0]), "000fa0");
+        assert_eq!(to_hex(&vec![]), "");
+    }
+
+    #[test]
+    fn test_ecb_oracles() {
+        let client = Client::new(oracle_server(DEFAULT_PORT)).expect("valid rocket instance");
+        for challenge in [12, 14].iter() {
+            let oracle = |m: &[u8]| hex_to_bytes(&get(&client, format!("/challenge{}/encrypt?message={}", challenge, to_hex(&m.to_vec()))).1);
+            assert!(bytes_to_ascii(&ecb_byte_at_a_time(oracle)).starts_with("Rollin' in my 5.0"));
+        }
+    }
+
+    #[test]
+    fn test_cbc_bitflipping() {
+        let client = Client::new(oracle_server(DEFAULT_PORT)).expect("valid rocket instance");
+        let (_, token) = get(&client, String::from("/challenge16/token?userdata=%3Badmin%3Dtrue%3B"));
+        assert_eq!(get(&client, format!("/challenge16/admin?token={}", token)).1, "false");
+
+        //The prefix is two whole blocks, so flipping bits in the second block changes the user data
+        let (_, token) = get(&client, String::from("/challenge16/token?userdata=%3Aadmin%3Ctrue%3A"));
+        let mut forged = hex_to_bytes(&token);
+        forged[16] ^= 1;
+        forged[22] ^= 1;
+        forged[27] ^= 1;
+        assert_eq!(get(&client, format!("/challenge16/admin?token={}", to_hex(&forged))).1, "true");
+    }
+
+    #[test]
+    fn test_ctr_bitflipping() {
+        let client = Client::new(oracle_server(DEFAULT_PORT)).expect("valid rocket instance");
+        let (_, token) = get(&client, String::from("/challenge26/token?userdata=%3Aadmin%3Ctrue%3A"));
+        let mut forged = hex_to_bytes(&token);
+        forged[32] ^= 1;
+        forged[38] ^= 1;
+        forged[43] ^= 1;
+        assert_eq!(get(&client, format!("/challenge26/admin?token={}", to_hex(&forged))).1, "true");
+    }
+
+    #[test]
+    fn test_padding_oracle() {
+        let client = Client::new(oracle_server(DEFAULT_PORT)).expect("valid rocket instance");
+        let response = values(&get(&client, String::from("/challenge17/ciphertext")).1);
+        let (ciphertext, iv) = (hex_to_bytes(&response[0]), hex_to_bytes(&response[1]));
+
+        let oracle = |c: &[u8], iv: &[u8]| get(&client, format!("/challenge17/check?ciphertext={}&iv={}", to_hex(&c.to_vec()), to_hex(&iv.to_vec()))).1 == "true";
+        let plaintext = pkcs7_unpad(&padding_oracle_decrypt(oracle, 16, &ciphertext, Some(&iv))).unwrap();
+        assert!(bytes_to_ascii(&plaintext).starts_with("00000"));
+    }
+
+    #[test]
+    fn test_rsa_oracles() {
+        let client = Client::new(oracle_server(DEFAULT_PORT)).expect("valid rocket instance");
+        let key = values(&get(&client, String::from("/challenge46/public_key")).1);
+        let n = Mpz::from_str_radix(&key[0], 16).unwrap();
+        let e = Mpz::from_str_radix(&key[1], 16).unwrap();
+
+        //Encryptions of 2 and 3 decrypt to an even and an odd number
+        let two = to_hex(&hex_to_bytes(&Mpz::from(2).powm(&e, &n).to_str_radix(16)));
+        let three = to_hex(&hex_to_bytes(&Mpz::from(3).powm(&e, &n).to_str_radix(16)));
+        assert_eq!(get(&client, format!("/challenge46/is_odd?ciphertext={}", two)).1, "false");
+        assert_eq!(get(&client, format!("/challenge46/is_odd?ciphertext={}", three)).1, "true");
+
+        let ciphertext = get(&client, String::from("/challenge47/ciphertext")).1;
+        assert_eq!(get(&client, format!("/challenge47/check?ciphertext={}", ciphertext)).1, "true");
+        assert_eq!(get(&client, format!("/challenge47/check?ciphertext={}", three)).1, "false");
+    }
+
+    #[test]
+    fn test_compression_oracle() {
+        let client = Client::new(oracle_server(DEFAULT_PORT)).expect("valid rocket instance");
+        let (status, length) = get(&client, format!("/challenge51/length?message={}", to_hex(&ascii_to_bytes("sessionid=T"))));
+        assert_eq!(status, Status::Ok);
+        assert_eq!(length.parse::<usize>().unwrap() % 16, 0);
+    }
+
+    #[test]
+    fn test_bad_requests() {
+        let client = Client::new(oracle_server(DEFAULT_PORT)).expect("valid rocket instance");
+        assert_eq!(get(&client, String::from("/challenge12/encrypt?message=xyz")).0, Status::BadRequest);
+        assert_eq!(get(&client, String::from("/challenge14/encrypt?message=abc")).0, Status::BadRequest);
+        assert_eq!(get(&client, String::from("/challenge16/admin?token=00ff")).0, Status::BadRequest);
+        assert_eq!(get(&client, format!("/challenge17/check?ciphertext={}&iv=00", "00".repeat(16))).0, Status::BadRequest);
+    }
+}
\ No newline at end of file
@@ -1,5 +1,8 @@
 use thiserror::Error;
+use crate::der::{digest_info, parse_digest_info, parse_digest_info_lenient};
 use crate::hash::Hash;
+use crate::mac::constant_time_eq;
+use crate::xor::xor_bytes;
 use rand::random;
 
 ///Custom padding errors
@@ -14,6 +17,9 @@ pub enum PaddingError {
     ///Error for bad padding on a PKCS1.5 message
     #[error("String was not a properly PKCS#1.5 padded message")]
     BadPKCS15MessagePadding,
+    ///Error for bad padding on an OAEP message
+    #[error("String was not a properly OAEP padded message")]
+    BadOAEPPadding,
 }
 
 ///Pads a string to the given block size according to the PCKS#7 padding scheme.
@@ -51,16 +57,19 @@ pub fn pkcs7_unpad(message: &Vec<u8>) -> Result<Vec<u8>, PaddingError> {
 }
 
 ///Pads a signature to the given block size according to the PKCS#1.5 padding scheme.
+///The hash is wrapped in a DER encoded DigestInfo which identifies the hash function.
+///Will panic if the hash function has no DigestInfo identifier (BAD16, BAD24 and SHAKE), the message is not
+///a hash of the right length, or it does not fit in the bit length.
 pub fn pkcs15_signature_pad(message: &Vec<u8>, bit_length: usize, signature_algorithm: Hash) -> Vec<u8> {
     if message.len() > (bit_length/8) - 11 {
         panic!("Message too long to pad to this bit length.");
     }
-    if message.len() != signature_algorithm.hash_length() {
-        panic!("Message length does not match expected hash length.");
+    let mut digest_info = digest_info(signature_algorithm, message);
+    if digest_info.len() > (bit_length/8) - 11 {
+        panic!("Message too long to pad to this bit length.");
     }
 
-    let asn1_len = message.len() + 7; //Byte length of the ASN.1 signature block
-    let pad_len = (bit_length / 8) - asn1_len - 3; //Length of 0xff padding
+    let pad_len = (bit_length / 8) - digest_info.len() - 3; //Length of 0xff padding
     let mut padded = vec![];
 
     //Append pad header
@@ -73,92 +82,54 @@ pub fn pkcs15_signature_pad(message: &Vec<u8>, bit_length: usize, signature_algo
     //Append pad termination
     padded.push(0x00);
 
-    //Append sequence header
-    padded.push(0x70); //Object is a constructed sequence
-    padded.push((message.len() + 5) as u8); //Object will be hash length + 5 bytes long
-
-    //Append hash specifier
-    padded.push(0x46); //Object is an object identifier
-    padded.push(0x01); //Length is 1 byte
-    match signature_algorithm { //Hash identifier values (might be wrong but doesn't really matter)
-        Hash::SHA1 => padded.push(6),
-        Hash::SHA256 => padded.push(11),
-        Hash::MD4 => padded.push(4),
-        _ => padded.push(0) //Other hash functions should not be used but must have a case
-    }
-
-    //Append hash header
-    padded.push(0x44); //Object is an octet string
-    padded.push(signature_algorithm.hash_length() as u8); //Length is equal to hash length
-
-    //Append hash bytes
-    padded.append(&mut message.clone());
+    //Append DigestInfo
+    padded.append(&mut digest_info);
 
     return padded;
 }
 
-///Unpads a PKCS#1.5 padded signature.
-///Returns an error if the string is not properly padded.
-pub fn pkcs15_signature_unpad(message: &Vec<u8>) -> Result<Vec<u8>, PaddingError> {
-    //Traverse padding until we hit ANS.1 information. Error out if the padding is ever bad
-    if message[0] != 0x00 || message[1] != 0x01 || message[2] != 0xff {
-        return Err(PaddingError::BadPKCS15SignaturePadding);
-    }
-
-    let mut index = 2;
-    while message[index] == 0xff {
-        index += 1;
-    }
-
-    if message[index] != 0x00 || message[index+1] != 0x70 {
+///Checks the 00 01 [ff] 00 padding of a PKCS#1.5 padded signature and returns everything after it.
+///Returns an error if the padding is bad or has fewer than the given number of ff bytes.
+fn pkcs15_signature_strip(message: &Vec<u8>, min_pad_len: usize) -> Result<&[u8], PaddingError> {
+    if message.len() < 3 || message[0] != 0x00 || message[1] != 0x01 {
         return Err(PaddingError::BadPKCS15SignaturePadding);
     }
 
-    //confirm message length is valid
-    let asn_len = message[index+2];
-    if asn_len as usize + index + 3 as usize != message.len() {
+    let pad_len = message[2..].iter().take_while(|b| **b == 0xff).count();
+    let index = 2 + pad_len;
+    if pad_len < min_pad_len.max(1) || message.get(index) != Some(&0x00) {
         return Err(PaddingError::BadPKCS15SignaturePadding);
     }
 
-    //Infer hash length from hash algorithm
-    let hash_len = match message[index + 5] {
-        4 => Hash::MD4.hash_length(),
-        6 => Hash::SHA1.hash_length(),
-        11 => Hash::SHA256.hash_length(),
-        _ => return Err(PaddingError::BadPKCS15SignaturePadding)
-    };
+    return Ok(&message[index+1..]);
+}
 
-    //Return hash bytes
-    return Ok(message[index+8..index+8+hash_len].to_vec());
+///Parses a PKCS#1.5 padded signature, returning the hash function and hash.
+///Returns an error unless there are at least 8 ff padding bytes and the rest is exactly a DER encoded DigestInfo.
+pub fn pkcs15_signature_parse(message: &Vec<u8>) -> Result<(Hash, Vec<u8>), PaddingError> {
+    let digest_info = pkcs15_signature_strip(message, 8)?;
+    return parse_digest_info(digest_info).map_err(|_| PaddingError::BadPKCS15SignaturePadding);
 }
 
-///Unpads a PKCS#1.5 padded signature, but with no total length check.
+///Parses a PKCS#1.5 padded signature with a lenient DigestInfo parser, returning the hash function and hash.
+///Unchecked parameters, garbage in lengths and trailing bytes are all accepted, so signatures can be forged.
 ///Returns an error if the string is not properly padded.
-pub fn pkcs15_signature_unpad_lazy(message: &Vec<u8>) -> Result<Vec<u8>, PaddingError> {
-    //Traverse padding until we hit ANS.1 information. Error out if the padding is ever bad
-    if message[0] != 0x00 || message[1] != 0x01 || message[2] != 0xff {
-        return Err(PaddingError::BadPKCS15SignaturePadding);
-    }
-
-    let mut index = 2;
-    while message[index] == 0xff {
-        index += 1;
-    }
-
-    if message[index] != 0x00 || message[index+1] != 0x70 {
-        return Err(PaddingError::BadPKCS15SignaturePadding);
-    }
+pub fn pkcs15_signature_parse_lenient(message: &Vec<u8>) -> Result<(Hash, Vec<u8>), PaddingError> {
+    let digest_info = pkcs15_signature_strip(message, 1)?;
+    return parse_digest_info_lenient(digest_info).map_err(|_| PaddingError::BadPKCS15SignaturePadding);
+}
 
-    //Infer hash length from hash algorithm
-    let hash_len = match message[index + 5] {
-        4 => Hash::MD4.hash_length(),
-        6 => Hash::SHA1.hash_length(),
-        11 => Hash::SHA256.hash_length(),
-        _ => return Err(PaddingError::BadPKCS15SignaturePadding)
-    };
+///Unpads a PKCS#1.5 padded signature.
+///Returns an error if the string is not properly padded.
+pub fn pkcs15_signature_unpad(message: &Vec<u8>) -> Result<Vec<u8>, PaddingError> {
+    return pkcs15_signature_parse(message).map(|(_, hash)| hash);
+}
 
-    //Return hash bytes
-    return Ok(message[index+8..index+8+hash_len].to_vec());
+///Unpads a PKCS#1.5 padded signature with the lenient DigestInfo parser. A single ff padding byte is enough,
+///the parameters are not checked, garbage is accepted in long form lengths and bytes may trail the hash.
+///Returns an error if the string is not properly padded.
+pub fn pkcs15_signature_unpad_lazy(message: &Vec<u8>) -> Result<Vec<u8>, PaddingError> {
+    return pkcs15_signature_parse_lenient(message).map(|(_, hash)| hash);
 }
 
 ///Pads a message to the given bit length according to the PKCS#1.5 padding scheme.
@@ -207,6 +178,163 @@ pub fn pkcs15_message_unpad(message: &Vec<u8>) -> Result<Vec<u8>, PaddingError>
     return Ok(message[index+1..].to_vec());
 }
 
+///Generates a mask of the given length from the seed with the MGF1 function from RFC 8017
+pub fn mgf1(seed: &Vec<u8>, mask_length: usize, hash: Hash) -> Vec<u8> {
+    let mut mask = vec![];
+    let mut counter: u32 = 0;
+    while mask.len() < mask_length {
+        let mut input = seed.clone();
+        input.extend_from_slice(&counter.to_be_bytes());
+        mask.append(&mut hash.digest(&input));
+        counter += 1;
+    }
+
+    mask.truncate(mask_length);
+    return mask;
+}
+
+///Pads a message to the given bit length with OAEP (RFC 8017), using a random seed.
+///The hash is used both for the label and for MGF1, and an empty label is the same as no label.
+///Will panic if the message is too long to pad to this bit length.
+pub fn oaep_pad(message: &Vec<u8>, label: &Vec<u8>, bit_length: usize, hash: Hash) -> Vec<u8> {
+    let seed: Vec<u8> = (0..hash.hash_length()).map(|_| random()).collect();
+    return oaep_pad_with_seed(message, label, bit_length, hash, &seed);
+}
+
+///Pads a message to the given bit length with OAEP (RFC 8017), using the given seed.
+///Will panic if the message is too long to pad to this bit length or the seed is not the length of a hash.
+pub fn oaep_pad_with_seed(message: &Vec<u8>, label: &Vec<u8>, bit_length: usize, hash: Hash, seed: &Vec<u8>) -> Vec<u8> {
+    let k = (bit_length + 7) / 8;
+    let h_len = hash.hash_length();
+    if message.len() + 2 * h_len + 2 > k {
+        panic!("Message too long to pad to this bit length.");
+    }
+    if seed.len() != h_len {
+        panic!("Seed length does not match expected hash length.");
+    }
+
+    //Data block: label hash || 00 padding || 01 || message
+    let mut data_block = hash.digest(label);
+    data_block.append(&mut vec![0x00; k - message.len() - 2 * h_len - 2]);
+    data_block.push(0x01);
+    data_block.append(&mut message.clone());
+
+    //Mask the data block with the seed, then the seed with the masked data block
+    let masked_data_block = xor_bytes(&data_block, &mgf1(seed, k - h_len - 1, hash));
+    let mut masked_seed = xor_bytes(seed, &mgf1(&masked_data_block, h_len, hash));
+
+    let mut padded = vec![0x00];
+    padded.append(&mut masked_seed);
+    padded.extend_from_slice(&masked_data_block);
+    return padded;
+}
+
+///Unpads an OAEP padded message for the given bit length, hash and label.
+///Leading zero bytes may be missing (as they are from RSA decryption).
+///Returns the same error whichever check fails, so that the failures cannot be told apart.
+pub fn oaep_unpad(message: &Vec<u8>, label: &Vec<u8>, bit_length: usize, hash: Hash) -> Result<Vec<u8>, PaddingError> {
+    let k = (bit_length + 7) / 8;
+    let h_len = hash.hash_length();
+    if message.len() > k || k < 2 * h_len + 2 {
+        return Err(PaddingError::BadOAEPPadding);
+    }
+    let mut padded = vec![0x00; k - message.len()];
+    padded.extend_from_slice(message);
+
+    //Unmask the seed, then the data block
+    let masked_seed = padded[1..h_len+1].to_vec();
+    let masked_data_block = padded[h_len+1..].to_vec();
+    let seed = xor_bytes(&masked_seed, &mgf1(&masked_data_block, h_len, hash));
+    let data_block = xor_bytes(&masked_data_block, &mgf1(&seed, k - h_len - 1, hash));
+
+    //Check every part of the padding before deciding, instead of returning at the first bad one
+    let mut valid = padded[0] == 0x00;
+    valid &= constant_time_eq(&data_block[..h_len], &hash.digest(label));
+    let separator = data_block[h_len..].iter().position(|b| *b != 0x00);
+    valid &= separator.map_or(false, |i| data_block[h_len + i] == 0x01);
+
+    if !valid {
+        return Err(PaddingError::BadOAEPPadding);
+    }
+    return Ok(data_block[h_len + separator.unwrap() + 1..].to_vec());
+}
+
+///Encodes a message hash for a PSS signature (RFC 8017) with a random salt of the given length.
+///The encoding is em_bits long, which should be one less than the bit length of the modulus.
+///Will panic if the hash and salt are too long to encode in em_bits.
+pub fn pss_encode(message_hash: &Vec<u8>, em_bits: usize, hash: Hash, salt_length: usize) -> Vec<u8> {
+    let salt: Vec<u8> = (0..salt_length).map(|_| random()).collect();
+    return pss_encode_with_salt(message_hash, em_bits, hash, &salt);
+}
+
+///Encodes a message hash for a PSS signature (RFC 8017) with the given salt.
+///Will panic if the hash and salt are too long to encode in em_bits, or the message hash is the wrong length.
+pub fn pss_encode_with_salt(message_hash: &Vec<u8>, em_bits: usize, hash: Hash, salt: &Vec<u8>) -> Vec<u8> {
+    let em_length = (em_bits + 7) / 8;
+    let h_len = hash.hash_length();
+    if message_hash.len() != h_len {
+        panic!("Message length does not match expected hash length.");
+    }
+    if em_length < h_len + salt.len() + 2 {
+        panic!("Salt too long to pad to this bit length.");
+    }
+
+    //H = Hash(00 x 8 || message hash || salt)
+    let h = pss_salted_hash(message_hash, salt, hash);
+
+    //Data block: 00 padding || 01 || salt, masked with MGF1(H)
+    let mut data_block = vec![0x00; em_length - salt.len() - h_len - 2];
+    data_block.push(0x01);
+    data_block.extend_from_slice(salt);
+    let mut masked_data_block = xor_bytes(&data_block, &mgf1(&h, em_length - h_len - 1, hash));
+    masked_data_block[0] &= 0xff >> (8 * em_length - em_bits);
+
+    let mut encoded = masked_data_block;
+    encoded.extend_from_slice(&h);
+    encoded.push(0xbc);
+    return encoded;
+}
+
+///Checks a PSS encoding (RFC 8017) of the message hash with the given salt length.
+///Leading zero bytes of the encoding may be missing (as they are from RSA verification).
+pub fn pss_verify(message_hash: &Vec<u8>, encoded: &Vec<u8>, em_bits: usize, hash: Hash, salt_length: usize) -> bool {
+    let em_length = (em_bits + 7) / 8;
+    let h_len = hash.hash_length();
+    if message_hash.len() != h_len || encoded.len() > em_length || em_length < h_len + salt_length + 2 {
+        return false;
+    }
+    let mut padded = vec![0x00; em_length - encoded.len()];
+    padded.extend_from_slice(encoded);
+
+    //Bits above em_bits must be clear, and the encoding must end in bc
+    let top_bits = 0xff >> (8 * em_length - em_bits);
+    if padded[em_length - 1] != 0xbc || padded[0] & !top_bits != 0 {
+        return false;
+    }
+
+    let masked_data_block = padded[..em_length - h_len - 1].to_vec();
+    let h = padded[em_length - h_len - 1..em_length - 1].to_vec();
+    let mut data_block = xor_bytes(&masked_data_block, &mgf1(&h, em_length - h_len - 1, hash));
+    data_block[0] &= top_bits;
+
+    //Data block must be 00 padding || 01 || salt
+    let separator = em_length - h_len - salt_length - 2;
+    if data_block[..separator].iter().any(|b| *b != 0x00) || data_block[separator] != 0x01 {
+        return false;
+    }
+
+    let salt = data_block[separator + 1..].to_vec();
+    return constant_time_eq(&h, &pss_salted_hash(message_hash, &salt, hash));
+}
+
+///Computes Hash(00 x 8 || message hash || salt), which a PSS encoding ends with
+fn pss_salted_hash(message_hash: &Vec<u8>, salt: &Vec<u8>, hash: Hash) -> Vec<u8> {
+    let mut salted = vec![0x00; 8];
+    salted.extend_from_slice(message_hash);
+    salted.extend_from_slice(salt);
+    return hash.digest(&salted);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -254,26 +382,58 @@ mod tests {
         //Initial padding: 00 01 [ff] 00
         expected.push(0x00);
         expected.push(0x01);
-        expected.append(&mut vec![0xff; 102]);
+        expected.append(&mut vec![0xff; 91]);
         expected.push(0x00);
 
-        //Sequence header
-        expected.push(0x70); //Object is a constructed sequence
-        expected.push(0x15); //Total length is 21 bytes
-
-        //Append hash specifier
-        expected.push(0x46); //Object is an object identifier
-        expected.push(0x01); //Length is 1 byte
-        expected.push(0x04); //Identifier for MD4 hash
+        //DigestInfo sequence header
+        expected.push(0x30); //Object is a sequence
+        expected.push(0x20); //Total length is 32 bytes
+
+        //Append algorithm identifier
+        expected.push(0x30); //Object is a sequence
+        expected.push(0x0c); //Length is 12 bytes
+        expected.push(0x06); //Object is an object identifier
+        expected.push(0x08); //Length is 8 bytes
+        expected.append(&mut vec![0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x02, 0x04]); //1.2.840.113549.2.4 (MD4)
+        expected.push(0x05); //Parameters are NULL
+        expected.push(0x00);
 
         //Append hash bytes
-        expected.push(0x44); //Object is an octet string
+        expected.push(0x04); //Object is an octet string
         expected.push(0x10); //Length is 16 bytes
         expected.append(&mut hash.clone()); //Hash bytes
 
         assert_eq!(pkcs15_signature_pad(&hash, 1024, Hash::MD4), expected);
     }
 
+    #[test]
+    fn test_pkcs15_signature_other_hashes() {
+        for hash in [Hash::MD5, Hash::SHA224, Hash::SHA384, Hash::SHA512, Hash::SHA512_256].iter() {
+            let digest = hash.digest(&vec![0x55; 10]);
+            let padded = pkcs15_signature_pad(&digest, 1024, *hash);
+            assert_eq!(padded.len(), 128);
+            assert_eq!(pkcs15_signature_unpad(&padded).unwrap(), digest);
+            assert_eq!(pkcs15_signature_unpad_lazy(&padded).unwrap(), digest);
+        }
+    }
+
+    #[test]
+    fn test_pkcs15_signatures() {
+        //Produced by Python cryptography 48.0.0 with key.sign(message, PKCS1v15(), hash)
+        let r = pkcs1_example_key();
+        let message = crate::converter::ascii_to_bytes("PKCS#1 v1.5 test message");
+        let vectors = [
+            (Hash::SHA256, "37cb9506aa73c92bab960261e1c6e5664b6cac7a596b573373b987dc8ac4f253f471935b21409741322f01e9efe37b6faf5ebfb4ced9d328693099c3dd1b90011bb55ed1475fb587c45837a6c904faee7f078eb0980df5668fdf6dcd486fb61e67d63c3736dc3b64bc295fb32cbf72c0a3299ee52df32ad3b922fefd0a7e5e59"),
+            (Hash::SHA1, "6d2d49a7567e8f4ee71b5a866e61501b560a0417f10f332c09e4fd0135ce2b4e51617cf6f3595f436cd7abe5bd71cecab263b3e30c2ee7a94ccf5853e5afa056cc9b787836a027555ccffe73993220edb266bda58843210afe1a892cd8e5c7dda46404de8648631776e07f5772dc9a6cfad73109811465044b4dc17c764e0713"),
+            (Hash::SHA3_256, "603e42a8fd9a6b6fe4666c7feb21d4086ddf3b1200cae494050f19d363131f2ab715ad5bbf7af96dc3b9e770de09977f2065afcc2bca4983875d3ba96feaa3b76c15f054197489123a781b59463ffa2c8edaefd19b14c57b9ce97de941356918e4dcadc9429f59d05666ad3b4ad64e4a1cc66f6a4b06f134cd83591d41cc7e84")
+        ];
+
+        for (hash, signature) in vectors.iter() {
+            let padded = pkcs15_signature_pad(&hash.digest(&message), 1024, *hash);
+            assert_eq!(r.decrypt(&padded), crate::converter::hex_to_bytes(signature));
+        }
+    }
+
     #[test]
     #[should_panic(expected="Message too long to pad to this bit length.")]
     fn test_pkcs15_signature_pad_length_error() {
@@ -288,6 +448,12 @@ mod tests {
         pkcs15_signature_pad(&message, 1024, Hash::MD4);
     }
 
+    #[test]
+    #[should_panic(expected="Hash has no DigestInfo algorithm identifier.")]
+    fn test_pkcs15_signature_pad_no_identifier() {
+        pkcs15_signature_pad(&vec![0x55; 2], 1024, Hash::BAD16);
+    }
+
     #[test]
     fn test_pkcs15_signature_unpad() {
         let hash: Vec<u8> = [0xaa; 16].to_vec();
@@ -296,25 +462,26 @@ mod tests {
         //Initial padding: 00 01 [ff] 00
         padded.push(0x00);
         padded.push(0x01);
-        padded.append(&mut vec![0xff; 102]);
+        padded.append(&mut vec![0xff; 91]);
         padded.push(0x00);
 
-        //Sequence header
-        padded.push(0x70); //Object is a constructed sequence
-        padded.push(0x15); //Total length is 21 bytes
-
-        //Append hash specifier
-        padded.push(0x42); //Object is a primitive integer
-        padded.push(0x01); //Length is 1 byte
-        padded.push(0x04); //Identifier for MD4 hash
+        //DigestInfo for MD4
+        padded.append(&mut vec![0x30, 0x20, 0x30, 0x0c, 0x06, 0x08, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x02, 0x04, 0x05, 0x00]);
 
         //Append hash bytes
-        padded.push(0x44); //Object is an octet string
+        padded.push(0x04); //Object is an octet string
         padded.push(0x10); //Length is 16 bytes
         padded.append(&mut hash.clone()); //Hash bytes
 
         assert_eq!(pkcs15_signature_unpad(&padded).unwrap(), hash);
         assert_eq!(pkcs15_signature_unpad_lazy(&padded).unwrap(), hash);
+        assert_eq!(pkcs15_signature_parse(&padded).unwrap(), (Hash::MD4, hash.clone()));
+
+        //Too little padding for a strict parse
+        let mut short_pad = vec![0x00, 0x01, 0xff, 0x00];
+        short_pad.extend_from_slice(&padded[94..]);
+        assert!(pkcs15_signature_unpad(&short_pad).is_err());
+        assert_eq!(pkcs15_signature_unpad_lazy(&short_pad).unwrap(), hash);
     }
 
     #[test]
@@ -335,26 +502,20 @@ mod tests {
         let hash: Vec<u8> = [0xaa; 16].to_vec();
         let mut padded: Vec<u8> = vec![];
 
-        //Initial padding: 00 01 [ff] 00
+        //Initial padding: 00 01 [ff x 8] 00
         padded.push(0x00);
         padded.push(0x01);
-        padded.push(0xff);
+        padded.append(&mut vec![0xff; 8]);
         padded.push(0x00);
 
-        //Sequence header
-        padded.push(0x70); //Object is a constructed sequence
-        padded.push(0x15); //Total length is 21 bytes
-
-        //Append hash specifier
-        padded.push(0x42); //Object is a primitive integer
-        padded.push(0x01); //Length is 1 byte
-        padded.push(0x04); //Identifier for MD4 hash
+        //DigestInfo for MD4
+        padded.append(&mut vec![0x30, 0x20, 0x30, 0x0c, 0x06, 0x08, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x02, 0x04, 0x05, 0x00]);
 
         //Append hash bytes
-        padded.push(0x44); //Object is an octet string
+        padded.push(0x04); //Object is an octet string
         padded.push(0x10); //Length is 16 bytes
         padded.append(&mut hash.clone()); //Hash bytes
-        padded.append(&mut vec![0xff; 121]);
+        padded.append(&mut vec![0xff; 83]);
 
         match pkcs15_signature_unpad(&padded) {
             Ok(_) => panic!("Should have thrown an error for badly padded string!"),
@@ -409,4 +570,149 @@ mod tests {
             Err(_) => assert!(true)
         }
     }
+
+    ///RSA key from the RSAES-OAEP worked example in the PKCS#1 v2.1 test vectors, with e=17
+    fn pkcs1_example_key() -> crate::rsa::RSA {
+        let p = gmp::mpz::Mpz::from_str_radix("eecfae81b1b9b3c908810b10a1b5600199eb9f44aef4fda493b81a9e3d84f632124ef0236e5d1e3b7e28fae7aa040a2d5b252176459d1f397541ba2a58fb6599", 16).unwrap();
+        let q = gmp::mpz::Mpz::from_str_radix("c97fb1f027f453f6341233eaaad1d9353f6c42d08866b1d05a0f2035028b9d869840b41666b42e92ea0da3b43204b5cfce3352524d0416a5a441e700af461503", 16).unwrap();
+        return crate::rsa::RSA::new_from_primes(&p, &q, &gmp::mpz::Mpz::from(17)).unwrap();
+    }
+
+    #[test]
+    fn test_mgf1() {
+        assert_eq!(mgf1(&crate::converter::ascii_to_bytes("foo"), 3, Hash::SHA1), vec![0x1a, 0xc9, 0x07]);
+        assert_eq!(mgf1(&crate::converter::ascii_to_bytes("bar"), 5, Hash::SHA1), vec![0xbc, 0x0c, 0x65, 0x5e, 0x01]);
+        assert_eq!(mgf1(&crate::converter::ascii_to_bytes("bar"), 50, Hash::SHA256), crate::converter::hex_to_bytes("382576a7841021cc28fc4c0948753fb8312090cea942ea4c4e735d10dc724b155f9f6069f289d61daca0cb814502ef04eae1"));
+    }
+
+    #[test]
+    fn test_oaep_pad_with_seed() {
+        let message = crate::converter::hex_to_bytes("d436e99569fd32a7c8a05bbc90d32c49");
+        let seed = crate::converter::hex_to_bytes("aafd12f659cae63489b479e5076ddec2f06cb58f");
+        let padded = oaep_pad_with_seed(&message, &vec![], 1024, Hash::SHA1, &seed);
+        assert_eq!(padded, crate::converter::hex_to_bytes("00eb7a19ace9e3006350e329504b45e2ca82310b26dcd87d5c68f1eea8f55267c31b2e8bb4251f84d7e0b2c04626f5aff93edcfb25c9c2b3ff8ae10e839a2ddb4cdcfe4ff47728b4a1b7c1362baad29ab48d2869d5024121435811591be392f982fb3e87d095aeb40448db972f3ac14f7bc275195281ce32d2f1b76d4d353e2d"));
+
+        let r = pkcs1_example_key();
+        let ciphertext = crate::converter::hex_to_bytes("1253e04dc0a5397bb44a7ab87e9bf2a039a33d1e996fc82a94ccd30074c95df763722017069e5268da5d1c0b4f872cf653c11df82314a67968dfeae28def04bb6d84b1c31d654a1970e5783bd6eb96a024c2ca2f4a90fe9f2ef5c9c140e5bb48da9536ad8700c84fc9130adea74e558d51a74ddf85d8b50de96838d6063e0955");
+        assert_eq!(r.encrypt(&padded), ciphertext);
+        assert_eq!(oaep_unpad(&r.decrypt(&ciphertext), &vec![], 1024, Hash::SHA1).unwrap(), message);
+    }
+
+    #[test]
+    fn test_oaep_unpad_label() {
+        //Python cryptography 48.0.0 OAEP output, using SHA-256 for both hash and MGF1 and the label "cryptopals"
+        let r = pkcs1_example_key();
+        let ciphertext = crate::converter::hex_to_bytes("27bd1ccd8d9e06f76a26f66139a372e2d16a62553a5d0be57422d535ad41147107d38c3bea625523587ffbed03e72cea74854e085a1d42b28b4b521b9963de3ce8278e4f395409010695cc94059a7aa46b11cd2bb28d15cb958eacf29dd7297737df1ee2f2f168b8ac629627d6967c34a3259bf5ab1abeadd9286318d342ac5b");
+        let label = crate::converter::ascii_to_bytes("cryptopals");
+        let padded = r.decrypt(&ciphertext);
+
+        assert_eq!(oaep_unpad(&padded, &label, 1024, Hash::SHA256).unwrap(), crate::converter::ascii_to_bytes("Manger's attack"));
+        assert!(oaep_unpad(&padded, &vec![], 1024, Hash::SHA256).is_err());
+        assert!(oaep_unpad(&padded, &label, 1024, Hash::SHA1).is_err());
+    }
+
+    #[test]
+    fn test_oaep_round_trip() {
+        let message = crate::converter::ascii_to_bytes("OAEP works with any hash");
+        let label = crate::converter::ascii_to_bytes("label");
+        for hash in [Hash::SHA1, Hash::SHA256, Hash::SHA384, Hash::MD5, Hash::SHA3_256].iter() {
+            let padded = oaep_pad(&message, &label, 1024, *hash);
+            assert_eq!(padded.len(), 128);
+            assert_eq!(padded[0], 0);
+            assert_ne!(padded, oaep_pad(&message, &label, 1024, *hash));
+            assert_eq!(oaep_unpad(&padded, &label, 1024, *hash).unwrap(), message);
+        }
+    }
+
+    #[test]
+    fn test_oaep_unpad_error() {
+        let padded = oaep_pad(&vec![0x6a; 22], &vec![], 1024, Hash::SHA256);
+        for i in [0, 1, 40, 127].iter() {
+            let mut bad_pad = padded.clone();
+            bad_pad[*i] ^= 0x01;
+            assert!(oaep_unpad(&bad_pad, &vec![], 1024, Hash::SHA256).is_err());
+        }
+        assert!(oaep_unpad(&vec![0xaa; 129], &vec![], 1024, Hash::SHA256).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected="Message too long to pad to this bit length.")]
+    fn test_oaep_pad_length_error() {
+        oaep_pad(&vec![0x6a; 15], &vec![], 1024, Hash::SHA512);
+    }
+
+    #[test]
+    fn test_pss_encode() {
+        let message_hash = Hash::SHA256.digest(&crate::converter::ascii_to_bytes("message"));
+        for salt_length in [0, 20, 32, 62].iter() {
+            let encoded = pss_encode(&message_hash, 1023, Hash::SHA256, *salt_length);
+            assert_eq!(encoded.len(), 128);
+            assert_eq!(encoded[0] & 0x80, 0);
+            assert_eq!(encoded[127], 0xbc);
+            assert!(pss_verify(&message_hash, &encoded, 1023, Hash::SHA256, *salt_length));
+            assert!(!pss_verify(&message_hash, &encoded, 1023, Hash::SHA256, *salt_length + 1));
+        }
+
+        //Without a salt, the encoding is deterministic
+        assert_eq!(pss_encode(&message_hash, 1023, Hash::SHA256, 0), pss_encode(&message_hash, 1023, Hash::SHA256, 0));
+        assert_ne!(pss_encode(&message_hash, 1023, Hash::SHA256, 32), pss_encode(&message_hash, 1023, Hash::SHA256, 32));
+    }
+
+    #[test]
+    fn test_pss_encode_em_bits() {
+        //When em_bits is a multiple of 8, the encoding is a byte shorter than the modulus
+        let message_hash = Hash::SHA1.digest(&crate::converter::ascii_to_bytes("message"));
+        let encoded = pss_encode(&message_hash, 1024, Hash::SHA1, 20);
+        assert_eq!(encoded.len(), 128);
+        assert!(pss_verify(&message_hash, &encoded, 1024, Hash::SHA1, 20));
+
+        let encoded = pss_encode(&message_hash, 1020, Hash::SHA1, 20);
+        assert_eq!(encoded[0] & 0xf0, 0);
+        assert!(pss_verify(&message_hash, &encoded, 1020, Hash::SHA1, 20));
+    }
+
+    #[test]
+    fn test_pss_verify_error() {
+        let message_hash = Hash::SHA256.digest(&crate::converter::ascii_to_bytes("message"));
+        let encoded = pss_encode(&message_hash, 1023, Hash::SHA256, 32);
+        for i in [0, 50, 100, 126, 127].iter() {
+            let mut bad = encoded.clone();
+            bad[*i] ^= 0x01;
+            assert!(!pss_verify(&message_hash, &bad, 1023, Hash::SHA256, 32));
+        }
+
+        let mut high_bit = encoded.clone();
+        high_bit[0] |= 0x80;
+        assert!(!pss_verify(&message_hash, &high_bit, 1023, Hash::SHA256, 32));
+        assert!(!pss_verify(&Hash::SHA256.digest(&vec![]), &encoded, 1023, Hash::SHA256, 32));
+        assert!(!pss_verify(&message_hash, &encoded, 1023, Hash::SHA3_256, 32));
+    }
+
+    #[test]
+    fn test_pss_signatures() {
+        //PSS is randomized, so these come from Python cryptography 48.0.0 and are checked by verifying rather than re-signing
+        let r = pkcs1_example_key();
+        let message = crate::converter::ascii_to_bytes("RSA-PSS test message");
+        let vectors = [
+            (Hash::SHA256, 0, "647dce5003d2a563b2b21055cee62da6975bd2a8a99e865d5a94eb3d274cd1113cf3cdff94c0c670c82dabbb4d1443e9c1802202ec2f65256c83ecf43c6ebb5679fd8f9ab6f0c036ab51b439512053029a7d578146bb225053aadc2a387dcfac971b53ef506bf2645c8bb997452251081373db4bb65d570b8187743f57c29a71"),
+            (Hash::SHA256, 32, "9ff77ace1c8d0ed261b5d2fa14ceb6cb3bdcb0f9ac1881ae9b3764cb10d9a0d6706e7d4d8724da365dfd82c9aae369893b3c4df477b7f332d36f1a039e319b9c2b453a629427e29ac78d6dc06515967ccbc2d5537e8040a8b7b8d7d70dab56f07e355c00a6553b472f191c498cd7c2cd9326751791a46154ab6361a5e3731aee"),
+            (Hash::SHA1, 20, "63d289880173d119fed8a6ffef2d59aaa676c795860016ec509552456cca079647a5d59eb6db154e05ab8e6e1bb8147c05826d9b36bafae2d9eceb446f00a8ab0b0d85085339f627e1f954fc5faae93bd7a213ea17bd996f1089b72674cd89cf60e8f750cb5232639a352f11a2f4e1991974acc79199bc79523647a480aea723")
+        ];
+
+        for (hash, salt_length, signature) in vectors.iter() {
+            let encoded = r.encrypt(&crate::converter::hex_to_bytes(signature));
+            assert!(pss_verify(&hash.digest(&message), &encoded, 1023, *hash, *salt_length));
+            assert!(!pss_verify(&hash.digest(&vec![]), &encoded, 1023, *hash, *salt_length));
+        }
+
+        //Without a salt, signing gives exactly the same signature
+        let encoded = pss_encode_with_salt(&Hash::SHA256.digest(&message), 1023, Hash::SHA256, &vec![]);
+        assert_eq!(r.decrypt(&encoded), crate::converter::hex_to_bytes("647dce5003d2a563b2b21055cee62da6975bd2a8a99e865d5a94eb3d274cd1113cf3cdff94c0c670c82dabbb4d1443e9c1802202ec2f65256c83ecf43c6ebb5679fd8f9ab6f0c036ab51b439512053029a7d578146bb225053aadc2a387dcfac971b53ef506bf2645c8bb997452251081373db4bb65d570b8187743f57c29a71"));
+    }
+
+    #[test]
+    #[should_panic(expected="Salt too long to pad to this bit length.")]
+    fn test_pss_encode_length_error() {
+        pss_encode(&Hash::SHA512.digest(&vec![]), 1023, Hash::SHA512, 63);
+    }
 }
\ No newline at end of file
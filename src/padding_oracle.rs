@@ -0,0 +1,274 @@
+use rand::random;
+use thiserror::Error;
+use crate::padding::pkcs7_pad;
+use crate::xor::xor_bytes;
+
+///Custom padding oracle attack errors
+#[derive(Error, Debug, PartialEq)]
+pub enum PaddingOracleError {
+    ///Error for a block size of zero
+    #[error("Illegal block size {0} for a padding oracle attack!")]
+    IllegalBlockSize(usize),
+    ///Error for ciphertext which is empty or not a whole number of blocks
+    #[error("Ciphertext length must be a nonzero multiple of the block size.")]
+    IllegalCiphertextLength(usize),
+    ///Error for an IV which is not one block long
+    #[error("Illegal IV length {0} for a padding oracle attack!")]
+    IllegalIVLength(usize),
+    ///Error for an oracle which accepted none of the 256 values for a byte, as a noisy or misconfigured one might
+    #[error("Padding oracle did not accept any value for byte {0} of the block.")]
+    NoValidByte(usize),
+}
+
+///Recovers the block cipher decryption of a single ciphertext block (before the CBC XOR) using a padding oracle.
+///Each query is a two block ciphertext (forged block, target block), so the oracle's IV does not matter.
+///Returns an error if the oracle accepts no value for some byte.
+fn decrypt_block_intermediate<F: Fn(&[u8], &[u8]) -> bool>(oracle: &F, block: &[u8], iv: &[u8]) -> Result<Vec<u8>, PaddingOracleError> {
+    let block_size = block.len();
+    let mut intermediate: Vec<u8> = vec![0; block_size];
+    let mut query: Vec<u8> = vec![0; block_size * 2];
+    query[block_size..].copy_from_slice(block);
+
+    for i in (0..block_size).rev() {
+        //Set the already known bytes so that they decrypt to the target padding value
+        let target_padding = (block_size - i) as u8;
+        for j in i+1..block_size {
+            query[j] = intermediate[j] ^ target_padding;
+        }
+
+        //Search for the byte that gives valid padding
+        let mut found = false;
+        for byte in 0..=255 {
+            query[i] = byte;
+            if !oracle(&query, iv) {
+                continue;
+            }
+
+            //At the end of the block, make sure the padding wasn't longer than one byte by changing the previous byte
+            if i == block_size - 1 && i > 0 {
+                query[i - 1] ^= 1;
+                let still_valid = oracle(&query, iv);
+                query[i - 1] ^= 1;
+                if !still_valid {
+                    continue;
+                }
+            }
+
+            intermediate[i] = byte ^ target_padding;
+            found = true;
+            break;
+        }
+
+        if !found {
+            return Err(PaddingOracleError::NoValidByte(i));
+        }
+    }
+
+    return Ok(intermediate);
+}
+
+///Decrypts a CBC ciphertext using a padding oracle, which takes a ciphertext and IV and returns true if the
+///decryption is properly PKCS#7 padded. Returns the padded plaintext.
+///If the IV is not known, the first block cannot be recovered and is left out of the result.
+///Will panic if the block size is zero, the ciphertext is not a nonzero number of blocks, the IV is not one block,
+///or the oracle accepts no value for some byte.
+pub fn padding_oracle_decrypt<F: Fn(&[u8], &[u8]) -> bool>(oracle: F, block_size: usize, ciphertext: &Vec<u8>, iv: Option<&Vec<u8>>) -> Vec<u8> {
+    return try_padding_oracle_decrypt(oracle, block_size, ciphertext, iv).unwrap_or_else(|e| panic!("{}", e));
+}
+
+///Decrypts a CBC ciphertext using a padding oracle, which takes a ciphertext and IV and returns true if the
+///decryption is properly PKCS#7 padded. Returns the padded plaintext.
+///If the IV is not known, the first block cannot be recovered and is left out of the result.
+///Returns an error if the block size is zero, the ciphertext is not a nonzero number of blocks, the IV is not one block,
+///or the oracle accepts no value for some byte.
+pub fn try_padding_oracle_decrypt<F: Fn(&[u8], &[u8]) -> bool>(oracle: F, block_size: usize, ciphertext: &Vec<u8>, iv: Option<&Vec<u8>>) -> Result<Vec<u8>, PaddingOracleError> {
+    if block_size == 0 {
+        return Err(PaddingOracleError::IllegalBlockSize(block_size));
+    }
+    if ciphertext.len() % block_size != 0 || ciphertext.len() == 0 {
+        return Err(PaddingOracleError::IllegalCiphertextLength(ciphertext.len()));
+    }
+    if let Some(iv) = iv {
+        if iv.len() != block_size {
+            return Err(PaddingOracleError::IllegalIVLength(iv.len()));
+        }
+    }
+
+    //The IV passed to the oracle only needs to have the right length
+    let query_iv = match iv {
+        None => vec![0; block_size],
+        Some(iv) => iv.clone()
+    };
+
+    let mut plaintext: Vec<u8> = vec![];
+    for start in (0..ciphertext.len()).step_by(block_size) {
+        let previous = if start == 0 {
+            match iv {
+                None => continue,
+                Some(iv) => iv.clone()
+            }
+        }
+        else {
+            ciphertext[start - block_size..start].to_vec()
+        };
+
+        let intermediate = decrypt_block_intermediate(&oracle, &ciphertext[start..start + block_size], &query_iv)?;
+        plaintext.append(&mut xor_bytes(&intermediate, &previous));
+    }
+
+    return Ok(plaintext);
+}
+
+///Forges a CBC encryption of the given plaintext using a padding oracle, working backwards from a random final block.
+///The plaintext is PKCS#7 padded before encrypting. Returns the ciphertext and the IV it must be decrypted with.
+///Will panic if the block size is zero or the oracle accepts no value for some byte.
+pub fn padding_oracle_encrypt<F: Fn(&[u8], &[u8]) -> bool>(oracle: F, block_size: usize, plaintext: &Vec<u8>) -> (Vec<u8>, Vec<u8>) {
+    return try_padding_oracle_encrypt(oracle, block_size, plaintext).unwrap_or_else(|e| panic!("{}", e));
+}
+
+///Forges a CBC encryption of the given plaintext using a padding oracle, working backwards from a random final block.
+///The plaintext is PKCS#7 padded before encrypting. Returns the ciphertext and the IV it must be decrypted with,
+///or an error if the block size is zero or the oracle accepts no value for some byte.
+pub fn try_padding_oracle_encrypt<F: Fn(&[u8], &[u8]) -> bool>(oracle: F, block_size: usize, plaintext: &Vec<u8>) -> Result<(Vec<u8>, Vec<u8>), PaddingOracleError> {
+    if block_size == 0 {
+        return Err(PaddingOracleError::IllegalBlockSize(block_size));
+    }
+    let padded = pkcs7_pad(plaintext, block_size);
+    let query_iv = vec![0; block_size];
+
+    //Each block is chosen so that the following block decrypts to the right plaintext
+    let mut current: Vec<u8> = vec![];
+    for _i in 0..block_size {
+        current.push(random());
+    }
+    let mut ciphertext = current.clone();
+    for start in (0..padded.len()).step_by(block_size).rev() {
+        let intermediate = decrypt_block_intermediate(&oracle, &current, &query_iv)?;
+        current = xor_bytes(&intermediate, &padded[start..start + block_size].to_vec());
+        if start > 0 {
+            let mut block = current.clone();
+            block.append(&mut ciphertext);
+            ciphertext = block;
+        }
+    }
+
+    return Ok((ciphertext, current));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aes::{encrypt_cbc, decrypt_cbc};
+    use crate::converter::ascii_to_bytes;
+    use crate::padding::pkcs7_unpad;
+
+    static KEY: [u8; 16] = [0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c];
+    static IV: [u8; 16] = [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f];
+
+    fn aes_oracle(ciphertext: &[u8], iv: &[u8]) -> bool {
+        return pkcs7_unpad(&decrypt_cbc(&ciphertext.to_vec(), &KEY.to_vec(), &iv.to_vec())).is_ok();
+    }
+
+    ///Toy 64-bit Feistel cipher, to test the attack with 8 byte blocks
+    fn feistel(block: &[u8], key: u32, decrypt: bool) -> Vec<u8> {
+        let mut left = u32::from_be_bytes([block[0], block[1], block[2], block[3]]);
+        let mut right = u32::from_be_bytes([block[4], block[5], block[6], block[7]]);
+        let round_keys: Vec<u32> = (0..8).map(|i| key.rotate_left(4 * i) ^ i).collect();
+        for i in 0..8 {
+            let k = if decrypt {round_keys[7 - i]} else {round_keys[i]};
+            let f = (right ^ k).wrapping_mul(0x9e3779b9).rotate_left(11);
+            let new_right = left ^ f;
+            left = right;
+            right = new_right;
+        }
+
+        let mut output = right.to_be_bytes().to_vec();
+        output.extend_from_slice(&left.to_be_bytes());
+        return output;
+    }
+
+    fn feistel_encrypt_cbc(plaintext: &Vec<u8>, key: u32, iv: &Vec<u8>) -> Vec<u8> {
+        let mut ciphertext = vec![];
+        let mut previous = iv.clone();
+        for block in plaintext.chunks(8) {
+            previous = feistel(&xor_bytes(&block.to_vec(), &previous), key, false);
+            ciphertext.extend_from_slice(&previous);
+        }
+        return ciphertext;
+    }
+
+    fn feistel_decrypt_cbc(ciphertext: &[u8], key: u32, iv: &[u8]) -> Vec<u8> {
+        let mut plaintext = vec![];
+        let mut previous = iv.to_vec();
+        for block in ciphertext.chunks(8) {
+            plaintext.append(&mut xor_bytes(&feistel(block, key, true), &previous));
+            previous = block.to_vec();
+        }
+        return plaintext;
+    }
+
+    fn feistel_oracle(ciphertext: &[u8], iv: &[u8]) -> bool {
+        return pkcs7_unpad(&feistel_decrypt_cbc(ciphertext, 0xdeadbeef, iv)).is_ok();
+    }
+
+    #[test]
+    fn test_feistel() {
+        let block = ascii_to_bytes("8 bytes!");
+        assert_eq!(feistel(&feistel(&block, 12345, false), 12345, true), block);
+    }
+
+    #[test]
+    fn test_decrypt() {
+        let plaintext = pkcs7_pad(&ascii_to_bytes("Padding oracle attacks decrypt one byte at a time"), 16);
+        let ciphertext = encrypt_cbc(&plaintext, &KEY.to_vec(), &IV.to_vec());
+        assert_eq!(padding_oracle_decrypt(aes_oracle, 16, &ciphertext, Some(&IV.to_vec())), plaintext);
+    }
+
+    #[test]
+    fn test_decrypt_unknown_iv() {
+        let plaintext = pkcs7_pad(&ascii_to_bytes("The first block is lost without the IV"), 16);
+        let ciphertext = encrypt_cbc(&plaintext, &KEY.to_vec(), &IV.to_vec());
+
+        //Oracle which ignores the IV it is given and uses a secret one instead
+        let oracle = |c: &[u8], _iv: &[u8]| aes_oracle(c, &IV);
+        assert_eq!(padding_oracle_decrypt(&oracle, 16, &ciphertext, None), plaintext[16..].to_vec());
+    }
+
+    #[test]
+    fn test_decrypt_block_size_8() {
+        let iv = ascii_to_bytes("feistel!");
+        let plaintext = pkcs7_pad(&ascii_to_bytes("Works with DES-sized blocks too"), 8);
+        let ciphertext = feistel_encrypt_cbc(&plaintext, 0xdeadbeef, &iv);
+        assert_eq!(padding_oracle_decrypt(feistel_oracle, 8, &ciphertext, Some(&iv)), plaintext);
+    }
+
+    #[test]
+    fn test_encrypt() {
+        let plaintext = ascii_to_bytes("Forged without ever knowing the key");
+        let (ciphertext, iv) = padding_oracle_encrypt(aes_oracle, 16, &plaintext);
+        assert_eq!(pkcs7_unpad(&decrypt_cbc(&ciphertext, &KEY.to_vec(), &iv)).unwrap(), plaintext);
+
+        let (ciphertext, iv) = padding_oracle_encrypt(feistel_oracle, 8, &plaintext);
+        assert_eq!(ciphertext.len(), 40);
+        assert_eq!(pkcs7_unpad(&feistel_decrypt_cbc(&ciphertext, 0xdeadbeef, &iv)).unwrap(), plaintext);
+    }
+
+    #[test]
+    #[should_panic(expected="Ciphertext length must be a nonzero multiple of the block size.")]
+    fn test_decrypt_bad_length() {
+        padding_oracle_decrypt(aes_oracle, 16, &vec![0; 20], None);
+    }
+
+    #[test]
+    fn test_try_decrypt() {
+        let ciphertext = encrypt_cbc(&pkcs7_pad(&ascii_to_bytes("Bad oracles"), 16), &KEY.to_vec(), &IV.to_vec());
+        assert_eq!(try_padding_oracle_decrypt(aes_oracle, 16, &ciphertext, Some(&vec![0; 8])), Err(PaddingOracleError::IllegalIVLength(8)));
+        assert_eq!(try_padding_oracle_decrypt(aes_oracle, 0, &ciphertext, None), Err(PaddingOracleError::IllegalBlockSize(0)));
+        assert_eq!(try_padding_oracle_decrypt(aes_oracle, 16, &vec![0; 20], None), Err(PaddingOracleError::IllegalCiphertextLength(20)));
+
+        //An oracle which never accepts anything fails on the last byte of the first block it tries
+        let broken = |_c: &[u8], _iv: &[u8]| false;
+        assert_eq!(try_padding_oracle_decrypt(broken, 16, &ciphertext, Some(&IV.to_vec())), Err(PaddingOracleError::NoValidByte(15)));
+        assert_eq!(try_padding_oracle_encrypt(broken, 16, &ascii_to_bytes("Bad oracles")), Err(PaddingOracleError::NoValidByte(15)));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,466 @@
+use std::ops::{Add, Sub, Mul, Div, Rem};
+use gmp::mpz::Mpz;
+use crate::gf128::GF128;
+
+///Polynomial with coefficients in GF(2^128), stored lowest degree first.
+///Trailing zero coefficients are always trimmed, so the zero polynomial has no coefficients.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Polynomial {
+    pub coefficients: Vec<GF128>
+}
+
+impl Polynomial {
+    ///Creates a polynomial from coefficients given lowest degree first
+    pub fn new(coefficients: Vec<GF128>) -> Polynomial {
+        let mut ret = Polynomial {coefficients};
+        ret.trim();
+        return ret;
+    }
+
+    ///Returns the zero polynomial
+    pub fn zero() -> Polynomial {
+        return Polynomial {coefficients: vec![]};
+    }
+
+    ///Returns the constant polynomial 1
+    pub fn one() -> Polynomial {
+        return Polynomial {coefficients: vec![GF128::one()]};
+    }
+
+    ///Returns the polynomial c*x^degree
+    pub fn monomial(coefficient: GF128, degree: usize) -> Polynomial {
+        let mut coefficients = vec![GF128::zero(); degree + 1];
+        coefficients[degree] = coefficient;
+        return Polynomial::new(coefficients);
+    }
+
+    ///Returns a random polynomial of degree less than the given bound
+    pub fn random(degree_bound: usize) -> Polynomial {
+        let mut coefficients = vec![];
+        for _i in 0..degree_bound {
+            coefficients.push(GF128::random());
+        }
+        return Polynomial::new(coefficients);
+    }
+
+    ///Removes trailing zero coefficients
+    fn trim(&mut self) {
+        while !self.coefficients.is_empty() && self.coefficients[self.coefficients.len() - 1].is_zero() {
+            self.coefficients.pop();
+        }
+    }
+
+    ///Returns true if this is the zero polynomial
+    pub fn is_zero(&self) -> bool {
+        return self.coefficients.is_empty();
+    }
+
+    ///Returns true if this is the constant polynomial 1
+    pub fn is_one(&self) -> bool {
+        return self.coefficients.len() == 1 && self.coefficients[0] == GF128::one();
+    }
+
+    ///Returns the degree of the polynomial (the zero polynomial is treated as degree 0)
+    pub fn degree(&self) -> usize {
+        return if self.is_zero() {0} else {self.coefficients.len() - 1};
+    }
+
+    ///Returns the coefficient of the highest-degree term
+    pub fn leading_coefficient(&self) -> GF128 {
+        return match self.coefficients.last() {
+            None => GF128::zero(),
+            Some(c) => *c
+        }
+    }
+
+    ///Scales the polynomial so that its leading coefficient is 1
+    pub fn monic(&self) -> Polynomial {
+        if self.is_zero() {
+            return Polynomial::zero();
+        }
+
+        let inverse = self.leading_coefficient().inverse();
+        return Polynomial::new(self.coefficients.iter().map(|c| *c * inverse).collect());
+    }
+
+    ///Evaluates the polynomial at x using Horner's rule
+    pub fn evaluate(&self, x: GF128) -> GF128 {
+        let mut result = GF128::zero();
+        for c in self.coefficients.iter().rev() {
+            result = result * x + *c;
+        }
+        return result;
+    }
+
+    ///Performs long division, returning (quotient, remainder).
+    ///Will panic if the divisor is the zero polynomial.
+    pub fn div_rem(&self, divisor: &Polynomial) -> (Polynomial, Polynomial) {
+        if divisor.is_zero() {
+            panic!("Division by the zero polynomial!");
+        }
+
+        let mut remainder = self.clone();
+        if remainder.degree() < divisor.degree() || remainder.is_zero() {
+            return (Polynomial::zero(), remainder);
+        }
+
+        let divisor_degree = divisor.degree();
+        let lead_inverse = divisor.leading_coefficient().inverse();
+        let mut quotient = vec![GF128::zero(); remainder.degree() - divisor_degree + 1];
+
+        //Cancel the leading term of the remainder until it is shorter than the divisor
+        while !remainder.is_zero() && remainder.degree() >= divisor_degree {
+            let shift = remainder.degree() - divisor_degree;
+            let factor = remainder.leading_coefficient() * lead_inverse;
+            quotient[shift] = factor;
+
+            for i in 0..=divisor_degree {
+                remainder.coefficients[i + shift] = remainder.coefficients[i + shift] - (divisor.coefficients[i] * factor);
+            }
+            remainder.trim();
+        }
+
+        return (Polynomial::new(quotient), remainder);
+    }
+
+    ///Computes the monic greatest common divisor of two polynomials
+    pub fn gcd(&self, other: &Polynomial) -> Polynomial {
+        let mut a = self.clone();
+        let mut b = other.clone();
+
+        while !b.is_zero() {
+            let r = &a % &b;
+            a = b;
+            b = r;
+        }
+
+        return a.monic();
+    }
+
+    ///Computes the formal derivative. In characteristic 2 even-degree terms vanish.
+    pub fn derivative(&self) -> Polynomial {
+        let mut coefficients = vec![];
+        for i in 1..self.coefficients.len() {
+            coefficients.push(if i % 2 == 1 {self.coefficients[i]} else {GF128::zero()});
+        }
+        return Polynomial::new(coefficients);
+    }
+
+    ///Computes self^exponent modulo the given polynomial by square-and-multiply
+    pub fn pow_mod(&self, exponent: &Mpz, modulus: &Polynomial) -> Polynomial {
+        let mut result = &Polynomial::one() % modulus;
+        let base = self % modulus;
+
+        for i in (0..exponent.bit_length()).rev() {
+            result = &(&result * &result) % modulus;
+            if exponent.tstbit(i) {
+                result = &(&result * &base) % modulus;
+            }
+        }
+
+        return result;
+    }
+
+    ///Computes the square root of a polynomial whose odd-degree coefficients are all zero.
+    ///Each remaining coefficient c is replaced by c^(2^127), its square root in GF(2^128).
+    fn sqrt(&self) -> Polynomial {
+        let mut coefficients = vec![];
+        for i in (0..self.coefficients.len()).step_by(2) {
+            let mut c = self.coefficients[i];
+            for _j in 0..127 {
+                c = c * c;
+            }
+            coefficients.push(c);
+        }
+        return Polynomial::new(coefficients);
+    }
+
+    ///Splits a monic polynomial into square-free factors, returned as (factor, multiplicity) pairs
+    pub fn square_free_factorization(&self) -> Vec<(Polynomial, usize)> {
+        let mut factors = vec![];
+        let mut c = self.gcd(&self.derivative());
+        let mut w = self / &c;
+
+        //Collect factors whose multiplicity is not divisible by 2
+        let mut multiplicity = 1;
+        while !w.is_one() {
+            let y = w.gcd(&c);
+            let factor = &w / &y;
+            if !factor.is_one() {
+                factors.push((factor, multiplicity));
+            }
+            w = y;
+            c = &c / &w;
+            multiplicity += 1;
+        }
+
+        //Whatever remains is a perfect square - recurse on its square root
+        if !c.is_one() {
+            for (factor, m) in c.sqrt().square_free_factorization() {
+                factors.push((factor, 2 * m));
+            }
+        }
+
+        return factors;
+    }
+
+    ///Splits a monic square-free polynomial into (product, degree) pairs,
+    ///where each product is made up of all irreducible factors of that degree
+    pub fn distinct_degree_factorization(&self) -> Vec<(Polynomial, usize)> {
+        let mut factors = vec![];
+        let mut remaining = self.clone();
+        let x = Polynomial::monomial(GF128::one(), 1);
+        let mut h = x.clone();
+        let mut degree = 1;
+
+        while remaining.degree() >= 2 * degree {
+            //Raise h to the q = 2^128 power by repeated squaring, giving x^(q^degree)
+            for _i in 0..128 {
+                h = &(&h * &h) % &remaining;
+            }
+
+            let g = remaining.gcd(&(&h - &x));
+            if !g.is_one() {
+                remaining = &remaining / &g;
+                h = &h % &remaining;
+                factors.push((g, degree));
+            }
+            degree += 1;
+        }
+
+        if remaining.degree() > 0 {
+            let d = remaining.degree();
+            factors.push((remaining, d));
+        }
+
+        return factors;
+    }
+
+    ///Splits a monic square-free polynomial whose irreducible factors all have the given degree
+    ///using Cantor-Zassenhaus with the characteristic-2 exponent (q^d - 1)/3
+    pub fn equal_degree_factorization(&self, degree: usize) -> Vec<Polynomial> {
+        let target_count = self.degree() / degree;
+        let exponent = ((Mpz::one() << (128 * degree)) - Mpz::one()) / Mpz::from(3);
+        let mut factors = vec![self.clone()];
+
+        while factors.len() < target_count {
+            let h = Polynomial::random(self.degree());
+            let g = &h.pow_mod(&exponent, self) - &Polynomial::one();
+
+            //Attempt to split every factor which is not yet irreducible
+            let mut new_factors = vec![];
+            for u in factors {
+                if u.degree() > degree {
+                    let d = u.gcd(&g);
+                    if !d.is_one() && d != u {
+                        new_factors.push(&u / &d);
+                        new_factors.push(d);
+                        continue;
+                    }
+                }
+                new_factors.push(u);
+            }
+            factors = new_factors;
+        }
+
+        return factors;
+    }
+
+    ///Completely factors the polynomial into monic irreducibles, returned as (factor, multiplicity) pairs
+    pub fn factor(&self) -> Vec<(Polynomial, usize)> {
+        let mut factors = vec![];
+        for (square_free, multiplicity) in self.monic().square_free_factorization() {
+            for (product, degree) in square_free.distinct_degree_factorization() {
+                for irreducible in product.equal_degree_factorization(degree) {
+                    factors.push((irreducible, multiplicity));
+                }
+            }
+        }
+        return factors;
+    }
+
+    ///Finds all distinct roots of the polynomial in GF(2^128)
+    pub fn roots(&self) -> Vec<GF128> {
+        let mut roots = vec![];
+        for (square_free, _multiplicity) in self.monic().square_free_factorization() {
+            for (product, degree) in square_free.distinct_degree_factorization() {
+                if degree != 1 {
+                    continue;
+                }
+                //Each monic linear factor x + c has the root c, since -c = c in characteristic 2
+                for linear in product.equal_degree_factorization(1) {
+                    roots.push(linear.coefficients[0]);
+                }
+            }
+        }
+        return roots;
+    }
+}
+
+impl<'a> Add<&'a Polynomial> for &'a Polynomial {
+    type Output = Polynomial;
+
+    fn add(self, other: &Polynomial) -> Polynomial {
+        let length = if self.coefficients.len() > other.coefficients.len() {self.coefficients.len()} else {other.coefficients.len()};
+        let mut coefficients = vec![GF128::zero(); length];
+        for i in 0..self.coefficients.len() {
+            coefficients[i] = coefficients[i] + self.coefficients[i];
+        }
+        for i in 0..other.coefficients.len() {
+            coefficients[i] = coefficients[i] + other.coefficients[i];
+        }
+        return Polynomial::new(coefficients);
+    }
+}
+
+///Subtraction is identical to addition in characteristic 2
+impl<'a> Sub<&'a Polynomial> for &'a Polynomial {
+    type Output = Polynomial;
+
+    fn sub(self, other: &Polynomial) -> Polynomial {
+        return self + other;
+    }
+}
+
+impl<'a> Mul<&'a Polynomial> for &'a Polynomial {
+    type Output = Polynomial;
+
+    fn mul(self, other: &Polynomial) -> Polynomial {
+        if self.is_zero() || other.is_zero() {
+            return Polynomial::zero();
+        }
+
+        let mut coefficients = vec![GF128::zero(); self.coefficients.len() + other.coefficients.len() - 1];
+        for i in 0..self.coefficients.len() {
+            for j in 0..other.coefficients.len() {
+                coefficients[i + j] = coefficients[i + j] + (self.coefficients[i] * other.coefficients[j]);
+            }
+        }
+        return Polynomial::new(coefficients);
+    }
+}
+
+impl<'a> Div<&'a Polynomial> for &'a Polynomial {
+    type Output = Polynomial;
+
+    fn div(self, other: &Polynomial) -> Polynomial {
+        return self.div_rem(other).0;
+    }
+}
+
+impl<'a> Rem<&'a Polynomial> for &'a Polynomial {
+    type Output = Polynomial;
+
+    fn rem(self, other: &Polynomial) -> Polynomial {
+        return self.div_rem(other).1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ///Builds the monic linear polynomial x + c
+    fn linear(c: GF128) -> Polynomial {
+        return Polynomial::new(vec![c, GF128::one()]);
+    }
+
+    #[test]
+    fn test_new_trims() {
+        let p = Polynomial::new(vec![GF128::one(), GF128::zero(), GF128::zero()]);
+        assert_eq!(p, Polynomial::one());
+        assert_eq!(p.degree(), 0);
+        assert!(Polynomial::new(vec![GF128::zero()]).is_zero());
+    }
+
+    #[test]
+    fn test_mul_evaluate() {
+        let a = GF128::random();
+        let b = GF128::random();
+        let product = &linear(a) * &linear(b);
+
+        assert_eq!(product.degree(), 2);
+        assert_eq!(product.evaluate(a), GF128::zero());
+        assert_eq!(product.evaluate(b), GF128::zero());
+        assert_eq!(product.coefficients, vec![a * b, a + b, GF128::one()]);
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let a = Polynomial::random(6);
+        let b = Polynomial::random(3);
+        let (q, r) = a.div_rem(&b);
+
+        assert!(r.degree() < b.degree());
+        assert_eq!(&(&q * &b) + &r, a);
+    }
+
+    #[test]
+    #[should_panic(expected="Division by the zero polynomial!")]
+    fn test_div_rem_zero() {
+        Polynomial::one().div_rem(&Polynomial::zero());
+    }
+
+    #[test]
+    fn test_gcd() {
+        let common = linear(GF128::random());
+        let a = &common * &linear(GF128::random());
+        let b = &common * &linear(GF128::random());
+
+        assert_eq!(a.gcd(&b), common);
+    }
+
+    #[test]
+    fn test_derivative() {
+        let c: Vec<GF128> = (0..4).map(|_| GF128::random()).collect();
+        let p = Polynomial::new(c.clone());
+        assert_eq!(p.derivative(), Polynomial::new(vec![c[1], GF128::zero(), c[3]]));
+    }
+
+    #[test]
+    fn test_square_free_factorization() {
+        let a = linear(GF128::random());
+        let b = linear(GF128::random());
+        let p = &(&a * &a) * &b;
+
+        let factors = p.square_free_factorization();
+        assert!(factors.contains(&(b, 1)));
+        assert!(factors.contains(&(a, 2)));
+    }
+
+    #[test]
+    fn test_roots() {
+        let r1 = GF128::random();
+        let r2 = GF128::random();
+        let r3 = GF128::random();
+
+        //Multiply in a quadratic with no roots (x^2 + x + c, with c chosen so it is irreducible) to check it is ignored
+        let mut quadratic = Polynomial::new(vec![GF128::random(), GF128::one(), GF128::one()]);
+        while quadratic.roots().len() != 0 {
+            quadratic = Polynomial::new(vec![GF128::random(), GF128::one(), GF128::one()]);
+        }
+
+        let p = &(&(&linear(r1) * &linear(r2)) * &linear(r3)) * &quadratic;
+        let roots = p.roots();
+
+        assert_eq!(roots.len(), 3);
+        assert!(roots.contains(&r1));
+        assert!(roots.contains(&r2));
+        assert!(roots.contains(&r3));
+    }
+
+    #[test]
+    fn test_factor() {
+        let a = linear(GF128::random());
+        let b = Polynomial::new(vec![GF128::random(), GF128::random(), GF128::one()]);
+        let p = &(&a * &a) * &b;
+
+        let mut product = Polynomial::one();
+        for (factor, multiplicity) in p.factor() {
+            for _i in 0..multiplicity {
+                product = &product * &factor;
+            }
+        }
+        assert_eq!(product, p);
+    }
+}
@@ -1,13 +1,74 @@
-use gmp::mpz::Mpz;
+use gmp::mpz::{Mpz, ProbabPrimeResult};
+use thiserror::Error;
 use crate::converter::hex_to_bytes;
 use rand::random;
 use crate::hash::Hash;
-use crate::padding::{pkcs15_signature_pad, pkcs15_signature_unpad_lazy};
+use crate::mac::constant_time_eq;
+use crate::padding::{pkcs15_signature_pad, pkcs15_signature_parse, pkcs15_signature_parse_lenient, pkcs15_signature_unpad_lazy, pss_encode, pss_verify};
+
+///Custom RSA key errors
+#[derive(Error, Debug, PartialEq)]
+pub enum RSAError {
+    ///Error for a public exponent which is even or less than 3
+    #[error("Illegal public exponent {0} passed for an RSA key!")]
+    IllegalPublicExponent(Mpz),
+    ///Error for validating a key which was created without its prime factors
+    #[error("RSA key does not have its prime factors!")]
+    MissingFactors,
+    ///Error for a factor of the modulus which is not prime
+    #[error("RSA factor {0} is not prime!")]
+    CompositeFactor(Mpz),
+    ///Error for a modulus which is not the product of the key's factors
+    #[error("RSA modulus is not the product of its factors!")]
+    ModulusMismatch,
+    ///Error for factors so close together that Fermat's method finds them quickly
+    #[error("RSA factors are too close together!")]
+    FactorsTooClose,
+    ///Error for a public exponent which shares a factor with (p-1)(q-1)
+    #[error("RSA public exponent is not invertible!")]
+    ExponentNotInvertible,
+    ///Error for a private exponent which is not the inverse of the public exponent
+    #[error("RSA private exponent does not match the public exponent!")]
+    PrivateExponentMismatch,
+    ///Error for a signature which was checked before release and found to be faulty
+    #[error("RSA signature failed verification and was not released!")]
+    FaultySignature,
+}
+
+///Number of Miller-Rabin rounds used when validating the factors of a key
+const PRIMALITY_ROUNDS: i32 = 25;
+
+///Bits by which |p-q| must exceed half the modulus length, for keys long enough for it to matter (as in FIPS 186-4)
+const MIN_FACTOR_DISTANCE_BITS: usize = 100;
 
 pub struct RSA {
     pub n: Mpz,
     pub e: Mpz,
-    d: Mpz
+    d: Mpz,
+    crt: Option<CRTParameters>,
+    ///Bit flipped in the mod p half of every CRT private key operation, to simulate a hardware fault
+    fault_bit: Option<usize>,
+    ///Whether signatures are checked with the public key before they are released
+    verify_before_release: bool
+}
+
+///Prime factors of the modulus and the exponents used to compute with them via the Chinese remainder theorem
+struct CRTParameters {
+    p: Mpz,
+    q: Mpz,
+    ///d mod (p-1)
+    dp: Mpz,
+    ///d mod (q-1)
+    dq: Mpz,
+    ///q^-1 mod p
+    q_inv: Mpz
+}
+
+///Generates RSA keys with a chosen public exponent
+pub struct RSABuilder {
+    key_length: usize,
+    e: Mpz,
+    verify_before_release: bool
 }
 
 pub struct RSASignature {
@@ -15,6 +76,19 @@ pub struct RSASignature {
     pub signature: Mpz
 }
 
+///Padding schemes for RSA signatures, with the hash used for the message
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SignatureScheme {
+    ///PKCS#1.5 signature padding, verified by checking the whole padded block
+    PKCS15(Hash),
+    ///PKCS#1.5 signature padding, verified by parsing the padded block and its DER encoded DigestInfo strictly
+    PKCS15DER(Hash),
+    ///PKCS#1.5 signature padding, verified by a lenient parser which accepts BERserk-style forgeries
+    PKCS15Lenient(Hash),
+    ///PSS signature padding (RFC 8017) with the given salt length, using the hash for MGF1 as well
+    PSS(Hash, usize)
+}
+
 ///Generates a random prime of the given bit length
 fn generate_prime(bit_length: usize) -> Mpz {
     //Compute desired byte length and how many extra bits this leaves
@@ -69,34 +143,174 @@ pub fn inverse_mod(a: &Mpz, b: &Mpz) -> Option<Mpz> {
     return Some(t);
 }
 
+///Returns an error if the public exponent is even or less than 3
+fn validate_public_exponent(e: &Mpz) -> Result<(), RSAError> {
+    if e < &Mpz::from(3) || !e.tstbit(0) {
+        return Err(RSAError::IllegalPublicExponent(e.clone()));
+    }
+    return Ok(());
+}
+
+impl RSABuilder {
+    ///Sets the public exponent, which must be odd and at least 3
+    pub fn public_exponent(mut self, e: &Mpz) -> RSABuilder {
+        self.e = e.clone();
+        return self;
+    }
+
+    ///Sets whether the key checks its signatures with the public key before releasing them
+    pub fn verify_before_release(mut self, verify: bool) -> RSABuilder {
+        self.verify_before_release = verify;
+        return self;
+    }
+
+    ///Generates a new RSA key.
+    ///Will panic if the public exponent is even or less than 3.
+    pub fn build(&self) -> RSA {
+        return self.try_build().unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    ///Generates a new RSA key.
+    ///Returns an error if the public exponent is even or less than 3.
+    pub fn try_build(&self) -> Result<RSA, RSAError> {
+        validate_public_exponent(&self.e)?;
+
+        loop {
+            //Generate p and q to multiply to the correct length without being too close together
+            let p = generate_prime(self.key_length / 2 - 10);
+            let q = generate_prime(self.key_length / 2 + 10);
+
+            //If n is too short, or e is not invertible mod (p-1)(q-1), try again.
+            if (&p * &q).bit_length() != self.key_length {
+                continue;
+            }
+            match RSA::new_from_primes(&p, &q, &self.e) {
+                Err(_) => continue,
+                Ok(mut rsa) => {
+                    rsa.verify_before_release = self.verify_before_release;
+                    return Ok(rsa);
+                }
+            }
+        }
+    }
+}
+
 impl RSA {
     ///Generates a new RSA key with the given key length (in bits) and e=3
     pub fn new(key_length: usize) -> RSA {
-        let mut ret = RSA {
-            n: Mpz::zero(),
+        return RSA::builder(key_length).build();
+    }
+
+    ///Starts building an RSA key with the given key length (in bits).
+    ///The public exponent defaults to 3, and signatures are not checked before release.
+    pub fn builder(key_length: usize) -> RSABuilder {
+        return RSABuilder {
+            key_length,
             e: Mpz::from(3),
-            d: Mpz::zero()
+            verify_before_release: false
         };
+    }
 
-        loop {
-            //Generate p and q to multiply to the correct length without being too close together
-            let p = generate_prime(key_length / 2 - 10);
-            let q = generate_prime(key_length / 2 + 10);
+    ///Creates an RSA instance from an existing modulus and exponents
+    pub fn new_from_parameters(n: &Mpz, e: &Mpz, d: &Mpz) -> RSA {
+        return RSA {
+            n: n.clone(),
+            e: e.clone(),
+            d: d.clone(),
+            crt: None,
+            fault_bit: None,
+            verify_before_release: false
+        };
+    }
 
-            //Caclulate n = p*q. If n is too short, try again.
-            ret.n = &p * &q;
-            if ret.n.bit_length() != key_length {
-                continue;
-            }
+    ///Creates an RSA instance from an existing modulus, exponents and prime factors, without checking them.
+    ///The CRT parameters are derived from the factors. Use validate to check that the components are consistent.
+    pub fn new_from_components(n: &Mpz, e: &Mpz, d: &Mpz, p: &Mpz, q: &Mpz) -> RSA {
+        return RSA {
+            n: n.clone(),
+            e: e.clone(),
+            d: d.clone(),
+            crt: Some(CRTParameters {
+                p: p.clone(),
+                q: q.clone(),
+                dp: d.modulus(&(p - Mpz::one())),
+                dq: d.modulus(&(q - Mpz::one())),
+                q_inv: inverse_mod(q, p).unwrap_or(Mpz::zero())
+            }),
+            fault_bit: None,
+            verify_before_release: false
+        };
+    }
+
+    ///Creates an RSA key from its prime factors and public exponent, calculating d = e^-1 mod (p-1)(q-1).
+    ///Returns an error if the resulting key does not pass validation.
+    pub fn new_from_primes(p: &Mpz, q: &Mpz, e: &Mpz) -> Result<RSA, RSAError> {
+        validate_public_exponent(e)?;
+        let et = (p - Mpz::one()) * (q - Mpz::one());
+        let d = match inverse_mod(e, &et) {
+            None => return Err(RSAError::ExponentNotInvertible),
+            Some(d) => d
+        };
+
+        let rsa = RSA::new_from_components(&(p * q), e, &d, p, q);
+        rsa.validate()?;
+        return Ok(rsa);
+    }
+
+    ///Checks that the key's factors are prime and multiply to the modulus, that they are not too close together,
+    ///and that the exponents are inverses of each other.
+    ///Returns an error describing the first problem found, or if the key was created without its factors.
+    pub fn validate(&self) -> Result<(), RSAError> {
+        let crt = match &self.crt {
+            None => return Err(RSAError::MissingFactors),
+            Some(crt) => crt
+        };
 
-            //Calculate d = e^-1 mod (p-1)(q-1). If no inverse exists, try again.
-            let et = (&p - Mpz::one()) * (&q - Mpz::one());
-            match inverse_mod(&ret.e, &et) {
-                None => continue,
-                Some(d) => ret.d = d
+        for factor in [&crt.p, &crt.q].iter() {
+            if factor.probab_prime(PRIMALITY_ROUNDS) == ProbabPrimeResult::NotPrime {
+                return Err(RSAError::CompositeFactor((*factor).clone()));
             }
-            return ret;
         }
+        if &crt.p * &crt.q != self.n {
+            return Err(RSAError::ModulusMismatch);
+        }
+
+        //Checks only the factors are distinct for keys too short for the distance requirement
+        let min_distance = (self.n.bit_length() / 2).saturating_sub(MIN_FACTOR_DISTANCE_BITS);
+        if (&crt.p - &crt.q).abs().bit_length() <= min_distance || crt.p == crt.q {
+            return Err(RSAError::FactorsTooClose);
+        }
+
+        validate_public_exponent(&self.e)?;
+        let p_minus_one = &crt.p - Mpz::one();
+        let q_minus_one = &crt.q - Mpz::one();
+        if self.e.gcd(&(&p_minus_one * &q_minus_one)) != Mpz::one() {
+            return Err(RSAError::ExponentNotInvertible);
+        }
+
+        //d only has to invert e mod lcm(p-1, q-1), which divides (p-1)(q-1)
+        if (&self.e * &self.d).modulus(&p_minus_one.lcm(&q_minus_one)) != Mpz::one() {
+            return Err(RSAError::PrivateExponentMismatch);
+        }
+
+        return Ok(());
+    }
+
+    ///Raises x to the private exponent mod n, using the CRT parameters if the key has them
+    fn private_operation(&self, x: &Mpz) -> Mpz {
+        return match &self.crt {
+            None => x.powm(&self.d, &self.n),
+            Some(crt) => {
+                //Combine x^d mod p and x^d mod q with Garner's formula
+                let mut m_p = x.powm(&crt.dp, &crt.p);
+                if let Some(bit) = self.fault_bit {
+                    m_p.combit(bit);
+                }
+                let m_q = x.powm(&crt.dq, &crt.q);
+                let h = (&crt.q_inv * (m_p - &m_q)).modulus(&crt.p);
+                m_q + h * &crt.q
+            }
+        };
     }
 
     ///Encrypts the given message with the instance's public key
@@ -110,23 +324,65 @@ impl RSA {
     ///Decrypts the given message with the instance's private key
     pub fn decrypt(&self, ciphertext: &Vec<u8>) -> Vec<u8> {
         let c = Mpz::from(&ciphertext[0..]);
-        let m = c.powm(&self.d, &self.n);
+        let m = self.private_operation(&c);
 
         return hex_to_bytes(&m.to_str_radix(16));
     }
 
-    ///Generates a PKCS#1.5 padded signature for this message with the instance's private key
+    ///Makes every later CRT private key operation flip the given bit of its mod p half, or stops doing so for None.
+    ///Has no effect on keys created without their factors.
+    pub fn set_fault(&mut self, bit: Option<usize>) {
+        self.fault_bit = bit;
+    }
+
+    ///Sets whether the key checks its signatures with the public key before releasing them
+    pub fn set_verify_before_release(&mut self, verify: bool) {
+        self.verify_before_release = verify;
+    }
+
+    ///Generates a PKCS#1.5 padded signature for this message with the instance's private key.
+    ///Will panic if signatures are checked before release and this one is faulty.
     pub fn sign_message(&self, message: &Vec<u8>) -> RSASignature {
-        let hash = Hash::MD4.digest(&message);
-        let padded = pkcs15_signature_pad(&hash, self.n.bit_length(), Hash::MD4);
+        return self.sign_message_with(message, SignatureScheme::PKCS15(Hash::MD4));
+    }
+
+    ///Generates a PKCS#1.5 padded signature for this message with the instance's private key.
+    ///Returns an error if signatures are checked before release and this one is faulty.
+    pub fn try_sign_message(&self, message: &Vec<u8>) -> Result<RSASignature, RSAError> {
+        return self.try_sign_message_with(message, SignatureScheme::PKCS15(Hash::MD4));
+    }
+
+    ///Generates a signature for this message with the instance's private key, padded with the given scheme.
+    ///Will panic if signatures are checked before release and this one is faulty.
+    pub fn sign_message_with(&self, message: &Vec<u8>, scheme: SignatureScheme) -> RSASignature {
+        return self.try_sign_message_with(message, scheme).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    ///Generates a signature for this message with the instance's private key, padded with the given scheme.
+    ///Returns an error if signatures are checked before release and this one is faulty.
+    pub fn try_sign_message_with(&self, message: &Vec<u8>, scheme: SignatureScheme) -> Result<RSASignature, RSAError> {
+        let padded = match scheme {
+            SignatureScheme::PKCS15(hash) | SignatureScheme::PKCS15DER(hash) | SignatureScheme::PKCS15Lenient(hash) => {
+                pkcs15_signature_pad(&hash.digest(message), self.n.bit_length(), hash)
+            },
+            SignatureScheme::PSS(hash, salt_length) => pss_encode(&hash.digest(message), self.n.bit_length() - 1, hash, salt_length)
+        };
         let h = Mpz::from(&padded[0..]);
-        return RSASignature {
+        let signature = self.private_operation(&h);
+
+        //A faulty signature gives away the factors of n, so it must never leave the signer
+        if self.verify_before_release && signature.powm(&self.e, &self.n) != h {
+            return Err(RSAError::FaultySignature);
+        }
+
+        return Ok(RSASignature {
             message: message.clone(),
-            signature: h.powm(&self.d, &self.n)
-        };
+            signature
+        });
     }
 
-    ///Verifies a PKCS#1.5 padded signature with the instance's public key
+    ///Verifies a PKCS#1.5 padded MD4 signature with the instance's public key.
+    ///Only the start of the padded block is parsed, so signatures can be forged as in challenge 42.
     pub fn verify_signature(&self, signature: &RSASignature) -> bool {
         let h = Hash::MD4.digest(&signature.message);
         let v = signature.signature.powm(&self.e, &self.n);
@@ -138,6 +394,44 @@ impl RSA {
             Err(_) => false
         }
     }
+
+    ///Verifies a signature padded with the given scheme with the instance's public key.
+    ///The whole padded block is checked, so there is no room for garbage bytes.
+    pub fn verify_signature_with(&self, signature: &RSASignature, scheme: SignatureScheme) -> bool {
+        if signature.signature < Mpz::zero() || signature.signature >= self.n {
+            return false;
+        }
+        let v = signature.signature.powm(&self.e, &self.n);
+        let v_bytes = hex_to_bytes(&v.to_str_radix(16));
+
+        return match scheme {
+            SignatureScheme::PKCS15(hash) => {
+                //Rebuild the padded block from the message and compare the whole thing
+                let expected = pkcs15_signature_pad(&hash.digest(&signature.message), self.n.bit_length(), hash);
+                if v_bytes.len() > expected.len() {
+                    return false;
+                }
+                let mut padded = vec![0x00; expected.len() - v_bytes.len()];
+                padded.extend_from_slice(&v_bytes);
+                constant_time_eq(&padded, &expected)
+            },
+            SignatureScheme::PKCS15DER(hash) | SignatureScheme::PKCS15Lenient(hash) => {
+                let mut padded = vec![0x00; (self.n.bit_length() / 8).saturating_sub(v_bytes.len())];
+                padded.extend_from_slice(&v_bytes);
+                let parsed = match scheme {
+                    SignatureScheme::PKCS15DER(_) => pkcs15_signature_parse(&padded),
+                    _ => pkcs15_signature_parse_lenient(&padded)
+                };
+                match parsed {
+                    Ok((algorithm, digest)) => algorithm == hash && constant_time_eq(&digest, &hash.digest(&signature.message)),
+                    Err(_) => false
+                }
+            },
+            SignatureScheme::PSS(hash, salt_length) => {
+                pss_verify(&hash.digest(&signature.message), &v_bytes, self.n.bit_length() - 1, hash, salt_length)
+            }
+        };
+    }
 }
 
 #[cfg(test)]
@@ -150,7 +444,10 @@ mod tests {
         let r = RSA {
             n: Mpz::from(3233),
             e: Mpz::from(17),
-            d: Mpz::from(413)
+            d: Mpz::from(413),
+            crt: None,
+            fault_bit: None,
+            verify_before_release: false
         };
 
         assert_eq!(r.encrypt(&vec![65]), vec![10, 230]);
@@ -161,12 +458,24 @@ mod tests {
         let r = RSA {
             n: Mpz::from(3233),
             e: Mpz::from(17),
-            d: Mpz::from(413)
+            d: Mpz::from(413),
+            crt: None,
+            fault_bit: None,
+            verify_before_release: false
         };
 
         assert_eq!(r.decrypt(&vec![10, 230]), vec![65]);
     }
 
+    #[test]
+    fn test_new_from_parameters() {
+        let r = RSA::new_from_parameters(&Mpz::from(3233), &Mpz::from(17), &Mpz::from(413));
+
+        assert_eq!(r.n, Mpz::from(3233));
+        assert_eq!(r.e, Mpz::from(17));
+        assert_eq!(r.decrypt(&r.encrypt(&vec![65])), vec![65]);
+    }
+
     #[test]
     fn test_large_encrypt_decrypt() {
         let r = RSA::new(1024);
@@ -202,4 +511,115 @@ mod tests {
         s.signature = &s.signature + &Mpz::one();
         assert!(!r.verify_signature(&s));
     }
+
+    #[test]
+    fn test_builder_public_exponent() {
+        let r = RSA::builder(1024).public_exponent(&Mpz::from(65537)).build();
+        let message = ascii_to_bytes("This is a test message for a key with e=65537.");
+
+        assert_eq!(r.e, Mpz::from(65537));
+        assert_eq!(r.n.bit_length(), 1024);
+        assert_eq!(r.validate(), Ok(()));
+        assert_eq!(r.decrypt(&r.encrypt(&message)), message);
+        assert!(r.verify_signature(&r.sign_message(&message)));
+    }
+
+    #[test]
+    fn test_builder_illegal_exponent() {
+        for e in [0, 1, 2, 65536].iter() {
+            let result = RSA::builder(1024).public_exponent(&Mpz::from(*e)).try_build();
+            assert_eq!(result.err(), Some(RSAError::IllegalPublicExponent(Mpz::from(*e))));
+        }
+    }
+
+    #[test]
+    fn test_crt_matches_plain() {
+        let r = RSA::builder(1024).public_exponent(&Mpz::from(17)).build();
+        let plain = RSA::new_from_parameters(&r.n, &r.e, &r.d);
+        let ciphertext = r.encrypt(&ascii_to_bytes("Both ways of decrypting should agree."));
+
+        assert_eq!(r.decrypt(&ciphertext), plain.decrypt(&ciphertext));
+        assert_eq!(r.sign_message(&ciphertext).signature, plain.sign_message(&ciphertext).signature);
+    }
+
+    #[test]
+    fn test_new_from_primes() {
+        let r = RSA::new_from_primes(&Mpz::from(61), &Mpz::from(53), &Mpz::from(17)).unwrap();
+
+        assert_eq!(r.n, Mpz::from(3233));
+        assert_eq!(r.d, Mpz::from(2753));
+        assert_eq!(r.decrypt(&vec![10, 230]), vec![65]);
+    }
+
+    #[test]
+    fn test_new_from_primes_invalid() {
+        assert_eq!(RSA::new_from_primes(&Mpz::from(61), &Mpz::from(53), &Mpz::from(3)).err(), Some(RSAError::ExponentNotInvertible));
+        assert_eq!(RSA::new_from_primes(&Mpz::from(63), &Mpz::from(53), &Mpz::from(17)).err(), Some(RSAError::CompositeFactor(Mpz::from(63))));
+        assert_eq!(RSA::new_from_primes(&Mpz::from(61), &Mpz::from(61), &Mpz::from(17)).err(), Some(RSAError::FactorsTooClose));
+    }
+
+    #[test]
+    fn test_validate() {
+        let n = Mpz::from(3233);
+        let e = Mpz::from(17);
+        let p = Mpz::from(61);
+        let q = Mpz::from(53);
+
+        assert_eq!(RSA::new_from_parameters(&n, &e, &Mpz::from(413)).validate(), Err(RSAError::MissingFactors));
+        assert_eq!(RSA::new_from_components(&n, &e, &Mpz::from(413), &p, &q).validate(), Ok(()));
+        assert_eq!(RSA::new_from_components(&n, &e, &Mpz::from(2753), &p, &q).validate(), Ok(()));
+        assert_eq!(RSA::new_from_components(&n, &e, &Mpz::from(414), &p, &q).validate(), Err(RSAError::PrivateExponentMismatch));
+        assert_eq!(RSA::new_from_components(&Mpz::from(3239), &e, &Mpz::from(413), &p, &q).validate(), Err(RSAError::ModulusMismatch));
+        assert_eq!(RSA::new_from_components(&n, &Mpz::from(3), &Mpz::from(413), &p, &q).validate(), Err(RSAError::ExponentNotInvertible));
+    }
+
+    #[test]
+    fn test_validate_close_factors() {
+        //Consecutive 512 bit primes are easily found from their product with Fermat's method
+        let p = generate_prime(512);
+        let q = p.nextprime();
+        let n = &p * &q;
+        assert_eq!(RSA::new_from_components(&n, &Mpz::from(65537), &Mpz::one(), &p, &q).validate(), Err(RSAError::FactorsTooClose));
+        assert_eq!(RSA::new_from_primes(&p, &q, &Mpz::from(65537)).err(), Some(RSAError::FactorsTooClose));
+    }
+
+    #[test]
+    fn test_signature_schemes() {
+        let r = RSA::builder(1024).public_exponent(&Mpz::from(65537)).build();
+        let m = ascii_to_bytes("This is a test message for RSA signature schemes");
+        let schemes = [
+            SignatureScheme::PKCS15(Hash::SHA1),
+            SignatureScheme::PKCS15(Hash::SHA256),
+            SignatureScheme::PSS(Hash::SHA256, 0),
+            SignatureScheme::PSS(Hash::SHA256, 32),
+            SignatureScheme::PSS(Hash::SHA3_256, 16)
+        ];
+
+        for scheme in schemes.iter() {
+            let mut s = r.sign_message_with(&m, *scheme);
+            assert!(r.verify_signature_with(&s, *scheme));
+            for other in schemes.iter().filter(|other| *other != scheme) {
+                assert!(!r.verify_signature_with(&s, *other));
+            }
+
+            s.message = ascii_to_bytes("This is a different message");
+            assert!(!r.verify_signature_with(&s, *scheme));
+        }
+
+        //The three PKCS#1.5 verifiers accept the same signatures
+        let s = r.sign_message_with(&m, SignatureScheme::PKCS15(Hash::SHA256));
+        assert!(r.verify_signature_with(&s, SignatureScheme::PKCS15DER(Hash::SHA256)));
+        assert!(r.verify_signature_with(&s, SignatureScheme::PKCS15Lenient(Hash::SHA256)));
+        assert!(!r.verify_signature_with(&s, SignatureScheme::PKCS15DER(Hash::SHA1)));
+        assert!(!r.verify_signature_with(&s, SignatureScheme::PKCS15Lenient(Hash::SHA1)));
+    }
+
+    #[test]
+    fn test_verify_signature_out_of_range() {
+        let r = RSA::builder(1024).public_exponent(&Mpz::from(65537)).build();
+        let scheme = SignatureScheme::PKCS15(Hash::SHA256);
+        let mut s = r.sign_message_with(&ascii_to_bytes("Signatures must be reduced mod n"), scheme);
+        s.signature = &s.signature + &r.n;
+        assert!(!r.verify_signature_with(&s, scheme));
+    }
 }
\ No newline at end of file
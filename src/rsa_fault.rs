@@ -0,0 +1,80 @@
+use gmp::mpz::Mpz;
+use crate::hash::Hash;
+use crate::padding::pkcs15_signature_pad;
+use crate::rsa::{RSA, RSASignature};
+
+///Factors n from an RSA signature s of the padded message m which was computed with a fault in one CRT half.
+///Since s^e = m mod one factor but not the other, gcd(s^e - m, n) is that factor.
+///Returns None if the signature is correct (or faulty in both halves), since then the gcd gives no factor.
+pub fn factor_from_faulty_signature(n: &Mpz, e: &Mpz, m: &Mpz, s: &Mpz) -> Option<(Mpz, Mpz)> {
+    let difference = (s.powm(e, n) - m).modulus(n);
+    let p = difference.gcd(n);
+    if p == Mpz::one() || &p == n {
+        return None;
+    }
+
+    let q = n / &p;
+    return Some((p, q));
+}
+
+///Recovers the private key from a single faulty signature made by RSA::sign_message, using only the public key
+pub fn crt_fault_attack(public_key: &RSA, signature: &RSASignature) -> Option<RSA> {
+    let hash = Hash::MD4.digest(&signature.message);
+    let padded = pkcs15_signature_pad(&hash, public_key.n.bit_length(), Hash::MD4);
+    let m = Mpz::from(&padded[0..]);
+
+    let (p, q) = factor_from_faulty_signature(&public_key.n, &public_key.e, &m, &signature.signature)?;
+    return RSA::new_from_primes(&p, &q, &public_key.e).ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::ascii_to_bytes;
+    use crate::rsa::RSAError;
+
+    #[test]
+    fn test_crt_fault_attack() {
+        let mut signer = RSA::builder(1024).public_exponent(&Mpz::from(65537)).build();
+        let message = ascii_to_bytes("Pay Mallory $100");
+        signer.set_fault(Some(17));
+        let signature = signer.sign_message(&message);
+        assert!(!signer.verify_signature(&signature));
+
+        let public_key = RSA::new_from_parameters(&signer.n, &signer.e, &Mpz::zero());
+        let recovered = crt_fault_attack(&public_key, &signature).unwrap();
+        assert_eq!(recovered.n, signer.n);
+        assert_eq!(recovered.validate(), Ok(()));
+
+        //The recovered key signs as the original one does without the fault
+        signer.set_fault(None);
+        let forged = ascii_to_bytes("Pay Mallory $1,000,000");
+        assert_eq!(recovered.sign_message(&forged).signature, signer.sign_message(&forged).signature);
+    }
+
+    #[test]
+    fn test_crt_fault_attack_correct_signature() {
+        let signer = RSA::builder(1024).public_exponent(&Mpz::from(65537)).build();
+        let signature = signer.sign_message(&ascii_to_bytes("Pay Mallory $100"));
+        assert!(crt_fault_attack(&signer, &signature).is_none());
+    }
+
+    #[test]
+    fn test_verify_before_release() {
+        let mut signer = RSA::builder(1024).public_exponent(&Mpz::from(65537)).verify_before_release(true).build();
+        let message = ascii_to_bytes("Pay Mallory $100");
+        assert!(signer.verify_signature(&signer.try_sign_message(&message).unwrap()));
+
+        signer.set_fault(Some(17));
+        assert_eq!(signer.try_sign_message(&message).err(), Some(RSAError::FaultySignature));
+    }
+
+    #[test]
+    #[should_panic(expected="RSA signature failed verification and was not released!")]
+    fn test_verify_before_release_panics() {
+        let mut signer = RSA::builder(1024).build();
+        signer.set_verify_before_release(true);
+        signer.set_fault(Some(0));
+        signer.sign_message(&ascii_to_bytes("Pay Mallory $100"));
+    }
+}
\ No newline at end of file
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use gmp::mpz::Mpz;
 use crate::converter::{ascii_to_bytes, bytes_to_hex, hex_to_bytes};
 use crate::hash::Hash;
-use crate::mac::{MAC, verify_hmac, create_hmac};
+use crate::mac::{MAC, verify_hmac, create_hmac, constant_time_eq};
 
 lazy_static! {
     pub static ref N: Mpz = Mpz::from_str_radix("ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b139b22514a08798e3404ddef9519b3cd3a431b302b0a6df25f14374fe1356d6d51c245e485b576625e7ec6f44c42e9a637ed6b0bff5cb6f406b7edee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3dc2007cb8a163bf0598da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb9ed529077096966d670c354e4abc9804f1746c08ca237327ffffffffffffffff", 16).unwrap();
@@ -106,7 +106,7 @@ impl SRPServer {
         return match self.logins.get(email) {
             None => false,
             Some(i) => {
-                if mac.message != i.salt.to_be_bytes().to_vec() {
+                if !constant_time_eq(&mac.message, &i.salt.to_be_bytes()) {
                     false
                 }
                 else {
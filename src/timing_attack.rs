@@ -0,0 +1,280 @@
+use std::io::{BufRead, BufReader, Error, ErrorKind, Write};
+use std::net::TcpStream;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+///Fraction of samples dropped from each end when taking a trimmed mean
+const TRIM_FRACTION: f64 = 0.2;
+
+///Scales the median absolute deviation to estimate the standard deviation of normally distributed values
+const MAD_SCALE: f64 = 1.4826;
+
+///Settings for the adaptive timing attack
+pub struct TimingAttackSettings {
+    ///Samples taken of every candidate byte in the first round
+    pub initial_samples: usize,
+    ///Samples the leading candidate must have before it is accepted
+    pub confirm_samples: usize,
+    ///Most rounds of sampling the leading candidates before giving up on a position
+    pub max_rounds: usize,
+    ///Number of leading candidates which are sampled again in each later round
+    pub finalists: usize,
+    ///How many standard deviations (of the wrong guesses' times) the best candidate must lead the second by
+    pub confidence: f64,
+    ///Most guesses to undo before giving up on the whole attack
+    pub max_backtracks: usize
+}
+
+impl TimingAttackSettings {
+    ///Default settings, which work for per-byte delays down to around half a millisecond
+    pub fn new() -> TimingAttackSettings {
+        return TimingAttackSettings {
+            initial_samples: 1,
+            confirm_samples: 5,
+            max_rounds: 50,
+            finalists: 8,
+            confidence: 5.0,
+            max_backtracks: 10
+        };
+    }
+}
+
+///Outcome of attacking a single byte position
+enum Position {
+    ///The oracle accepted this whole guess
+    Accepted(Vec<u8>),
+    ///This byte is the most likely value for the position
+    Byte(u8),
+    ///No byte stood out from the rest
+    Unsure
+}
+
+///Mean of the samples after dropping the highest and lowest ones, so that outliers (like scheduler delays) do not dominate
+fn trimmed_mean(samples: &Vec<f64>) -> f64 {
+    let mut sorted = samples.clone();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let trim = (sorted.len() as f64 * TRIM_FRACTION) as usize;
+    let kept = &sorted[trim..sorted.len() - trim];
+    return kept.iter().sum::<f64>() / kept.len() as f64;
+}
+
+///Median of a nonempty list of values
+fn median(values: &Vec<f64>) -> f64 {
+    let mut sorted = values.clone();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let middle = sorted.len() / 2;
+    return if sorted.len() % 2 == 0 {
+        (sorted[middle - 1] + sorted[middle]) / 2.0
+    }
+    else {
+        sorted[middle]
+    };
+}
+
+///Times a single oracle query in nanoseconds, also returning whether the oracle accepted the guess
+fn time_query<F: Fn(&Vec<u8>) -> bool>(oracle: &F, guess: &Vec<u8>) -> (bool, f64) {
+    let start = Instant::now();
+    let accepted = oracle(guess);
+    return (accepted, start.elapsed().as_nanos() as f64);
+}
+
+///Finds the next byte after the known ones as the candidate whose queries take longest.
+///Leading candidates are sampled again until one of them stands out, or the maximum number of rounds is reached.
+fn recover_byte<F: Fn(&Vec<u8>) -> bool>(oracle: &F, known: &Vec<u8>, length: usize, excluded: &Vec<u8>, settings: &TimingAttackSettings) -> Position {
+    let candidates: Vec<u8> = (0..=255).filter(|byte| !excluded.contains(byte)).collect();
+    let guess_for = |byte: u8| {
+        let mut guess = known.clone();
+        guess.push(byte);
+        guess.resize(length, 0);
+        guess
+    };
+
+    //The last byte does not need timing, since the oracle accepts the right value
+    if known.len() == length - 1 {
+        return match candidates.iter().map(|byte| guess_for(*byte)).find(|guess| oracle(guess)) {
+            None => Position::Unsure,
+            Some(guess) => Position::Accepted(guess)
+        };
+    }
+
+    let mut samples: Vec<Vec<f64>> = vec![vec![]; 256];
+    let mut to_sample = candidates.clone();
+    let mut rounds = settings.initial_samples;
+    for _attempt in 0..settings.max_rounds {
+        //Candidates are sampled in turn, so that drift in the timings affects them all alike
+        for _round in 0..rounds {
+            for byte in to_sample.iter() {
+                let guess = guess_for(*byte);
+                let (accepted, time) = time_query(oracle, &guess);
+                if accepted {
+                    return Position::Accepted(guess);
+                }
+                samples[*byte as usize].push(time);
+            }
+        }
+
+        let mut ranked: Vec<(f64, u8)> = candidates.iter().map(|byte| (trimmed_mean(&samples[*byte as usize]), *byte)).collect();
+        ranked.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+        if ranked.len() < 2 {
+            return match ranked.first() {
+                None => Position::Unsure,
+                Some((_, byte)) => Position::Byte(*byte)
+            };
+        }
+
+        //Wrong guesses all take about as long as each other, so their spread shows how big a lead is significant
+        let rest: Vec<f64> = ranked[1..].iter().map(|(time, _)| *time).collect();
+        let center = median(&rest);
+        let spread = MAD_SCALE * median(&rest.iter().map(|time| (time - center).abs()).collect());
+        let (best_time, best_byte) = ranked[0];
+        if samples[best_byte as usize].len() >= settings.confirm_samples && best_time - ranked[1].0 > settings.confidence * spread {
+            return Position::Byte(best_byte);
+        }
+
+        to_sample = ranked.iter().take(settings.finalists).map(|(_, byte)| *byte).collect();
+        rounds = 1;
+    }
+
+    return Position::Unsure;
+}
+
+///Recovers the value an oracle compares guesses against, when the comparison takes longer the more leading bytes match.
+///The oracle returns true if it accepts the guess. Bytes are found from first to last, and if no candidate stands out
+///at some position, the previous byte is assumed to be wrong and is ruled out before trying it again.
+///Returns None if the attack has to backtrack too many times, or if the length is zero and the oracle rejects an empty guess.
+pub fn timing_attack<F: Fn(&Vec<u8>) -> bool>(oracle: F, length: usize, settings: &TimingAttackSettings) -> Option<Vec<u8>> {
+    //There are no bytes to time, so the empty guess is the only candidate
+    if length == 0 {
+        return if oracle(&vec![]) {Some(vec![])} else {None};
+    }
+
+    let mut known: Vec<u8> = vec![];
+    let mut excluded: Vec<Vec<u8>> = vec![vec![]; length];
+    let mut backtracks = 0;
+
+    loop {
+        match recover_byte(&oracle, &known, length, &excluded[known.len()], settings) {
+            Position::Accepted(value) => return Some(value),
+            Position::Byte(byte) => known.push(byte),
+            Position::Unsure => {
+                backtracks += 1;
+                if backtracks > settings.max_backtracks {
+                    return None;
+                }
+
+                //Bytes ruled out here were only wrong after the previous byte, which is now being replaced
+                excluded[known.len()].clear();
+                if let Some(byte) = known.pop() {
+                    excluded[known.len()].push(byte);
+                }
+            }
+        }
+    }
+}
+
+///Performs byte-by-byte comparison of a and b, sleeping for the delay after each matching byte.
+///This is the leaky comparison the timing attack recovers values from.
+pub fn insecure_compare(a: &Vec<u8>, b: &Vec<u8>, delay: Duration) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    for i in 0..a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        sleep(delay);
+    }
+    return true;
+}
+
+///Sends a GET request to an HTTP server on localhost, and returns the status code as soon as it arrives
+pub fn http_get_status(port: u16, path: &str) -> std::io::Result<u16> {
+    let mut stream = TcpStream::connect(("127.0.0.1", port))?;
+    write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", path)?;
+
+    let mut status_line = String::new();
+    BufReader::new(stream).read_line(&mut status_line)?;
+    return match status_line.split_whitespace().nth(1).and_then(|code| code.parse().ok()) {
+        None => Err(Error::new(ErrorKind::InvalidData, "Malformed HTTP status line.")),
+        Some(code) => Ok(code)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::net::TcpListener;
+    use std::thread::spawn;
+
+    #[test]
+    fn test_insecure_compare() {
+        assert!(insecure_compare(&vec![1, 2, 3], &vec![1, 2, 3], Duration::from_micros(500)));
+        assert!(!insecure_compare(&vec![1, 2, 3], &vec![1, 2, 4], Duration::from_micros(500)));
+        assert!(!insecure_compare(&vec![1, 2, 3], &vec![1, 2], Duration::from_micros(500)));
+    }
+
+    #[test]
+    fn test_statistics() {
+        assert_eq!(median(&vec![3.0, 1.0, 2.0]), 2.0);
+        assert_eq!(median(&vec![4.0, 1.0, 3.0, 2.0]), 2.5);
+        assert_eq!(trimmed_mean(&vec![1.0, 2.0, 3.0, 4.0, 1000.0]), 3.0);
+        assert_eq!(trimmed_mean(&vec![7.0]), 7.0);
+    }
+
+    #[test]
+    fn test_timing_attack_5ms() {
+        let secret = vec![0x9c, 0x01, 0xfe];
+        let oracle = |guess: &Vec<u8>| insecure_compare(guess, &secret, Duration::from_millis(5));
+        assert_eq!(timing_attack(oracle, secret.len(), &TimingAttackSettings::new()), Some(secret.clone()));
+    }
+
+    #[test]
+    fn test_timing_attack_half_ms() {
+        let secret = vec![0x42, 0x00, 0xa7, 0x13];
+        let oracle = |guess: &Vec<u8>| insecure_compare(guess, &secret, Duration::from_micros(500));
+        assert_eq!(timing_attack(oracle, secret.len(), &TimingAttackSettings::new()), Some(secret.clone()));
+    }
+
+    #[test]
+    fn test_timing_attack_backtracks() {
+        //A decoy first byte is even slower than the right one, but then nothing after it stands out
+        let secret = vec![0x10, 0x20, 0x30];
+        let oracle = |guess: &Vec<u8>| {
+            if guess[0] == 0xd0 {
+                sleep(Duration::from_millis(4));
+                return false;
+            }
+            insecure_compare(guess, &secret, Duration::from_millis(2))
+        };
+        assert_eq!(timing_attack(oracle, secret.len(), &TimingAttackSettings::new()), Some(secret.clone()));
+    }
+
+    #[test]
+    fn test_timing_attack_no_leak() {
+        //Without a timing leak, the attack gives up instead of guessing forever
+        let settings = TimingAttackSettings {max_rounds: 3, max_backtracks: 2, ..TimingAttackSettings::new()};
+        assert_eq!(timing_attack(|guess: &Vec<u8>| guess == &vec![1, 2, 3], 3, &settings), None);
+    }
+
+    #[test]
+    fn test_timing_attack_zero_length() {
+        assert_eq!(timing_attack(|guess: &Vec<u8>| guess.is_empty(), 0, &TimingAttackSettings::new()), Some(vec![]));
+        assert_eq!(timing_attack(|_guess: &Vec<u8>| false, 0, &TimingAttackSettings::new()), None);
+    }
+
+    #[test]
+    fn test_http_get_status() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let length = stream.read(&mut request).unwrap();
+            assert!(request[..length].starts_with(b"GET /check/abc HTTP/1.1\r\n"));
+            stream.write_all(b"HTTP/1.1 202 Accepted\r\nContent-Length: 0\r\n\r\n").unwrap();
+        });
+        assert_eq!(http_get_status(port, "/check/abc").unwrap(), 202);
+    }
+}
\ No newline at end of file